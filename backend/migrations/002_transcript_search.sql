@@ -0,0 +1,27 @@
+-- Full-text search over transcript segment text
+ALTER TABLE transcripts
+    ADD COLUMN IF NOT EXISTS search_config REGCONFIG NOT NULL DEFAULT 'english',
+    ADD COLUMN IF NOT EXISTS search_vector TSVECTOR;
+
+CREATE OR REPLACE FUNCTION transcripts_search_vector_update() RETURNS trigger AS $$
+BEGIN
+    NEW.search_vector := to_tsvector(
+        NEW.search_config,
+        coalesce(
+            (SELECT string_agg(seg ->> 'text', ' ') FROM jsonb_array_elements(NEW.segments) AS seg),
+            ''
+        )
+    );
+    RETURN NEW;
+END
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_transcripts_search_vector ON transcripts;
+CREATE TRIGGER trg_transcripts_search_vector
+    BEFORE INSERT OR UPDATE OF segments, search_config ON transcripts
+    FOR EACH ROW EXECUTE FUNCTION transcripts_search_vector_update();
+
+-- Backfill existing rows through the trigger.
+UPDATE transcripts SET segments = segments;
+
+CREATE INDEX IF NOT EXISTS idx_transcripts_search_vector ON transcripts USING GIN (search_vector);
@@ -0,0 +1,18 @@
+-- User edits to transcripts with full revision history
+ALTER TABLE transcripts
+    ADD COLUMN IF NOT EXISTS revision INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS original_segments JSONB;
+
+-- The machine transcript is kept as revision 0 and never edited.
+UPDATE transcripts SET original_segments = segments WHERE original_segments IS NULL;
+
+CREATE TABLE IF NOT EXISTS transcript_revisions (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    transcript_id UUID NOT NULL REFERENCES transcripts (id) ON DELETE CASCADE,
+    revision INTEGER NOT NULL,
+    segments JSONB NOT NULL,
+    author_id UUID NOT NULL,
+    restored_from INTEGER,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    UNIQUE (transcript_id, revision)
+);
@@ -0,0 +1,22 @@
+-- Roles granted to users (identified by the id the auth layer supplies)
+CREATE TABLE IF NOT EXISTS user_roles (
+    user_id UUID NOT NULL,
+    role TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (user_id, role)
+);
+
+-- Archived topics are hidden from listing but kept for historical sessions
+ALTER TABLE topics ADD COLUMN IF NOT EXISTS archived_at TIMESTAMPTZ;
+
+CREATE OR REPLACE FUNCTION set_updated_at() RETURNS trigger AS $$
+BEGIN
+    NEW.updated_at := now();
+    RETURN NEW;
+END
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_topics_updated_at ON topics;
+CREATE TRIGGER trg_topics_updated_at
+    BEFORE UPDATE ON topics
+    FOR EACH ROW EXECUTE FUNCTION set_updated_at();
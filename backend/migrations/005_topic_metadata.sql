@@ -0,0 +1,9 @@
+-- Topic grouping and discovery metadata
+ALTER TABLE topics
+    ADD COLUMN IF NOT EXISTS category TEXT,
+    ADD COLUMN IF NOT EXISTS tags TEXT[] NOT NULL DEFAULT '{}',
+    ADD COLUMN IF NOT EXISTS target_skill TEXT,
+    ADD COLUMN IF NOT EXISTS estimated_duration_seconds INTEGER;
+
+CREATE INDEX IF NOT EXISTS idx_topics_category ON topics (category);
+CREATE INDEX IF NOT EXISTS idx_topics_tags ON topics USING GIN (tags);
@@ -0,0 +1,44 @@
+-- Per-locale topic text; the base topic columns hold the default (English) text
+CREATE TABLE IF NOT EXISTS topic_translations (
+    topic_id UUID NOT NULL REFERENCES topics (id) ON DELETE CASCADE,
+    locale TEXT NOT NULL,
+    title TEXT NOT NULL,
+    prompt_hint TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (topic_id, locale)
+);
+
+DROP TRIGGER IF EXISTS trg_topic_translations_updated_at ON topic_translations;
+CREATE TRIGGER trg_topic_translations_updated_at
+    BEFORE UPDATE ON topic_translations
+    FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+-- Practice language chosen at session creation
+ALTER TABLE sessions ADD COLUMN IF NOT EXISTS language TEXT NOT NULL DEFAULT 'en';
+
+-- New transcripts pick their text search configuration from the session language.
+CREATE OR REPLACE FUNCTION transcripts_search_vector_update() RETURNS trigger AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        NEW.search_config := (
+            SELECT CASE s.language
+                WHEN 'es' THEN 'spanish'::regconfig
+                WHEN 'en' THEN 'english'::regconfig
+                ELSE 'simple'::regconfig
+            END
+            FROM sessions s
+            WHERE s.id = NEW.session_id
+        );
+        NEW.search_config := coalesce(NEW.search_config, 'english'::regconfig);
+    END IF;
+    NEW.search_vector := to_tsvector(
+        NEW.search_config,
+        coalesce(
+            (SELECT string_agg(seg ->> 'text', ' ') FROM jsonb_array_elements(NEW.segments) AS seg),
+            ''
+        )
+    );
+    RETURN NEW;
+END
+$$ LANGUAGE plpgsql;
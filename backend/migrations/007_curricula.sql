@@ -0,0 +1,44 @@
+-- Curricula are ordered units of topics that unlock one after another
+CREATE TABLE IF NOT EXISTS curricula (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    title TEXT NOT NULL UNIQUE,
+    description TEXT,
+    archived_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+DROP TRIGGER IF EXISTS trg_curricula_updated_at ON curricula;
+CREATE TRIGGER trg_curricula_updated_at
+    BEFORE UPDATE ON curricula
+    FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+-- Sessions carry no score yet, so a unit's bar is a number of ended sessions, optionally
+-- each lasting at least min_duration_seconds.
+CREATE TABLE IF NOT EXISTS curriculum_units (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    curriculum_id UUID NOT NULL REFERENCES curricula (id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    title TEXT NOT NULL,
+    required_sessions INTEGER NOT NULL DEFAULT 1 CHECK (required_sessions > 0),
+    min_duration_seconds INTEGER CHECK (min_duration_seconds > 0),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    UNIQUE (curriculum_id, position)
+);
+
+CREATE TABLE IF NOT EXISTS curriculum_unit_topics (
+    unit_id UUID NOT NULL REFERENCES curriculum_units (id) ON DELETE CASCADE,
+    topic_id UUID NOT NULL REFERENCES topics (id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    PRIMARY KEY (unit_id, topic_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_curriculum_unit_topics_topic_id ON curriculum_unit_topics (topic_id);
+
+CREATE TABLE IF NOT EXISTS curriculum_enrollments (
+    user_id UUID NOT NULL,
+    curriculum_id UUID NOT NULL REFERENCES curricula (id) ON DELETE CASCADE,
+    enrolled_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    completed_at TIMESTAMPTZ,
+    PRIMARY KEY (user_id, curriculum_id)
+);
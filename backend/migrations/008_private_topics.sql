@@ -0,0 +1,7 @@
+-- Learner-owned topics; NULL owner means a global, admin-managed topic
+ALTER TABLE topics ADD COLUMN IF NOT EXISTS owner_id UUID;
+
+-- Titles stay unique among global topics and within each owner's private topics
+ALTER TABLE topics DROP CONSTRAINT IF EXISTS topics_title_key;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_topics_global_title ON topics (title) WHERE owner_id IS NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_topics_owner_title ON topics (owner_id, title) WHERE owner_id IS NOT NULL;
@@ -0,0 +1,7 @@
+-- Drill mode chosen at session creation, with its timing parameters
+ALTER TABLE sessions ADD COLUMN IF NOT EXISTS mode TEXT NOT NULL DEFAULT 'free';
+ALTER TABLE sessions ADD COLUMN IF NOT EXISTS mode_params JSONB NOT NULL DEFAULT '{}'::jsonb;
+
+ALTER TABLE sessions DROP CONSTRAINT IF EXISTS sessions_mode_check;
+ALTER TABLE sessions ADD CONSTRAINT sessions_mode_check
+    CHECK (mode IN ('free', 'impromptu', 'timed_speech', 'qa_round'));
@@ -0,0 +1,25 @@
+-- Durable queue of storage objects to remove after their rows are deleted
+CREATE TABLE IF NOT EXISTS storage_deletions (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id UUID NOT NULL,
+    object_key TEXT NOT NULL,
+    attempts INT NOT NULL DEFAULT 0,
+    last_error TEXT,
+    next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    completed_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_storage_deletions_pending
+    ON storage_deletions (next_attempt_at)
+    WHERE completed_at IS NULL;
+CREATE INDEX IF NOT EXISTS idx_storage_deletions_user_id ON storage_deletions (user_id);
+
+-- One row per account deletion; completed once every queued object is gone
+CREATE TABLE IF NOT EXISTS account_deletions (
+    user_id UUID PRIMARY KEY,
+    sessions_deleted INT NOT NULL DEFAULT 0,
+    objects_queued INT NOT NULL DEFAULT 0,
+    requested_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    completed_at TIMESTAMPTZ
+);
@@ -0,0 +1,5 @@
+-- Deleted sessions are tombstoned first and purged once the restore window passes
+ALTER TABLE sessions ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_sessions_deleted_at ON sessions (deleted_at)
+    WHERE deleted_at IS NOT NULL;
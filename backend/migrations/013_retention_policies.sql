@@ -0,0 +1,10 @@
+-- Per-user retention overrides (days); NULL falls back to the deployment default
+CREATE TABLE IF NOT EXISTS retention_policies (
+    user_id UUID PRIMARY KEY,
+    audio_days INT CHECK (audio_days > 0),
+    transcript_days INT CHECK (transcript_days > 0),
+    metadata_days INT CHECK (metadata_days > 0),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_sessions_start_time ON sessions (start_time);
@@ -0,0 +1,18 @@
+-- Revocable, expiring read-only links to a single session
+CREATE TABLE IF NOT EXISTS session_shares (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    session_id UUID NOT NULL REFERENCES sessions (id) ON DELETE CASCADE,
+    token TEXT NOT NULL UNIQUE,
+    scope TEXT NOT NULL CHECK (scope IN ('transcript', 'transcript_audio')),
+    expires_at TIMESTAMPTZ NOT NULL,
+    revoked_at TIMESTAMPTZ,
+    view_count INT NOT NULL DEFAULT 0,
+    last_viewed_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_session_shares_session_id ON session_shares (session_id);
+
+-- 'shared' while the session has at least one active link
+ALTER TABLE sessions DROP CONSTRAINT IF EXISTS sessions_privacy_check;
+ALTER TABLE sessions ADD CONSTRAINT sessions_privacy_check CHECK (privacy IN ('private', 'shared'));
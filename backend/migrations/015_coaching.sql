@@ -0,0 +1,50 @@
+-- Coach/student relationships: the coach invites, the student accepts or declines
+CREATE TABLE IF NOT EXISTS coach_links (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    coach_id UUID NOT NULL,
+    student_id UUID NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'accepted', 'declined', 'revoked')),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    responded_at TIMESTAMPTZ,
+    UNIQUE (coach_id, student_id),
+    CHECK (coach_id <> student_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_coach_links_student_id ON coach_links (student_id);
+
+-- Sessions a student has explicitly opened to one of their coaches
+CREATE TABLE IF NOT EXISTS coach_session_grants (
+    session_id UUID NOT NULL REFERENCES sessions (id) ON DELETE CASCADE,
+    coach_id UUID NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (session_id, coach_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_coach_session_grants_coach_id ON coach_session_grants (coach_id);
+
+-- Review comments anchored to a transcript segment and/or an audio time range;
+-- replies hang off a top-level comment and carry no anchor of their own
+CREATE TABLE IF NOT EXISTS review_comments (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    session_id UUID NOT NULL REFERENCES sessions (id) ON DELETE CASCADE,
+    author_id UUID NOT NULL,
+    parent_id UUID REFERENCES review_comments (id) ON DELETE CASCADE,
+    body TEXT NOT NULL,
+    segment_index INT,
+    start_ms BIGINT,
+    end_ms BIGINT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_review_comments_session_id ON review_comments (session_id, created_at);
+
+CREATE TABLE IF NOT EXISTS notifications (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id UUID NOT NULL,
+    kind TEXT NOT NULL,
+    payload JSONB NOT NULL DEFAULT '{}'::jsonb,
+    read_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_notifications_user_id ON notifications (user_id, created_at DESC);
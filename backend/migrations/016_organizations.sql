@@ -0,0 +1,42 @@
+-- Organizations are tenants; a user belongs to at most one of them
+CREATE TABLE IF NOT EXISTS organizations (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    name TEXT NOT NULL UNIQUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE TABLE IF NOT EXISTS organization_members (
+    org_id UUID NOT NULL REFERENCES organizations (id) ON DELETE CASCADE,
+    user_id UUID NOT NULL UNIQUE,
+    role TEXT NOT NULL DEFAULT 'member' CHECK (role IN ('owner', 'admin', 'member')),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (org_id, user_id)
+);
+
+-- Cohorts (classes) group members inside one organization
+CREATE TABLE IF NOT EXISTS cohorts (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    org_id UUID NOT NULL REFERENCES organizations (id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    UNIQUE (org_id, name)
+);
+
+CREATE TABLE IF NOT EXISTS cohort_members (
+    cohort_id UUID NOT NULL REFERENCES cohorts (id) ON DELETE CASCADE,
+    user_id UUID NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (cohort_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_cohort_members_user_id ON cohort_members (user_id);
+
+-- Sessions remember the tenant they were recorded under; NULL means personal
+ALTER TABLE sessions ADD COLUMN IF NOT EXISTS org_id UUID REFERENCES organizations (id) ON DELETE SET NULL;
+CREATE INDEX IF NOT EXISTS idx_sessions_org_id ON sessions (org_id) WHERE org_id IS NOT NULL;
+
+-- Org catalog topics have an org and no owner; global topics have neither
+ALTER TABLE topics ADD COLUMN IF NOT EXISTS org_id UUID REFERENCES organizations (id) ON DELETE CASCADE;
+DROP INDEX IF EXISTS idx_topics_global_title;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_topics_global_title ON topics (title) WHERE owner_id IS NULL AND org_id IS NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_topics_org_title ON topics (org_id, title) WHERE org_id IS NOT NULL;
@@ -0,0 +1,5 @@
+-- PII-redacted copy of the current segments, served to everyone but the owner;
+-- NULL until the transcript is next written (readers redact on the fly meanwhile)
+ALTER TABLE transcripts
+    ADD COLUMN IF NOT EXISTS redacted_segments JSONB,
+    ADD COLUMN IF NOT EXISTS redacted_at TIMESTAMPTZ;
@@ -0,0 +1,41 @@
+-- Per-user data keys for audio and transcript encryption, wrapped by a master key
+-- from configuration. master_key_id names the key that wrapped each row so rotation
+-- can find the ones still to re-wrap.
+CREATE TABLE IF NOT EXISTS user_data_keys (
+    user_id UUID PRIMARY KEY,
+    master_key_id TEXT NOT NULL,
+    wrapped_key BYTEA NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    rotated_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_user_data_keys_master ON user_data_keys (master_key_id);
+
+-- Encrypted transcripts store an object instead of a segment array; they get an
+-- empty search vector rather than failing the trigger.
+CREATE OR REPLACE FUNCTION transcripts_search_vector_update() RETURNS trigger AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        NEW.search_config := (
+            SELECT CASE s.language
+                WHEN 'es' THEN 'spanish'::regconfig
+                WHEN 'en' THEN 'english'::regconfig
+                ELSE 'simple'::regconfig
+            END
+            FROM sessions s
+            WHERE s.id = NEW.session_id
+        );
+        NEW.search_config := coalesce(NEW.search_config, 'english'::regconfig);
+    END IF;
+    NEW.search_vector := to_tsvector(
+        NEW.search_config,
+        CASE WHEN jsonb_typeof(NEW.segments) = 'array' THEN
+            coalesce(
+                (SELECT string_agg(seg ->> 'text', ' ') FROM jsonb_array_elements(NEW.segments) AS seg),
+                ''
+            )
+        ELSE '' END
+    );
+    RETURN NEW;
+END
+$$ LANGUAGE plpgsql;
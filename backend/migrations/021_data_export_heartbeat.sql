@@ -0,0 +1,4 @@
+-- Last sign of life from the worker building an export; the lease is measured from it
+-- (or from created_at while the export is still pending)
+ALTER TABLE data_exports
+    ADD COLUMN IF NOT EXISTS heartbeat_at TIMESTAMPTZ;
@@ -0,0 +1,5 @@
+-- Sessions that fail to purge back off instead of blocking every session queued behind them
+ALTER TABLE sessions
+    ADD COLUMN IF NOT EXISTS purge_attempts INT NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS purge_error TEXT,
+    ADD COLUMN IF NOT EXISTS purge_retry_at TIMESTAMPTZ;
@@ -0,0 +1,5 @@
+-- Recordings that retention fails to purge back off like sessions do
+ALTER TABLE audio_recordings
+    ADD COLUMN IF NOT EXISTS purge_attempts INT NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS purge_error TEXT,
+    ADD COLUMN IF NOT EXISTS purge_retry_at TIMESTAMPTZ;
@@ -0,0 +1,22 @@
+use axum::extract::{Json, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use tracing::error;
+
+use crate::auth::CurrentUser;
+use crate::services::deletion;
+use crate::state::SharedState;
+
+// Rows are removed immediately; stored objects are purged by the background worker.
+pub async fn delete_account(
+    State(state): State<SharedState>,
+    CurrentUser(user_id): CurrentUser,
+) -> Result<impl IntoResponse, StatusCode> {
+    match deletion::delete_account(&state.db, &state.storage, user_id).await {
+        Ok(record) => Ok((StatusCode::ACCEPTED, Json(record))),
+        Err(err) => {
+            error!("account deletion failed: {:?}", err);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
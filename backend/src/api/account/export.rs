@@ -0,0 +1,144 @@
+use axum::extract::{Json, Path, State};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Redirect};
+use chrono::Utc;
+use serde::Serialize;
+use serde_json::{json, Value};
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::auth::{ClientInfo, CurrentUser};
+use crate::models::data_export::DataExport;
+use crate::services::audit::{self, Target};
+use crate::services::data_export;
+use crate::state::SharedState;
+
+#[derive(Serialize)]
+pub struct ExportStatusResponse {
+    #[serde(flatten)]
+    pub export: DataExport,
+    pub expired: bool,
+    // The download route, only while the archive is ready and unexpired.
+    pub download_url: Option<String>,
+}
+
+pub async fn request_export(
+    State(state): State<SharedState>,
+    CurrentUser(user_id): CurrentUser,
+    client: ClientInfo,
+) -> Result<impl IntoResponse, StatusCode> {
+    let (export, created) = match data_export::request_export(&state.db, user_id).await {
+        Ok(result) => result,
+        Err(err) => {
+            error!("failed to queue export: {:?}", err);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+    audit::record(
+        &state.db,
+        &client,
+        Some(user_id),
+        audit::EXPORT_REQUESTED,
+        Target::Export(export.id),
+        json!({ "created": created }),
+    )
+    .await;
+    if created {
+        info!("queued data export {} for user {}", export.id, user_id);
+        data_export::spawn_export(state.db.clone(), state.storage.clone(), export.clone());
+    }
+    Ok((StatusCode::ACCEPTED, Json(export)))
+}
+
+pub async fn export_status(
+    State(state): State<SharedState>,
+    CurrentUser(user_id): CurrentUser,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let export = match DataExport::get_for_user(&state.db, id, user_id).await {
+        Ok(Some(export)) => export,
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(err) => {
+            error!("failed to load export: {:?}", err);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    let expired = data_export::is_expired(&export, Utc::now());
+    // Polling is not a download; only download_export records one.
+    let download_url = match (expired, export.storage_key.as_deref()) {
+        (false, Some(_)) => Some(format!("/api/me/export/{}/download", export.id)),
+        _ => None,
+    };
+
+    Ok(Json(ExportStatusResponse {
+        export,
+        expired,
+        download_url,
+    }))
+}
+
+pub async fn download_export(
+    State(state): State<SharedState>,
+    CurrentUser(user_id): CurrentUser,
+    client: ClientInfo,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let export = match DataExport::get_for_user(&state.db, id, user_id).await {
+        Ok(Some(export)) => export,
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(err) => {
+            error!("failed to load export: {:?}", err);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+    if data_export::is_expired(&export, Utc::now()) {
+        return Err(StatusCode::GONE);
+    }
+    let (Some(key), Some(expires_at)) = (export.storage_key.as_deref(), export.expires_at) else {
+        return Err(StatusCode::NOT_FOUND);
+    };
+    let record_download = || {
+        audit::record(
+            &state.db,
+            &client,
+            Some(user_id),
+            audit::EXPORT_DOWNLOADED,
+            Target::Export(export.id),
+            Value::Null,
+        )
+    };
+
+    if !state.storage.encrypts() {
+        // Plain archives are streamed straight from the bucket.
+        let ttl = (expires_at - Utc::now()).to_std().unwrap_or_default();
+        return match state.storage.presigned_get_url(key, ttl).await {
+            Ok(url) => {
+                record_download().await;
+                Ok(Redirect::temporary(&url).into_response())
+            }
+            Err(err) => {
+                error!("failed to presign export {}: {:?}", export.id, err);
+                Err(StatusCode::INTERNAL_SERVER_ERROR)
+            }
+        };
+    }
+    // The bucket holds ciphertext, so sealed archives are decrypted here.
+    let bytes = match state.storage.get_bytes(key, user_id).await {
+        Ok(bytes) => bytes,
+        Err(err) => {
+            error!("failed to fetch export {}: {:?}", export.id, err);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+    record_download().await;
+    let disposition = format!("attachment; filename=\"export-{}.zip\"", export.id);
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/zip".to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        bytes,
+    )
+        .into_response())
+}
@@ -0,0 +1,22 @@
+use axum::routing::{delete, get, post};
+use axum::Router;
+
+use crate::state::SharedState;
+
+use self::delete::delete_account;
+use self::export::{download_export, export_status, request_export};
+use self::notifications::{list_notifications, mark_notification_read};
+
+mod delete;
+mod export;
+mod notifications;
+
+pub fn account_router() -> Router<SharedState> {
+    Router::new()
+        .route("/me", delete(delete_account))
+        .route("/me/export", post(request_export))
+        .route("/me/export/:id", get(export_status))
+        .route("/me/export/:id/download", get(download_export))
+        .route("/me/notifications", get(list_notifications))
+        .route("/me/notifications/:id/read", post(mark_notification_read))
+}
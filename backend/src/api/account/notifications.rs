@@ -0,0 +1,49 @@
+use axum::extract::{Json, Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use serde::Deserialize;
+use tracing::error;
+use uuid::Uuid;
+
+use crate::auth::CurrentUser;
+use crate::models::notification::Notification;
+use crate::state::SharedState;
+
+const NOTIFICATION_PAGE_SIZE: i64 = 50;
+
+#[derive(Deserialize)]
+pub struct NotificationParams {
+    #[serde(default)]
+    pub unread: bool,
+}
+
+pub async fn list_notifications(
+    State(state): State<SharedState>,
+    CurrentUser(user_id): CurrentUser,
+    Query(params): Query<NotificationParams>,
+) -> Result<impl IntoResponse, StatusCode> {
+    match Notification::list_for_user(&state.db, user_id, params.unread, NOTIFICATION_PAGE_SIZE)
+        .await
+    {
+        Ok(notifications) => Ok(Json(notifications)),
+        Err(err) => {
+            error!("failed to list notifications: {:?}", err);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn mark_notification_read(
+    State(state): State<SharedState>,
+    CurrentUser(user_id): CurrentUser,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, StatusCode> {
+    match Notification::mark_read(&state.db, id, user_id).await {
+        Ok(true) => Ok(StatusCode::NO_CONTENT),
+        Ok(false) => Err(StatusCode::NOT_FOUND),
+        Err(err) => {
+            error!("failed to mark notification read: {:?}", err);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use axum::body::Body;
+use axum::extract::{ConnectInfo, Json, OriginalUri, Query, State};
+use axum::http::{Request, StatusCode};
+use axum::middleware::Next;
+use axum::response::IntoResponse;
+use axum::routing::get;
+use axum::Router;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::net::SocketAddr;
+use tracing::error;
+use uuid::Uuid;
+
+use crate::auth::{trusted_proxies, AdminUser, ClientInfo};
+use crate::models::audit_event::{AuditEvent, AuditFilter};
+use crate::services::audit::{self, Target};
+use crate::state::SharedState;
+
+pub const DEFAULT_AUDIT_PAGE_SIZE: i64 = 50;
+pub const MAX_AUDIT_PAGE_SIZE: i64 = 500;
+
+pub fn audit_router() -> Router<SharedState> {
+    Router::new().route("/audit/events", get(list_audit_events))
+}
+
+#[derive(Deserialize)]
+pub struct AuditParams {
+    pub actor_id: Option<Uuid>,
+    pub action: Option<String>,
+    pub target_type: Option<String>,
+    pub target_id: Option<Uuid>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Serialize)]
+pub struct AuditPage {
+    pub events: Vec<AuditEvent>,
+    pub total: i64,
+}
+
+pub async fn list_audit_events(
+    State(state): State<SharedState>,
+    AdminUser(_admin_id): AdminUser,
+    Query(params): Query<AuditParams>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let filter = AuditFilter {
+        actor_id: params.actor_id,
+        action: params.action.filter(|a| !a.trim().is_empty()),
+        target_type: params.target_type.filter(|t| !t.trim().is_empty()),
+        target_id: params.target_id,
+        from: params.from,
+        to: params.to,
+    };
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_AUDIT_PAGE_SIZE)
+        .clamp(1, MAX_AUDIT_PAGE_SIZE);
+    let offset = params.offset.unwrap_or_default().max(0);
+    match AuditEvent::search(&state.db, &filter, limit, offset).await {
+        Ok((events, total)) => Ok(Json(AuditPage { events, total })),
+        Err(err) => {
+            error!("failed to query audit log: {:?}", err);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+// Records every 401/403 the API hands out, whichever extractor or handler produced it.
+pub async fn record_auth_failures(
+    State(state): State<SharedState>,
+    req: Request<Body>,
+    next: Next,
+) -> axum::response::Response {
+    let peer = req
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| *addr);
+    let client = ClientInfo::from_parts(req.headers(), peer, trusted_proxies());
+    let actor_id = req
+        .headers()
+        .get("x-user-id")
+        .and_then(|h| h.to_str().ok())
+        .and_then(|raw| Uuid::parse_str(raw).ok());
+    let method = req.method().to_string();
+    let path = req
+        .extensions()
+        .get::<OriginalUri>()
+        .map(|OriginalUri(uri)| uri.path().to_string())
+        .unwrap_or_else(|| req.uri().path().to_string());
+
+    let response = next.run(req).await;
+    let status = response.status();
+    if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
+        audit::record(
+            &state.db,
+            &client,
+            actor_id,
+            audit::AUTH_FAILED,
+            Target::None,
+            json!({ "method": method, "path": path, "status": status.as_u16() }),
+        )
+        .await;
+    }
+    response
+}
@@ -0,0 +1,32 @@
+use axum::extract::{Json, Path, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use uuid::Uuid;
+
+use super::coaching_error_response;
+use crate::auth::CurrentUser;
+use crate::services::coaching::{self, CommentInput};
+use crate::state::SharedState;
+
+pub async fn list_comments(
+    State(state): State<SharedState>,
+    CurrentUser(user_id): CurrentUser,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    coaching::list_comments(&state.db, id, user_id)
+        .await
+        .map(Json)
+        .map_err(coaching_error_response)
+}
+
+pub async fn add_comment(
+    State(state): State<SharedState>,
+    CurrentUser(user_id): CurrentUser,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<CommentInput>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let comment = coaching::add_comment(&state.db, &state.encryption, id, user_id, payload)
+        .await
+        .map_err(coaching_error_response)?;
+    Ok((StatusCode::CREATED, Json(comment)))
+}
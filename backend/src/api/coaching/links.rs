@@ -0,0 +1,80 @@
+use axum::extract::{Json, Path, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use super::coaching_error_response;
+use crate::auth::CurrentUser;
+use crate::models::coaching::CoachLink;
+use crate::services::coaching::{self, CoachingError};
+use crate::state::SharedState;
+
+#[derive(Deserialize)]
+pub struct InviteRequest {
+    pub student_id: Uuid,
+}
+
+pub async fn invite_student(
+    State(state): State<SharedState>,
+    CurrentUser(coach_id): CurrentUser,
+    Json(payload): Json<InviteRequest>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let link = coaching::invite_student(&state.db, coach_id, payload.student_id)
+        .await
+        .map_err(coaching_error_response)?;
+    Ok((StatusCode::CREATED, Json(link)))
+}
+
+pub async fn accept_invitation(
+    State(state): State<SharedState>,
+    CurrentUser(student_id): CurrentUser,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    coaching::respond_to_invitation(&state.db, id, student_id, true)
+        .await
+        .map(Json)
+        .map_err(coaching_error_response)
+}
+
+pub async fn decline_invitation(
+    State(state): State<SharedState>,
+    CurrentUser(student_id): CurrentUser,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    coaching::respond_to_invitation(&state.db, id, student_id, false)
+        .await
+        .map(Json)
+        .map_err(coaching_error_response)
+}
+
+pub async fn end_link(
+    State(state): State<SharedState>,
+    CurrentUser(user_id): CurrentUser,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    coaching::end_link(&state.db, id, user_id)
+        .await
+        .map_err(coaching_error_response)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn my_students(
+    State(state): State<SharedState>,
+    CurrentUser(coach_id): CurrentUser,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    CoachLink::for_coach(&state.db, coach_id)
+        .await
+        .map(Json)
+        .map_err(|err| coaching_error_response(CoachingError::Other(err)))
+}
+
+pub async fn my_coaches(
+    State(state): State<SharedState>,
+    CurrentUser(student_id): CurrentUser,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    CoachLink::for_student(&state.db, student_id)
+        .await
+        .map(Json)
+        .map_err(|err| coaching_error_response(CoachingError::Other(err)))
+}
@@ -0,0 +1,55 @@
+use axum::http::StatusCode;
+use axum::routing::{delete, get, post, put};
+use axum::Router;
+use tracing::error;
+
+use crate::services::coaching::CoachingError;
+use crate::state::SharedState;
+
+use self::comments::{add_comment, list_comments};
+use self::links::{
+    accept_invitation, decline_invitation, end_link, invite_student, my_coaches, my_students,
+};
+use self::sessions::{coached_session, coached_sessions, grant_session, revoke_session};
+
+mod comments;
+mod links;
+mod sessions;
+
+pub fn coaching_router() -> Router<SharedState> {
+    Router::new()
+        .route("/coaching/invitations", post(invite_student))
+        .route("/coaching/invitations/:id/accept", post(accept_invitation))
+        .route(
+            "/coaching/invitations/:id/decline",
+            post(decline_invitation),
+        )
+        .route("/coaching/links/:id", delete(end_link))
+        .route("/coaching/students", get(my_students))
+        .route("/coaching/coaches", get(my_coaches))
+        .route("/coaching/sessions", get(coached_sessions))
+        .route("/coaching/sessions/:id", get(coached_session))
+        .route(
+            "/sessions/:id/coaches/:coach_id",
+            put(grant_session).delete(revoke_session),
+        )
+        .route(
+            "/sessions/:id/comments",
+            get(list_comments).post(add_comment),
+        )
+}
+
+fn coaching_error_response(err: CoachingError) -> (StatusCode, String) {
+    let status = match &err {
+        CoachingError::NotFound => StatusCode::NOT_FOUND,
+        CoachingError::Invalid(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        CoachingError::Other(inner) => {
+            error!("coaching request failed: {:?}", inner);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "coaching request failed".into(),
+            );
+        }
+    };
+    (status, err.to_string())
+}
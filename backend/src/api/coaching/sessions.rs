@@ -0,0 +1,59 @@
+use axum::extract::{Json, Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use super::coaching_error_response;
+use crate::auth::CurrentUser;
+use crate::services::coaching;
+use crate::state::SharedState;
+
+#[derive(Deserialize)]
+pub struct CoachedSessionsParams {
+    pub student_id: Option<Uuid>,
+}
+
+pub async fn coached_sessions(
+    State(state): State<SharedState>,
+    CurrentUser(coach_id): CurrentUser,
+    Query(params): Query<CoachedSessionsParams>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    coaching::coached_sessions(&state.db, coach_id, params.student_id)
+        .await
+        .map(Json)
+        .map_err(coaching_error_response)
+}
+
+pub async fn coached_session(
+    State(state): State<SharedState>,
+    CurrentUser(coach_id): CurrentUser,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    coaching::coached_session_detail(&state.db, &state.encryption, id, coach_id)
+        .await
+        .map(Json)
+        .map_err(coaching_error_response)
+}
+
+pub async fn grant_session(
+    State(state): State<SharedState>,
+    CurrentUser(student_id): CurrentUser,
+    Path((id, coach_id)): Path<(Uuid, Uuid)>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    coaching::grant_session(&state.db, id, student_id, coach_id)
+        .await
+        .map_err(coaching_error_response)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn revoke_session(
+    State(state): State<SharedState>,
+    CurrentUser(student_id): CurrentUser,
+    Path((id, coach_id)): Path<(Uuid, Uuid)>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    coaching::revoke_session(&state.db, id, student_id, coach_id)
+        .await
+        .map_err(coaching_error_response)?;
+    Ok(StatusCode::NO_CONTENT)
+}
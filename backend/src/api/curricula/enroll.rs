@@ -0,0 +1,88 @@
+use axum::extract::{Json, Path, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::IntoResponse;
+use serde::Serialize;
+use tracing::info;
+use uuid::Uuid;
+
+use super::curriculum_error_response;
+use crate::auth::CurrentUser;
+use crate::models::topic::Topic;
+use crate::models::topic_translation::localize_topics;
+use crate::services::{curricula, locale};
+use crate::state::SharedState;
+
+#[derive(Serialize)]
+pub struct NextTopicResponse {
+    pub completed: bool,
+    pub unit_id: Option<Uuid>,
+    pub topic: Option<Topic>,
+}
+
+pub async fn enroll(
+    State(state): State<SharedState>,
+    CurrentUser(user_id): CurrentUser,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let (enrollment, created) = curricula::enroll(&state.db, user_id, id)
+        .await
+        .map_err(curriculum_error_response)?;
+    if created {
+        info!("user {} enrolled in curriculum {}", user_id, id);
+    }
+    let status = if created {
+        StatusCode::CREATED
+    } else {
+        StatusCode::OK
+    };
+    Ok((status, Json(enrollment)))
+}
+
+pub async fn progress(
+    State(state): State<SharedState>,
+    CurrentUser(user_id): CurrentUser,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let progress = curricula::progress(&state.db, user_id, id)
+        .await
+        .map_err(curriculum_error_response)?;
+    Ok(Json(progress))
+}
+
+pub async fn next_topic(
+    State(state): State<SharedState>,
+    CurrentUser(user_id): CurrentUser,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let progress = curricula::progress(&state.db, user_id, id)
+        .await
+        .map_err(curriculum_error_response)?;
+    let Some(next) = progress.next else {
+        return Ok(Json(NextTopicResponse {
+            completed: progress.completed,
+            unit_id: None,
+            topic: None,
+        }));
+    };
+
+    let topic = Topic::get(&state.db, next.topic_id)
+        .await
+        .map_err(|err| curriculum_error_response(err.into()))?;
+    let mut topics: Vec<Topic> = topic.into_iter().collect();
+    let locale = locale::negotiate(
+        None,
+        headers
+            .get(header::ACCEPT_LANGUAGE)
+            .and_then(|v| v.to_str().ok()),
+    );
+    localize_topics(&state.db, &mut topics, locale)
+        .await
+        .map_err(|err| curriculum_error_response(err.into()))?;
+
+    Ok(Json(NextTopicResponse {
+        completed: false,
+        unit_id: Some(next.unit_id),
+        topic: topics.pop(),
+    }))
+}
@@ -0,0 +1,48 @@
+use axum::extract::{Json, Path, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use super::curriculum_error_response;
+use crate::auth::AdminUser;
+use crate::models::curriculum::NewCurriculum;
+use crate::services::curricula;
+use crate::state::SharedState;
+
+pub async fn list_curricula(
+    State(state): State<SharedState>,
+) -> Result<impl IntoResponse, StatusCode> {
+    match curricula::list_curricula(&state.db).await {
+        Ok(curricula) => Ok(Json(curricula)),
+        Err(err) => {
+            error!("failed to list curricula: {:?}", err);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn get_curriculum(
+    State(state): State<SharedState>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let curriculum = curricula::get_curriculum(&state.db, id)
+        .await
+        .map_err(curriculum_error_response)?;
+    Ok(Json(curriculum))
+}
+
+pub async fn create_curriculum(
+    State(state): State<SharedState>,
+    AdminUser(admin_id): AdminUser,
+    Json(payload): Json<NewCurriculum>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let curriculum = curricula::create_curriculum(&state.db, &payload)
+        .await
+        .map_err(curriculum_error_response)?;
+    info!(
+        "admin {} created curriculum {}",
+        admin_id, curriculum.curriculum.id
+    );
+    Ok((StatusCode::CREATED, Json(curriculum)))
+}
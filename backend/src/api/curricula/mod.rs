@@ -0,0 +1,39 @@
+use axum::http::StatusCode;
+use axum::routing::{get, post};
+use axum::Router;
+use tracing::error;
+
+use crate::services::curricula::CurriculumError;
+use crate::state::SharedState;
+
+use self::enroll::{enroll, next_topic, progress};
+use self::list::{create_curriculum, get_curriculum, list_curricula};
+
+mod enroll;
+mod list;
+
+pub fn curricula_router() -> Router<SharedState> {
+    Router::new()
+        .route("/curricula", get(list_curricula).post(create_curriculum))
+        .route("/curricula/:id", get(get_curriculum))
+        .route("/curricula/:id/enroll", post(enroll))
+        .route("/curricula/:id/progress", get(progress))
+        .route("/curricula/:id/next", get(next_topic))
+}
+
+fn curriculum_error_response(err: CurriculumError) -> (StatusCode, String) {
+    let status = match &err {
+        CurriculumError::NotFound => StatusCode::NOT_FOUND,
+        CurriculumError::NotEnrolled => StatusCode::CONFLICT,
+        CurriculumError::DuplicateTitle => StatusCode::CONFLICT,
+        CurriculumError::Invalid(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        CurriculumError::Other(inner) => {
+            error!("curriculum request failed: {:?}", inner);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "curriculum request failed".into(),
+            );
+        }
+    };
+    (status, err.to_string())
+}
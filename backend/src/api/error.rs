@@ -0,0 +1,233 @@
+use axum::body::Body;
+use axum::http::{header, HeaderValue, Request, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::Serialize;
+use tracing::error;
+use uuid::Uuid;
+
+use crate::services::coaching::CoachingError;
+use crate::services::deletion::RestoreError;
+use crate::services::sharing::ShareError;
+use crate::services::transcript_edits::TranscriptEditError;
+
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+pub const PROBLEM_CONTENT_TYPE: &str = "application/problem+json";
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+// Handler failures, rendered as RFC 7807 problem details. Server-side causes are logged
+// with the request id and kept out of the response body.
+#[derive(Debug, thiserror::Error)]
+pub enum ApiError {
+    #[error("{0}")]
+    BadRequest(String),
+    #[error("{0}")]
+    Unauthorized(String),
+    #[error("{0}")]
+    Forbidden(String),
+    #[error("{0}")]
+    NotFound(String),
+    #[error("{0}")]
+    Conflict(String),
+    #[error("{0}")]
+    Gone(String),
+    #[error("{0}")]
+    PreconditionRequired(String),
+    #[error("{0}")]
+    Validation(String),
+    #[error("{0}")]
+    NotImplemented(String),
+    #[error("an upstream service failed")]
+    Upstream(#[source] anyhow::Error),
+    #[error("the database is unavailable")]
+    Unavailable(#[source] anyhow::Error),
+    #[error("internal server error")]
+    Internal(#[source] anyhow::Error),
+}
+
+#[derive(Debug, Serialize)]
+pub struct Problem {
+    #[serde(rename = "type")]
+    pub problem_type: String,
+    pub title: String,
+    pub status: u16,
+    pub detail: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+}
+
+impl ApiError {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            ApiError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            ApiError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            ApiError::Forbidden(_) => StatusCode::FORBIDDEN,
+            ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::Conflict(_) => StatusCode::CONFLICT,
+            ApiError::Gone(_) => StatusCode::GONE,
+            ApiError::PreconditionRequired(_) => StatusCode::PRECONDITION_REQUIRED,
+            ApiError::Validation(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            ApiError::NotImplemented(_) => StatusCode::NOT_IMPLEMENTED,
+            ApiError::Upstream(_) => StatusCode::BAD_GATEWAY,
+            ApiError::Unavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+            ApiError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn slug(&self) -> &'static str {
+        match self {
+            ApiError::BadRequest(_) => "bad-request",
+            ApiError::Unauthorized(_) => "unauthorized",
+            ApiError::Forbidden(_) => "forbidden",
+            ApiError::NotFound(_) => "not-found",
+            ApiError::Conflict(_) => "conflict",
+            ApiError::Gone(_) => "gone",
+            ApiError::PreconditionRequired(_) => "precondition-required",
+            ApiError::Validation(_) => "validation-failed",
+            ApiError::NotImplemented(_) => "not-implemented",
+            ApiError::Upstream(_) => "upstream-failure",
+            ApiError::Unavailable(_) => "service-unavailable",
+            ApiError::Internal(_) => "internal-error",
+        }
+    }
+
+    pub fn problem(&self) -> Problem {
+        let status = self.status();
+        Problem {
+            problem_type: format!("/problems/{}", self.slug()),
+            title: status.canonical_reason().unwrap_or("Error").to_string(),
+            status: status.as_u16(),
+            detail: self.to_string(),
+            request_id: current_request_id(),
+        }
+    }
+}
+
+// Looks through the whole chain, so model errors wrapped in context still map by cause.
+impl From<anyhow::Error> for ApiError {
+    fn from(err: anyhow::Error) -> Self {
+        let cause = err
+            .chain()
+            .find_map(|cause| cause.downcast_ref::<sqlx::Error>());
+        match cause {
+            Some(sqlx::Error::RowNotFound) => ApiError::NotFound("resource not found".into()),
+            Some(
+                sqlx::Error::PoolTimedOut
+                | sqlx::Error::PoolClosed
+                | sqlx::Error::Io(_)
+                | sqlx::Error::Tls(_),
+            ) => ApiError::Unavailable(err),
+            Some(sqlx::Error::Database(db)) if db.is_unique_violation() => {
+                ApiError::Conflict("conflicts with an existing record".into())
+            }
+            Some(sqlx::Error::Database(db)) if db.is_foreign_key_violation() => {
+                ApiError::Validation("references a record that does not exist".into())
+            }
+            Some(sqlx::Error::Database(db)) if db.is_check_violation() => {
+                ApiError::Validation("violates a data constraint".into())
+            }
+            _ => ApiError::Internal(err),
+        }
+    }
+}
+
+impl From<sqlx::Error> for ApiError {
+    fn from(err: sqlx::Error) -> Self {
+        anyhow::Error::from(err).into()
+    }
+}
+
+impl From<ShareError> for ApiError {
+    fn from(err: ShareError) -> Self {
+        match err {
+            ShareError::NotFound => ApiError::NotFound(err.to_string()),
+            ShareError::Invalid(_) => ApiError::Validation(err.to_string()),
+            ShareError::Other(inner) => inner.into(),
+        }
+    }
+}
+
+impl From<CoachingError> for ApiError {
+    fn from(err: CoachingError) -> Self {
+        match err {
+            CoachingError::NotFound => ApiError::NotFound(err.to_string()),
+            CoachingError::Invalid(_) => ApiError::Validation(err.to_string()),
+            CoachingError::Other(inner) => inner.into(),
+        }
+    }
+}
+
+impl From<RestoreError> for ApiError {
+    fn from(err: RestoreError) -> Self {
+        match err {
+            RestoreError::NotFound => ApiError::NotFound(err.to_string()),
+            RestoreError::NotDeleted => ApiError::Conflict(err.to_string()),
+            RestoreError::Expired => ApiError::Gone(err.to_string()),
+            RestoreError::Other(inner) => inner.into(),
+        }
+    }
+}
+
+impl From<TranscriptEditError> for ApiError {
+    fn from(err: TranscriptEditError) -> Self {
+        match err {
+            TranscriptEditError::NotFound | TranscriptEditError::RevisionNotFound(_) => {
+                ApiError::NotFound(err.to_string())
+            }
+            TranscriptEditError::Conflict { .. } => ApiError::Conflict(err.to_string()),
+            TranscriptEditError::Invalid(_) => ApiError::Validation(err.to_string()),
+            TranscriptEditError::Database(inner) => inner.into(),
+            TranscriptEditError::Other(inner) => inner.into(),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let problem = self.problem();
+        if let ApiError::Upstream(cause)
+        | ApiError::Unavailable(cause)
+        | ApiError::Internal(cause) = &self
+        {
+            error!(
+                request_id = problem.request_id.as_deref().unwrap_or("-"),
+                "{}: {:?}", problem.detail, cause
+            );
+        }
+        let status = self.status();
+        (
+            status,
+            [(header::CONTENT_TYPE, PROBLEM_CONTENT_TYPE)],
+            Json(problem),
+        )
+            .into_response()
+    }
+}
+
+pub fn current_request_id() -> Option<String> {
+    REQUEST_ID.try_with(|id| id.clone()).ok()
+}
+
+// Reuses a well-formed incoming x-request-id or mints one, exposes it to error bodies
+// and echoes it on the response.
+pub async fn assign_request_id(req: Request<Body>, next: Next) -> Response {
+    let id = req
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|h| h.to_str().ok())
+        .map(str::trim)
+        .filter(|raw| {
+            !raw.is_empty() && raw.len() <= 128 && raw.chars().all(|c| c.is_ascii_graphic())
+        })
+        .map(str::to_string)
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+    let mut response = REQUEST_ID.scope(id.clone(), next.run(req)).await;
+    if let Ok(value) = HeaderValue::from_str(&id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
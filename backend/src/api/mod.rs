@@ -1,6 +1,14 @@
+use crate::api::account::account_router;
+use crate::api::audit::{audit_router, record_auth_failures};
+use crate::api::coaching::coaching_router;
+use crate::api::curricula::curricula_router;
+use crate::api::error::assign_request_id;
 use crate::api::health::health;
+use crate::api::orgs::orgs_router;
 use crate::api::realtime::realtime_router;
+use crate::api::retention::retention_router;
 use crate::api::sessions::sessions_router;
+use crate::api::shared::shared_router;
 use crate::api::topics::topics_router;
 use crate::state::SharedState;
 use axum::body::Body;
@@ -11,9 +19,17 @@ use axum::{middleware, Router};
 use tower_http::cors::CorsLayer;
 use tower_http::trace::TraceLayer;
 
+mod account;
+mod audit;
+mod coaching;
+mod curricula;
+pub mod error;
 mod health;
+mod orgs;
 mod realtime;
+mod retention;
 mod sessions;
+mod shared;
 mod topics;
 
 pub fn router(state: SharedState) -> Router {
@@ -21,8 +37,19 @@ pub fn router(state: SharedState) -> Router {
 
     let api = Router::new()
         .merge(topics_router())
+        .merge(curricula_router())
+        .merge(coaching_router())
+        .merge(orgs_router())
         .merge(sessions_router())
         .merge(realtime_router())
+        .merge(account_router())
+        .merge(retention_router())
+        .merge(shared_router())
+        .merge(audit_router())
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            record_auth_failures,
+        ))
         .layer(cors.clone())
         .layer(TraceLayer::new_for_http())
         .layer(middleware::from_fn(auth_maybe))
@@ -34,6 +61,7 @@ pub fn router(state: SharedState) -> Router {
         .layer(cors)
         .layer(TraceLayer::new_for_http())
         .layer(middleware::from_fn(auth_maybe))
+        .layer(middleware::from_fn(assign_request_id))
         .with_state(state)
 }
 
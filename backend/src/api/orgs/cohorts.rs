@@ -0,0 +1,90 @@
+use axum::extract::{Json, Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use super::org_error_response;
+use crate::auth::CurrentUser;
+use crate::services::organizations;
+use crate::state::SharedState;
+
+#[derive(Deserialize)]
+pub struct CreateCohortRequest {
+    pub name: String,
+}
+
+#[derive(Deserialize)]
+pub struct StatsParams {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+}
+
+pub async fn list_cohorts(
+    State(state): State<SharedState>,
+    CurrentUser(user_id): CurrentUser,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    organizations::list_cohorts(&state.db, id, user_id)
+        .await
+        .map(Json)
+        .map_err(org_error_response)
+}
+
+pub async fn create_cohort(
+    State(state): State<SharedState>,
+    CurrentUser(user_id): CurrentUser,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<CreateCohortRequest>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let cohort = organizations::create_cohort(&state.db, id, user_id, &payload.name)
+        .await
+        .map_err(org_error_response)?;
+    Ok((StatusCode::CREATED, Json(cohort)))
+}
+
+pub async fn cohort_members(
+    State(state): State<SharedState>,
+    CurrentUser(user_id): CurrentUser,
+    Path((id, cohort_id)): Path<(Uuid, Uuid)>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    organizations::cohort_members(&state.db, id, user_id, cohort_id)
+        .await
+        .map(Json)
+        .map_err(org_error_response)
+}
+
+pub async fn add_cohort_member(
+    State(state): State<SharedState>,
+    CurrentUser(user_id): CurrentUser,
+    Path((id, cohort_id, member_id)): Path<(Uuid, Uuid, Uuid)>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    organizations::add_cohort_member(&state.db, id, user_id, cohort_id, member_id)
+        .await
+        .map_err(org_error_response)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn remove_cohort_member(
+    State(state): State<SharedState>,
+    CurrentUser(user_id): CurrentUser,
+    Path((id, cohort_id, member_id)): Path<(Uuid, Uuid, Uuid)>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    organizations::remove_cohort_member(&state.db, id, user_id, cohort_id, member_id)
+        .await
+        .map_err(org_error_response)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn cohort_stats(
+    State(state): State<SharedState>,
+    CurrentUser(user_id): CurrentUser,
+    Path((id, cohort_id)): Path<(Uuid, Uuid)>,
+    Query(params): Query<StatsParams>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    organizations::cohort_stats(&state.db, id, user_id, cohort_id, params.from, params.to)
+        .await
+        .map(Json)
+        .map_err(org_error_response)
+}
@@ -0,0 +1,133 @@
+use axum::extract::{Json, Path, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use serde::Deserialize;
+use tracing::info;
+use uuid::Uuid;
+
+use super::org_error_response;
+use crate::auth::CurrentUser;
+use crate::services::organizations;
+use crate::state::SharedState;
+
+#[derive(Deserialize)]
+pub struct CreateOrganizationRequest {
+    pub name: String,
+}
+
+#[derive(Deserialize)]
+pub struct InviteMemberRequest {
+    pub user_id: Uuid,
+    #[serde(default = "default_role")]
+    pub role: String,
+}
+
+#[derive(Deserialize)]
+pub struct UpdateMemberRequest {
+    pub role: String,
+}
+
+fn default_role() -> String {
+    "member".into()
+}
+
+pub async fn create_organization(
+    State(state): State<SharedState>,
+    CurrentUser(user_id): CurrentUser,
+    Json(payload): Json<CreateOrganizationRequest>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let org = organizations::create_organization(&state.db, user_id, &payload.name)
+        .await
+        .map_err(org_error_response)?;
+    info!("user {} created organization {}", user_id, org.id);
+    Ok((StatusCode::CREATED, Json(org)))
+}
+
+pub async fn my_organization(
+    State(state): State<SharedState>,
+    CurrentUser(user_id): CurrentUser,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    organizations::my_organization(&state.db, user_id)
+        .await
+        .map(Json)
+        .map_err(org_error_response)
+}
+
+pub async fn list_members(
+    State(state): State<SharedState>,
+    CurrentUser(user_id): CurrentUser,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    organizations::list_members(&state.db, id, user_id)
+        .await
+        .map(Json)
+        .map_err(org_error_response)
+}
+
+pub async fn invite_member(
+    State(state): State<SharedState>,
+    CurrentUser(user_id): CurrentUser,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<InviteMemberRequest>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let invitation =
+        organizations::invite_member(&state.db, id, user_id, payload.user_id, &payload.role)
+            .await
+            .map_err(org_error_response)?;
+    Ok((StatusCode::CREATED, Json(invitation)))
+}
+
+pub async fn my_invitations(
+    State(state): State<SharedState>,
+    CurrentUser(user_id): CurrentUser,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    organizations::my_invitations(&state.db, user_id)
+        .await
+        .map(Json)
+        .map_err(org_error_response)
+}
+
+pub async fn accept_invitation(
+    State(state): State<SharedState>,
+    CurrentUser(user_id): CurrentUser,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    organizations::accept_invitation(&state.db, id, user_id)
+        .await
+        .map(Json)
+        .map_err(org_error_response)
+}
+
+pub async fn decline_invitation(
+    State(state): State<SharedState>,
+    CurrentUser(user_id): CurrentUser,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    organizations::decline_invitation(&state.db, id, user_id)
+        .await
+        .map(Json)
+        .map_err(org_error_response)
+}
+
+pub async fn update_member_role(
+    State(state): State<SharedState>,
+    CurrentUser(user_id): CurrentUser,
+    Path((id, member_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<UpdateMemberRequest>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    organizations::update_member_role(&state.db, id, user_id, member_id, &payload.role)
+        .await
+        .map(Json)
+        .map_err(org_error_response)
+}
+
+pub async fn remove_member(
+    State(state): State<SharedState>,
+    CurrentUser(user_id): CurrentUser,
+    Path((id, member_id)): Path<(Uuid, Uuid)>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    organizations::remove_member(&state.db, id, user_id, member_id)
+        .await
+        .map_err(org_error_response)?;
+    Ok(StatusCode::NO_CONTENT)
+}
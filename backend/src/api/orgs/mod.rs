@@ -0,0 +1,65 @@
+use axum::http::StatusCode;
+use axum::routing::{delete, get, post, put};
+use axum::Router;
+use tracing::error;
+
+use crate::services::organizations::OrgError;
+use crate::state::SharedState;
+
+use self::cohorts::{
+    add_cohort_member, cohort_members, cohort_stats, create_cohort, list_cohorts,
+    remove_cohort_member,
+};
+use self::members::{
+    accept_invitation, create_organization, decline_invitation, invite_member, list_members,
+    my_invitations, my_organization, remove_member, update_member_role,
+};
+use self::topics::{archive_org_topic, create_org_topic, list_org_topics};
+
+mod cohorts;
+mod members;
+mod topics;
+
+pub fn orgs_router() -> Router<SharedState> {
+    Router::new()
+        .route("/orgs", post(create_organization))
+        .route("/orgs/me", get(my_organization))
+        .route("/orgs/invitations", get(my_invitations))
+        .route("/orgs/invitations/:id/accept", post(accept_invitation))
+        .route("/orgs/invitations/:id/decline", post(decline_invitation))
+        .route("/orgs/:id/members", get(list_members))
+        .route("/orgs/:id/invitations", post(invite_member))
+        .route(
+            "/orgs/:id/members/:user_id",
+            put(update_member_role).delete(remove_member),
+        )
+        .route("/orgs/:id/cohorts", get(list_cohorts).post(create_cohort))
+        .route("/orgs/:id/cohorts/:cohort_id/members", get(cohort_members))
+        .route(
+            "/orgs/:id/cohorts/:cohort_id/members/:user_id",
+            put(add_cohort_member).delete(remove_cohort_member),
+        )
+        .route("/orgs/:id/cohorts/:cohort_id/stats", get(cohort_stats))
+        .route(
+            "/orgs/:id/topics",
+            get(list_org_topics).post(create_org_topic),
+        )
+        .route("/orgs/:id/topics/:topic_id", delete(archive_org_topic))
+}
+
+fn org_error_response(err: OrgError) -> (StatusCode, String) {
+    let status = match &err {
+        OrgError::NotFound => StatusCode::NOT_FOUND,
+        OrgError::Forbidden => StatusCode::FORBIDDEN,
+        OrgError::Conflict(_) => StatusCode::CONFLICT,
+        OrgError::Invalid(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        OrgError::Other(inner) => {
+            error!("organization request failed: {:?}", inner);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "organization request failed".into(),
+            );
+        }
+    };
+    (status, err.to_string())
+}
@@ -0,0 +1,49 @@
+use axum::extract::{Json, Path, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use tracing::info;
+use uuid::Uuid;
+
+use super::org_error_response;
+use crate::auth::CurrentUser;
+use crate::models::topic::NewTopic;
+use crate::services::organizations;
+use crate::state::SharedState;
+
+pub async fn list_org_topics(
+    State(state): State<SharedState>,
+    CurrentUser(user_id): CurrentUser,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    organizations::list_org_topics(&state.db, id, user_id)
+        .await
+        .map(Json)
+        .map_err(org_error_response)
+}
+
+pub async fn create_org_topic(
+    State(state): State<SharedState>,
+    CurrentUser(user_id): CurrentUser,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<NewTopic>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let topic = organizations::create_org_topic(&state.db, id, user_id, &payload)
+        .await
+        .map_err(org_error_response)?;
+    info!(
+        "user {} created topic {} for organization {}",
+        user_id, topic.id, id
+    );
+    Ok((StatusCode::CREATED, Json(topic)))
+}
+
+pub async fn archive_org_topic(
+    State(state): State<SharedState>,
+    CurrentUser(user_id): CurrentUser,
+    Path((id, topic_id)): Path<(Uuid, Uuid)>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    organizations::archive_org_topic(&state.db, id, user_id, topic_id)
+        .await
+        .map(Json)
+        .map_err(org_error_response)
+}
@@ -5,12 +5,16 @@ use axum::routing::post;
 use axum::Router;
 use chrono::{Duration, Utc};
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 use tracing::info;
 use uuid::Uuid;
 
-use crate::auth::CurrentUser;
+use crate::api::error::ApiError;
+use crate::auth::{ClientInfo, CurrentUser};
 use crate::models::client_secret::{ClientSecret, NewClientSecret};
 use crate::models::session::Session;
+use crate::services::audit::{self, Target};
+use crate::services::drills::{self, SessionMode};
 use crate::services::sessions;
 use crate::state::SharedState;
 use crate::telemetry;
@@ -28,6 +32,11 @@ pub struct RealtimeSessionResponse {
     pub client_secret: String,
     pub expires_at: String,
     pub session_id: Uuid,
+    // Practice language for the realtime provider's transcription and responses.
+    pub language: String,
+    pub mode: SessionMode,
+    // Hard cut-off for timed modes; the client secret never outlives it.
+    pub deadline: Option<String>,
 }
 
 pub fn realtime_router() -> Router<SharedState> {
@@ -37,8 +46,9 @@ pub fn realtime_router() -> Router<SharedState> {
 pub async fn mint_client_secret(
     State(state): State<SharedState>,
     CurrentUser(user_id): CurrentUser,
+    client: ClientInfo,
     Json(body): Json<RealtimeSessionRequest>,
-) -> impl IntoResponse {
+) -> Result<impl IntoResponse, ApiError> {
     let session = match Session::get(&state.db, body.session_id).await {
         Ok(sess) => sess,
         Err(err) => {
@@ -47,17 +57,23 @@ pub async fn mint_client_secret(
                 Some(body.session_id),
                 &format!("{:?}", err),
             );
-            return StatusCode::NOT_FOUND.into_response();
+            return Err(err.into());
         }
     };
 
+    if session.deleted_at.is_some() {
+        return Err(ApiError::NotFound("session not found".into()));
+    }
+
     if session.user_id != user_id {
         telemetry::log_failure(
             "client_secret_forbidden",
             Some(body.session_id),
             "user mismatch",
         );
-        return StatusCode::FORBIDDEN.into_response();
+        return Err(ApiError::Forbidden(
+            "session belongs to another user".into(),
+        ));
     }
 
     if let Some(status) = body.status.as_deref() {
@@ -70,10 +86,20 @@ pub async fn mint_client_secret(
         }
     }
 
-    let existing = ClientSecret::latest_for_session(&state.db, body.session_id)
-        .await
-        .ok()
-        .flatten();
+    let mode = match SessionMode::from_parts(&session.mode, &session.mode_params) {
+        Ok(mode) => mode,
+        Err(err) => {
+            telemetry::log_failure(
+                "client_secret_invalid_mode",
+                Some(body.session_id),
+                &format!("{:?}", err),
+            );
+            return Err(ApiError::Internal(err));
+        }
+    };
+    let deadline = mode.deadline(session.start_time);
+
+    let existing = ClientSecret::latest_for_session(&state.db, body.session_id).await?;
     let now = Utc::now();
     let expiry_buffer = Duration::seconds(30);
 
@@ -82,8 +108,10 @@ pub async fn mint_client_secret(
         let needs_refresh =
             body.force_refresh || existing_secret.expires_at <= now + expiry_buffer;
         if needs_refresh {
+            let Some(expires_at) = drills::secret_expiry(now, deadline) else {
+                return Err(time_limit_reached(body.session_id));
+            };
             let token = format!("client_secret_{}", Uuid::new_v4());
-            let expires_at = Utc::now() + Duration::minutes(10);
             let insert = ClientSecret::insert(
                 &state.db,
                 NewClientSecret {
@@ -102,7 +130,7 @@ pub async fn mint_client_secret(
                         Some(body.session_id),
                         &format!("{:?}", err),
                     );
-                    return StatusCode::BAD_REQUEST.into_response();
+                    return Err(err.into());
                 }
             }
         } else {
@@ -110,8 +138,10 @@ pub async fn mint_client_secret(
             (existing_secret.token, existing_secret.expires_at)
         }
     } else {
+        let Some(expires_at) = drills::secret_expiry(now, deadline) else {
+            return Err(time_limit_reached(body.session_id));
+        };
         let token = format!("client_secret_{}", Uuid::new_v4());
-        let expires_at = Utc::now() + Duration::minutes(10);
         let insert = ClientSecret::insert(
             &state.db,
             NewClientSecret {
@@ -129,7 +159,7 @@ pub async fn mint_client_secret(
                     Some(body.session_id),
                     &format!("{:?}", err),
                 );
-                return StatusCode::BAD_REQUEST.into_response();
+                return Err(err.into());
             }
         }
     };
@@ -139,19 +169,41 @@ pub async fn mint_client_secret(
         body.session_id,
         if reused { "reused" } else { "refreshed" }
     );
+    if !reused {
+        audit::record(
+            &state.db,
+            &client,
+            Some(user_id),
+            audit::CLIENT_SECRET_ISSUED,
+            Target::Session(body.session_id),
+            json!({ "expires_at": expires_at }),
+        )
+        .await;
+    }
     telemetry::log_recovery(
         "client_secret_issued",
         Some(body.session_id),
         if reused { "reused_valid_token" } else { "new_token" },
     );
 
-    (
+    Ok((
         StatusCode::OK,
         Json(RealtimeSessionResponse {
             client_secret: token,
             expires_at: expires_at.to_rfc3339(),
             session_id: body.session_id,
+            language: session.language,
+            mode,
+            deadline: deadline.map(|d| d.to_rfc3339()),
         }),
-    )
-        .into_response()
+    ))
+}
+
+fn time_limit_reached(session_id: Uuid) -> ApiError {
+    telemetry::log_failure(
+        "client_secret_time_limit_reached",
+        Some(session_id),
+        "session time limit reached",
+    );
+    ApiError::Gone("session time limit reached".into())
 }
@@ -0,0 +1,90 @@
+use axum::extract::{Json, Query, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::routing::get;
+use axum::Router;
+use chrono::Utc;
+use serde::Deserialize;
+use tracing::error;
+
+use crate::auth::{AdminUser, CurrentUser};
+use crate::models::retention_policy::RetentionPolicy;
+use crate::services::retention::{self, RetentionError, RetentionRule, RETENTION_BATCH_SIZE};
+use crate::state::SharedState;
+
+pub fn retention_router() -> Router<SharedState> {
+    Router::new()
+        .route(
+            "/me/retention",
+            get(get_retention)
+                .put(put_retention)
+                .delete(delete_retention),
+        )
+        .route("/retention/report", get(retention_report))
+}
+
+#[derive(Deserialize)]
+pub struct ReportParams {
+    pub limit: Option<i64>,
+}
+
+pub async fn get_retention(
+    State(state): State<SharedState>,
+    CurrentUser(user_id): CurrentUser,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    retention::user_retention(&state.db, RetentionRule::from_env(), user_id)
+        .await
+        .map(Json)
+        .map_err(|err| retention_error_response(err.into()))
+}
+
+pub async fn put_retention(
+    State(state): State<SharedState>,
+    CurrentUser(user_id): CurrentUser,
+    Json(rule): Json<RetentionRule>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    retention::set_user_override(&state.db, RetentionRule::from_env(), user_id, rule)
+        .await
+        .map(Json)
+        .map_err(retention_error_response)
+}
+
+pub async fn delete_retention(
+    State(state): State<SharedState>,
+    CurrentUser(user_id): CurrentUser,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    match RetentionPolicy::delete(&state.db, user_id).await {
+        Ok(true) => Ok(StatusCode::NO_CONTENT),
+        Ok(false) => Err((StatusCode::NOT_FOUND, "no retention override".into())),
+        Err(err) => Err(retention_error_response(err.into())),
+    }
+}
+
+pub async fn retention_report(
+    State(state): State<SharedState>,
+    AdminUser(_admin_id): AdminUser,
+    Query(params): Query<ReportParams>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let limit = params
+        .limit
+        .unwrap_or(RETENTION_BATCH_SIZE)
+        .clamp(1, RETENTION_BATCH_SIZE);
+    retention::report(&state.db, RetentionRule::from_env(), Utc::now(), limit)
+        .await
+        .map(Json)
+        .map_err(|err| retention_error_response(err.into()))
+}
+
+fn retention_error_response(err: RetentionError) -> (StatusCode, String) {
+    let status = match &err {
+        RetentionError::Invalid(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        RetentionError::Other(inner) => {
+            error!("retention request failed: {:?}", inner);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "retention request failed".into(),
+            );
+        }
+    };
+    (status, err.to_string())
+}
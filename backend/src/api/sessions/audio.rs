@@ -0,0 +1,37 @@
+use axum::extract::{Path, State};
+use axum::http::header;
+use axum::response::IntoResponse;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::api::error::ApiError;
+use crate::auth::{ClientInfo, CurrentUser};
+use crate::services::audit::{self, Target};
+use crate::services::coaching::{self, SessionAccess};
+use crate::state::SharedState;
+
+pub async fn session_audio(
+    State(state): State<SharedState>,
+    CurrentUser(user_id): CurrentUser,
+    client: ClientInfo,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+    let (access, audio) = coaching::session_audio(&state.db, &state.storage, id, user_id).await?;
+    let role = match access {
+        SessionAccess::Owner => "owner",
+        SessionAccess::Coach => "coach",
+    };
+    audit::record(
+        &state.db,
+        &client,
+        Some(user_id),
+        audit::AUDIO_ACCESSED,
+        Target::Session(id),
+        json!({ "via": "session_audio", "access": role }),
+    )
+    .await;
+    let mime = audio
+        .mime_type
+        .unwrap_or_else(|| "application/octet-stream".to_string());
+    Ok(([(header::CONTENT_TYPE, mime)], audio.bytes))
+}
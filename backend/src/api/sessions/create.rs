@@ -1,30 +1,81 @@
 use axum::extract::{Json, State};
-use axum::http::StatusCode;
+use axum::http::{header, HeaderMap, StatusCode};
 use axum::response::IntoResponse;
 use serde::Deserialize;
+use serde_json::json;
 use tracing::info;
 use uuid::Uuid;
 
-use crate::auth::CurrentUser;
-use crate::services::sessions;
+use crate::api::error::ApiError;
+use crate::auth::{ClientInfo, CurrentUser};
+use crate::services::audit::{self, Target};
+use crate::services::drills::SessionMode;
+use crate::services::locale;
+use crate::services::sessions::{self, CreateSessionError};
 use crate::state::SharedState;
 
 #[derive(Deserialize)]
 pub struct CreateSessionRequest {
     pub topic_id: Uuid,
+    // Practice language; defaults to the negotiated `Accept-Language`.
+    pub language: Option<String>,
+    #[serde(default)]
+    pub mode: SessionMode,
 }
 
 pub async fn create_session(
     State(state): State<SharedState>,
     CurrentUser(user_id): CurrentUser,
+    client: ClientInfo,
+    headers: HeaderMap,
     Json(payload): Json<CreateSessionRequest>,
-) -> Result<impl IntoResponse, StatusCode> {
-    info!("creating session for user {}", user_id);
-    match sessions::create_session(&state.db, user_id, payload.topic_id).await {
-        Ok(session) => Ok((StatusCode::CREATED, Json(session))),
-        Err(err) => {
-            eprintln!("failed to create session: {:?}", err);
-            Err(StatusCode::BAD_REQUEST)
-        }
+) -> Result<impl IntoResponse, ApiError> {
+    let language = match payload.language.as_deref() {
+        Some(requested) => match locale::normalize_locale(requested) {
+            Some(language) => language,
+            None => {
+                return Err(ApiError::BadRequest(format!(
+                    "unsupported session language: {}",
+                    requested
+                )));
+            }
+        },
+        None => locale::negotiate(
+            None,
+            headers
+                .get(header::ACCEPT_LANGUAGE)
+                .and_then(|h| h.to_str().ok()),
+        ),
+    };
+
+    if let Err(err) = payload.mode.validate() {
+        return Err(ApiError::BadRequest(format!(
+            "invalid session mode: {}",
+            err
+        )));
     }
+
+    info!("creating session for user {}", user_id);
+    let session = sessions::create_session(
+        &state.db,
+        user_id,
+        payload.topic_id,
+        language,
+        &payload.mode,
+    )
+    .await
+    .map_err(|err| match err {
+        CreateSessionError::Other(err) => ApiError::from(err),
+        err => ApiError::BadRequest(err.to_string()),
+    })?;
+    audit::record(
+        &state.db,
+        &client,
+        Some(user_id),
+        audit::SESSION_CREATED,
+        Target::Session(session.id),
+        json!({ "topic_id": session.topic_id, "mode": session.mode }),
+    )
+    .await;
+    Ok((StatusCode::CREATED, Json(session)))
 }
@@ -1,22 +1,30 @@
 use axum::extract::{Path, State};
 use axum::http::StatusCode;
 use axum::response::IntoResponse;
+use serde_json::Value;
 use uuid::Uuid;
 
-use crate::auth::CurrentUser;
-use crate::services::history::delete_session_for_user;
+use crate::api::error::ApiError;
+use crate::auth::{ClientInfo, CurrentUser};
+use crate::services::audit::{self, Target};
+use crate::services::deletion;
 use crate::state::SharedState;
 
 pub async fn delete_session(
     State(state): State<SharedState>,
     CurrentUser(user_id): CurrentUser,
+    client: ClientInfo,
     Path(id): Path<Uuid>,
-) -> Result<impl IntoResponse, StatusCode> {
-    match delete_session_for_user(&state.db, id, user_id).await {
-        Ok(_) => Ok(StatusCode::NO_CONTENT),
-        Err(err) => {
-            eprintln!("delete session failed: {:?}", err);
-            Err(StatusCode::NOT_FOUND)
-        }
-    }
+) -> Result<impl IntoResponse, ApiError> {
+    deletion::delete_session(&state.db, id, user_id).await?;
+    audit::record(
+        &state.db,
+        &client,
+        Some(user_id),
+        audit::SESSION_DELETED,
+        Target::Session(id),
+        Value::Null,
+    )
+    .await;
+    Ok(StatusCode::NO_CONTENT)
 }
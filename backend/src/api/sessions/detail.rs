@@ -1,13 +1,17 @@
 use axum::extract::{Path, State};
-use axum::http::StatusCode;
 use axum::response::IntoResponse;
 use axum::Json;
+use serde_json::json;
 use uuid::Uuid;
 
-use crate::auth::CurrentUser;
+use crate::api::error::ApiError;
+use crate::auth::{ClientInfo, CurrentUser};
+use crate::services::audit::{self, Target};
 use crate::services::history::{session_detail_for_user, SessionDetail};
 use crate::state::SharedState;
 
+use super::transcript::etag_headers;
+
 #[derive(serde::Serialize)]
 pub struct SessionDetailResponse {
     pub session: SessionDetail,
@@ -16,13 +20,23 @@ pub struct SessionDetailResponse {
 pub async fn session_detail(
     State(state): State<SharedState>,
     CurrentUser(user_id): CurrentUser,
+    client: ClientInfo,
     Path(id): Path<Uuid>,
-) -> Result<impl IntoResponse, StatusCode> {
-    match session_detail_for_user(&state.db, id, user_id).await {
-        Ok(session) => Ok(Json(SessionDetailResponse { session })),
-        Err(err) => {
-            eprintln!("session detail failed: {:?}", err);
-            Err(StatusCode::NOT_FOUND)
-        }
+) -> Result<impl IntoResponse, ApiError> {
+    let session = session_detail_for_user(&state.db, &state.encryption, id, user_id).await?;
+    // The detail hands out the recording's URL, so reading it counts as an access. Sealed
+    // recordings are only reachable through session_audio, which records its own access.
+    if session.audio_url.is_some() && !state.storage.encrypts() {
+        audit::record(
+            &state.db,
+            &client,
+            Some(user_id),
+            audit::AUDIO_ACCESSED,
+            Target::Session(id),
+            json!({ "via": "session_detail" }),
+        )
+        .await;
     }
+    let etag = etag_headers(session.transcript_revision);
+    Ok((etag, Json(SessionDetailResponse { session })))
 }
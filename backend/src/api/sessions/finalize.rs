@@ -3,16 +3,20 @@ use axum::http::StatusCode;
 use axum::response::IntoResponse;
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 use tracing::info;
 use uuid::Uuid;
 
-use crate::auth::CurrentUser;
+use crate::api::error::ApiError;
+use crate::auth::{ClientInfo, CurrentUser};
 use crate::models::audio_recording::AudioRecording;
 use crate::models::session::Session;
 use crate::models::transcript::{
     get_transcript_by_session, upsert_transcript, Transcript, TranscriptSegment,
 };
-use crate::services::{sessions, transcription};
+use crate::services::audit::{self, Target};
+use crate::services::transcript_merge::{self, MergeOptions};
+use crate::services::{curricula, history, redaction, sessions, transcription};
 use crate::state::SharedState;
 use crate::telemetry;
 
@@ -22,6 +26,11 @@ pub struct FinalizeRequest {
     pub status: String,
     pub duration_seconds: Option<i32>,
     pub audio_url: Option<String>,
+    // Coach turns captured from realtime events, on the session timeline.
+    #[serde(default)]
+    pub coach_turns: Vec<TranscriptSegment>,
+    // Offset of the uploaded recording's start relative to the session start.
+    pub audio_offset_ms: Option<i64>,
 }
 
 #[derive(Serialize)]
@@ -36,9 +45,10 @@ pub struct FinalizeResponse {
 pub async fn finalize_session(
     State(state): State<SharedState>,
     CurrentUser(user_id): CurrentUser,
+    client: ClientInfo,
     Path(id): Path<Uuid>,
     Json(payload): Json<FinalizeRequest>,
-) -> Result<impl IntoResponse, StatusCode> {
+) -> Result<impl IntoResponse, ApiError> {
     let session = match Session::get(&state.db, id).await {
         Ok(sess) => sess,
         Err(err) => {
@@ -47,20 +57,24 @@ pub async fn finalize_session(
                 Some(id),
                 &format!("session missing: {:?}", err),
             );
-            return Err(StatusCode::NOT_FOUND);
+            return Err(err.into());
         }
     };
 
+    if session.deleted_at.is_some() {
+        return Err(ApiError::NotFound("session not found".into()));
+    }
+
     if session.user_id != user_id {
         telemetry::log_failure("finalize_forbidden", Some(id), "user mismatch");
-        return Err(StatusCode::FORBIDDEN);
+        return Err(ApiError::Forbidden(
+            "session belongs to another user".into(),
+        ));
     }
 
     let existing_transcript: Option<Transcript> =
-        get_transcript_by_session(&state.db, id).await.unwrap_or(None);
-    let audio_record = AudioRecording::get_by_session(&state.db, id)
-        .await
-        .unwrap_or(None);
+        get_transcript_by_session(&state.db, &state.encryption, id).await?;
+    let audio_record = AudioRecording::get_by_session(&state.db, id).await?;
     let mut transcript = payload.transcript.clone();
 
     if transcript.is_empty() {
@@ -73,28 +87,41 @@ pub async fn finalize_session(
         }
     }
 
-    let audio_url = payload
-        .audio_url
-        .clone()
-        .or_else(|| audio_record.as_ref().map(|a| a.storage_url.clone()));
+    // Only this session's own recording is ever fetched; a client-supplied URL could point at
+    // any object in the bucket.
+    let audio_url = audio_record.as_ref().map(|a| a.storage_url.clone());
+    if payload.audio_url.is_some() && payload.audio_url != audio_url {
+        telemetry::log_failure("finalize_foreign_audio", Some(id), "audio_url mismatch");
+        return Err(ApiError::Validation(
+            "audio_url must be this session's recording".into(),
+        ));
+    }
 
     if transcript.is_empty() {
         if let Some(url) = audio_url.as_ref() {
             match transcription::transcribe_audio_from_url(
                 &state.storage,
                 url,
+                session.user_id,
                 payload.duration_seconds,
+                Some(&session.language),
             )
             .await
             {
-                Ok(segments) => transcript = segments,
+                Ok(mut segments) => {
+                    transcript_merge::shift_segments(
+                        &mut segments,
+                        payload.audio_offset_ms.unwrap_or_default(),
+                    );
+                    transcript = segments;
+                }
                 Err(err) => {
                     telemetry::log_failure(
                         "finalize_transcription_failed",
                         Some(id),
                         &format!("{:?}", err),
                     );
-                    return Err(StatusCode::BAD_REQUEST);
+                    return Err(ApiError::Upstream(err));
                 }
             }
         } else {
@@ -103,10 +130,18 @@ pub async fn finalize_session(
                 Some(id),
                 "no transcript and no audio_url provided",
             );
-            return Err(StatusCode::BAD_REQUEST);
+            return Err(ApiError::BadRequest(
+                "a transcript or an audio recording is required".into(),
+            ));
         }
     }
 
+    let transcript = transcript_merge::merge_transcripts(
+        &transcript,
+        &payload.coach_turns,
+        &MergeOptions::default(),
+    );
+
     let chosen_status = if session.status == "active" {
         payload.status.clone()
     } else {
@@ -130,7 +165,7 @@ pub async fn finalize_session(
             Some(id),
             &format!("finalize failed: {:?}", err),
         );
-        return Err(StatusCode::BAD_REQUEST);
+        return Err(err.into());
     }
 
     let mut should_persist = true;
@@ -149,24 +184,46 @@ pub async fn finalize_session(
     }
 
     if should_persist {
-        if let Err(err) = upsert_transcript(&state.db, id, true, &transcript).await {
+        if let Err(err) =
+            upsert_transcript(&state.db, &state.encryption, id, true, &transcript).await
+        {
             telemetry::log_failure(
                 "finalize_transcript_persist_failed",
                 Some(id),
                 &format!("{:?}", err),
             );
-            return Err(StatusCode::BAD_REQUEST);
+            return Err(err.into());
+        }
+        if let Err(err) = redaction::store_redacted_view(&state.db, &state.encryption, id).await {
+            telemetry::log_failure("finalize_redaction_failed", Some(id), &format!("{:?}", err));
         }
     }
 
+    if let Err(err) = curricula::record_completions(&state.db, user_id).await {
+        telemetry::log_failure(
+            "finalize_curriculum_progress_failed",
+            Some(id),
+            &format!("{:?}", err),
+        );
+    }
+
     info!("finalized session {}", id);
+    audit::record(
+        &state.db,
+        &client,
+        Some(user_id),
+        audit::SESSION_FINALIZED,
+        Target::Session(id),
+        json!({ "status": chosen_status, "duration_seconds": duration_seconds }),
+    )
+    .await;
     Ok((
         StatusCode::OK,
         Json(FinalizeResponse {
             session_id: id,
             status: chosen_status,
             transcript,
-            audio_url,
+            audio_url: history::playable_audio_url(&state.encryption, id, audio_url),
             duration_seconds,
         }),
     ))
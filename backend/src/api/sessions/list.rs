@@ -1,26 +1,79 @@
-use axum::extract::State;
-use axum::http::StatusCode;
+use axum::extract::{Query, State};
 use axum::response::IntoResponse;
 use axum::Json;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use uuid::Uuid;
 
+use crate::api::error::ApiError;
 use crate::auth::CurrentUser;
-use crate::services::history::{list_sessions_for_user, SessionListItem};
+use crate::services::history::{
+    list_sessions_for_user, HistoryCursor, HistoryFilter, HistoryPageRequest, HistorySort,
+    SessionListItem, DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE,
+};
 use crate::state::SharedState;
 
+#[derive(Deserialize)]
+pub struct SessionsListParams {
+    pub cursor: Option<String>,
+    pub limit: Option<i64>,
+    #[serde(default)]
+    pub sort: HistorySort,
+    pub topic_id: Option<Uuid>,
+    pub status: Option<String>,
+    pub difficulty: Option<String>,
+    pub language: Option<String>,
+    pub mode: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub has_audio: Option<bool>,
+    pub has_transcript: Option<bool>,
+}
+
 #[derive(serde::Serialize)]
 pub struct SessionsListResponse {
     pub sessions: Vec<SessionListItem>,
+    pub next_cursor: Option<String>,
+    pub total: i64,
 }
 
 pub async fn list_sessions(
     State(state): State<SharedState>,
     CurrentUser(user_id): CurrentUser,
-) -> Result<impl IntoResponse, StatusCode> {
-    match list_sessions_for_user(&state.db, user_id).await {
-        Ok(sessions) => Ok(Json(SessionsListResponse { sessions })),
-        Err(err) => {
-            eprintln!("failed to list sessions: {:?}", err);
-            Err(StatusCode::BAD_REQUEST)
-        }
-    }
+    Query(params): Query<SessionsListParams>,
+) -> Result<impl IntoResponse, ApiError> {
+    let cursor = match params.cursor.as_deref() {
+        Some(raw) => match HistoryCursor::decode(raw, params.sort) {
+            Ok(cursor) => Some(cursor),
+            Err(err) => return Err(ApiError::BadRequest(format!("invalid cursor: {}", err))),
+        },
+        None => None,
+    };
+
+    let page = HistoryPageRequest {
+        filter: HistoryFilter {
+            topic_id: params.topic_id,
+            status: params.status,
+            difficulty: params.difficulty,
+            language: params.language,
+            mode: params.mode,
+            from: params.from,
+            to: params.to,
+            has_audio: params.has_audio,
+            has_transcript: params.has_transcript,
+        },
+        sort: params.sort,
+        cursor,
+        limit: params
+            .limit
+            .unwrap_or(DEFAULT_PAGE_SIZE)
+            .clamp(1, MAX_PAGE_SIZE),
+    };
+
+    let page = list_sessions_for_user(&state.db, &state.encryption, user_id, &page).await?;
+    Ok(Json(SessionsListResponse {
+        sessions: page.sessions,
+        next_cursor: page.next_cursor,
+        total: page.total,
+    }))
 }
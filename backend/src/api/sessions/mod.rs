@@ -1,26 +1,57 @@
-use axum::routing::{get, post};
+use axum::routing::{delete, get, post};
 use axum::Router;
 
 use crate::state::SharedState;
 
+use self::audio::session_audio;
 use self::create::create_session;
 use self::delete::delete_session;
 use self::detail::session_detail;
 use self::finalize::finalize_session;
 use self::list::list_sessions;
+use self::restore::restore_session;
+use self::revisions::{diff_revisions, get_revision, list_revisions, restore_revision};
+use self::search::search_sessions;
+use self::shares::{create_share, list_shares, revoke_share};
+use self::transcript::{edit_transcript, export_transcript};
 use self::upload::upload_audio;
 
+mod audio;
 mod create;
 mod delete;
 mod detail;
 mod finalize;
 mod list;
+mod restore;
+mod revisions;
+mod search;
+mod shares;
+mod transcript;
 mod upload;
 
 pub fn sessions_router() -> Router<SharedState> {
     Router::new()
         .route("/sessions", post(create_session).get(list_sessions))
+        .route("/sessions/search", get(search_sessions))
         .route("/sessions/:id", get(session_detail).delete(delete_session))
+        .route("/sessions/:id/audio", get(session_audio))
         .route("/sessions/:id/finalize", post(finalize_session))
+        .route("/sessions/:id/restore", post(restore_session))
+        .route("/sessions/:id/shares", post(create_share).get(list_shares))
+        .route("/sessions/:id/shares/:share_id", delete(revoke_share))
+        .route(
+            "/sessions/:id/transcript",
+            get(export_transcript).patch(edit_transcript),
+        )
+        .route("/sessions/:id/transcript/diff", get(diff_revisions))
+        .route("/sessions/:id/transcript/revisions", get(list_revisions))
+        .route(
+            "/sessions/:id/transcript/revisions/:revision",
+            get(get_revision),
+        )
+        .route(
+            "/sessions/:id/transcript/revisions/:revision/restore",
+            post(restore_revision),
+        )
         .route("/sessions/:id/upload", post(upload_audio))
 }
@@ -0,0 +1,18 @@
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use uuid::Uuid;
+
+use crate::api::error::ApiError;
+use crate::auth::CurrentUser;
+use crate::services::deletion;
+use crate::state::SharedState;
+
+pub async fn restore_session(
+    State(state): State<SharedState>,
+    CurrentUser(user_id): CurrentUser,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+    deletion::restore_session(&state.db, id, user_id, deletion::restore_window()).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,114 @@
+use axum::extract::{Json, Path, Query, State};
+use axum::http::{header, HeaderMap};
+use axum::response::IntoResponse;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::api::error::ApiError;
+use crate::auth::CurrentUser;
+use crate::services::transcript_edits::{self, SegmentChange};
+use crate::state::SharedState;
+
+use super::transcript::{expected_revision, missing_revision, revision_etag};
+
+#[derive(Deserialize)]
+pub struct DiffParams {
+    pub from: i32,
+    pub to: Option<i32>,
+}
+
+#[derive(Serialize)]
+pub struct TranscriptDiffResponse {
+    pub from: i32,
+    pub to: i32,
+    pub changes: Vec<SegmentChange>,
+}
+
+#[derive(Deserialize, Default)]
+pub struct RestoreRequest {
+    pub base_revision: Option<i32>,
+}
+
+pub async fn list_revisions(
+    State(state): State<SharedState>,
+    CurrentUser(user_id): CurrentUser,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+    let history =
+        transcript_edits::revision_history(&state.db, &state.encryption, id, user_id).await?;
+    Ok((
+        [(header::ETAG, revision_etag(history.current_revision))],
+        Json(history),
+    ))
+}
+
+pub async fn get_revision(
+    State(state): State<SharedState>,
+    CurrentUser(user_id): CurrentUser,
+    Path((id, revision)): Path<(Uuid, i32)>,
+) -> Result<impl IntoResponse, ApiError> {
+    let transcript = transcript_edits::transcript_at_revision(
+        &state.db,
+        &state.encryption,
+        id,
+        user_id,
+        Some(revision),
+    )
+    .await?;
+    Ok(Json(transcript))
+}
+
+pub async fn diff_revisions(
+    State(state): State<SharedState>,
+    CurrentUser(user_id): CurrentUser,
+    Path(id): Path<Uuid>,
+    Query(params): Query<DiffParams>,
+) -> Result<impl IntoResponse, ApiError> {
+    let from = transcript_edits::transcript_at_revision(
+        &state.db,
+        &state.encryption,
+        id,
+        user_id,
+        Some(params.from),
+    );
+    let to = transcript_edits::transcript_at_revision(
+        &state.db,
+        &state.encryption,
+        id,
+        user_id,
+        params.to,
+    );
+    let (from, to) = tokio::try_join!(from, to)?;
+    Ok(Json(TranscriptDiffResponse {
+        from: from.revision,
+        to: to.revision,
+        changes: transcript_edits::diff_segments(&from.segments, &to.segments),
+    }))
+}
+
+pub async fn restore_revision(
+    State(state): State<SharedState>,
+    CurrentUser(user_id): CurrentUser,
+    Path((id, revision)): Path<(Uuid, i32)>,
+    headers: HeaderMap,
+    payload: Option<Json<RestoreRequest>>,
+) -> Result<impl IntoResponse, ApiError> {
+    let Json(payload) = payload.unwrap_or_default();
+    let Some(expected) = expected_revision(&headers, payload.base_revision) else {
+        return Err(missing_revision());
+    };
+
+    let restored = transcript_edits::restore_revision(
+        &state.db,
+        &state.encryption,
+        id,
+        user_id,
+        expected,
+        revision,
+    )
+    .await?;
+    Ok((
+        [(header::ETAG, revision_etag(restored.revision))],
+        Json(restored),
+    ))
+}
@@ -0,0 +1,51 @@
+use axum::extract::{Query, State};
+use axum::response::IntoResponse;
+use axum::Json;
+use serde::Deserialize;
+
+use crate::api::error::ApiError;
+use crate::auth::CurrentUser;
+use crate::services::search::{
+    search_transcripts_for_user, SessionSearchResult, DEFAULT_SEARCH_LIMIT, MAX_SEARCH_LIMIT,
+};
+use crate::state::SharedState;
+
+#[derive(Deserialize)]
+pub struct SearchParams {
+    #[serde(default)]
+    pub q: String,
+    pub limit: Option<i64>,
+}
+
+#[derive(serde::Serialize)]
+pub struct SessionSearchResponse {
+    pub query: String,
+    pub results: Vec<SessionSearchResult>,
+}
+
+pub async fn search_sessions(
+    State(state): State<SharedState>,
+    CurrentUser(user_id): CurrentUser,
+    Query(params): Query<SearchParams>,
+) -> Result<impl IntoResponse, ApiError> {
+    // Sealed transcripts leave nothing for the database to match against, so an empty result
+    // would be a lie rather than a miss.
+    if state.encryption.seals_transcripts() {
+        return Err(ApiError::NotImplemented(
+            "transcript search is unavailable while transcripts are encrypted".into(),
+        ));
+    }
+    let query = params.q.trim().to_string();
+    if query.is_empty() {
+        return Err(ApiError::BadRequest(
+            "search query must not be empty".into(),
+        ));
+    }
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_SEARCH_LIMIT)
+        .clamp(1, MAX_SEARCH_LIMIT);
+
+    let results = search_transcripts_for_user(&state.db, user_id, &query, limit).await?;
+    Ok(Json(SessionSearchResponse { query, results }))
+}
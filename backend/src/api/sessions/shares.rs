@@ -0,0 +1,66 @@
+use axum::extract::{Json, Path, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::api::error::ApiError;
+use crate::auth::{ClientInfo, CurrentUser};
+use crate::services::audit::{self, Target};
+use crate::services::sharing::{self, ShareScope};
+use crate::state::SharedState;
+
+#[derive(Deserialize)]
+pub struct CreateShareRequest {
+    #[serde(default)]
+    pub scope: ShareScope,
+    pub expires_in_hours: Option<i64>,
+}
+
+pub async fn create_share(
+    State(state): State<SharedState>,
+    CurrentUser(user_id): CurrentUser,
+    client: ClientInfo,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<CreateShareRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let share = sharing::create_share(
+        &state.db,
+        id,
+        user_id,
+        payload.scope,
+        payload.expires_in_hours,
+    )
+    .await?;
+    audit::record(
+        &state.db,
+        &client,
+        Some(user_id),
+        audit::SHARE_CREATED,
+        Target::Share(share.id),
+        json!({ "session_id": id, "scope": share.scope, "expires_at": share.expires_at }),
+    )
+    .await;
+    Ok((StatusCode::CREATED, Json(share)))
+}
+
+pub async fn list_shares(
+    State(state): State<SharedState>,
+    CurrentUser(user_id): CurrentUser,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+    sharing::list_shares(&state.db, id, user_id)
+        .await
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+pub async fn revoke_share(
+    State(state): State<SharedState>,
+    CurrentUser(user_id): CurrentUser,
+    Path((id, share_id)): Path<(Uuid, Uuid)>,
+) -> Result<impl IntoResponse, ApiError> {
+    sharing::revoke_share(&state.db, id, user_id, share_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,116 @@
+use axum::extract::{Json, Path, Query, State};
+use axum::http::{header, HeaderMap, HeaderValue};
+use axum::response::IntoResponse;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::api::error::ApiError;
+use crate::auth::CurrentUser;
+use crate::services::history::session_detail_for_user;
+use crate::services::transcript_edits::{self, SegmentEdit};
+use crate::services::transcript_export::{self, ExportFormat, TranscriptDocument};
+use crate::state::SharedState;
+
+#[derive(Deserialize)]
+pub struct TranscriptEditRequest {
+    pub base_revision: Option<i32>,
+    pub edits: Vec<SegmentEdit>,
+}
+
+pub(super) fn revision_etag(revision: i32) -> String {
+    format!("\"{}\"", revision)
+}
+
+// The expected revision comes from `If-Match` (the ETag handed out with the transcript) or,
+// for clients that cannot set headers, from `base_revision` in the body.
+// Sessions without a transcript have nothing to edit, so they get no ETag.
+pub(super) fn etag_headers(revision: Option<i32>) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    if let Some(value) = revision.and_then(|rev| HeaderValue::from_str(&revision_etag(rev)).ok()) {
+        headers.insert(header::ETAG, value);
+    }
+    headers
+}
+
+pub(super) fn missing_revision() -> ApiError {
+    ApiError::PreconditionRequired("send If-Match or base_revision".into())
+}
+
+pub(super) fn expected_revision(headers: &HeaderMap, body_revision: Option<i32>) -> Option<i32> {
+    headers
+        .get(header::IF_MATCH)
+        .and_then(|h| h.to_str().ok())
+        .and_then(|raw| {
+            raw.trim()
+                .trim_start_matches("W/")
+                .trim_matches('"')
+                .parse::<i32>()
+                .ok()
+        })
+        .or(body_revision)
+}
+
+pub async fn edit_transcript(
+    State(state): State<SharedState>,
+    CurrentUser(user_id): CurrentUser,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+    Json(payload): Json<TranscriptEditRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let Some(expected) = expected_revision(&headers, payload.base_revision) else {
+        return Err(missing_revision());
+    };
+
+    let updated = transcript_edits::edit_transcript(
+        &state.db,
+        &state.encryption,
+        id,
+        user_id,
+        expected,
+        &payload.edits,
+    )
+    .await?;
+    Ok((
+        [(header::ETAG, revision_etag(updated.revision))],
+        Json(updated),
+    ))
+}
+
+#[derive(Deserialize)]
+pub struct TranscriptExportParams {
+    #[serde(default)]
+    pub format: ExportFormat,
+}
+
+pub async fn export_transcript(
+    State(state): State<SharedState>,
+    CurrentUser(user_id): CurrentUser,
+    Path(id): Path<Uuid>,
+    Query(params): Query<TranscriptExportParams>,
+) -> Result<impl IntoResponse, ApiError> {
+    let session = session_detail_for_user(&state.db, &state.encryption, id, user_id).await?;
+
+    let doc = TranscriptDocument {
+        topic_title: &session.topic_title,
+        start_time: session.start_time,
+        segments: &session.transcript,
+    };
+    let body = transcript_export::render(params.format, &doc).map_err(ApiError::Internal)?;
+
+    let disposition = transcript_export::content_disposition(
+        &session.topic_title,
+        session.start_time,
+        params.format,
+    );
+    Ok((
+        etag_headers(session.transcript_revision),
+        [
+            (
+                header::CONTENT_TYPE,
+                params.format.content_type().to_string(),
+            ),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        body,
+    ))
+}
@@ -1,17 +1,24 @@
 use axum::extract::{Multipart, Path, State};
 use axum::http::StatusCode;
 use axum::response::IntoResponse;
+use serde_json::json;
 use tracing::info;
 use uuid::Uuid;
 
+use crate::api::error::ApiError;
+use crate::auth::{ClientInfo, CurrentUser};
 use crate::models::audio_recording::{AudioRecording, NewAudioRecording};
+use crate::models::session::Session;
+use crate::services::audit::{self, Target};
 use crate::state::SharedState;
 
 pub async fn upload_audio(
     State(state): State<SharedState>,
+    user: Option<CurrentUser>,
+    client: ClientInfo,
     Path(id): Path<Uuid>,
     mut multipart: Multipart,
-) -> impl IntoResponse {
+) -> Result<impl IntoResponse, ApiError> {
     let mut content: Option<Vec<u8>> = None;
     let mut filename = format!("{}.webm", id);
     let mut mime: Option<String> = None;
@@ -38,27 +45,25 @@ pub async fn upload_audio(
 
     let bytes = match content {
         Some(c) if !c.is_empty() => c,
-        _ => {
-            eprintln!("upload failed: missing or empty file field");
-            return StatusCode::BAD_REQUEST.into_response();
-        }
+        _ => return Err(ApiError::BadRequest("missing or empty file field".into())),
     };
 
+    // Recordings are sealed with the session owner's key, whoever uploads them.
+    let session = Session::get(&state.db, id).await?;
+    if session.deleted_at.is_some() {
+        return Err(ApiError::NotFound("session not found".into()));
+    }
+
+    let size = bytes.len();
     let key = format!("sessions/{}/{}", id, filename);
     info!("uploading audio for session {}", id);
-    let url = match state
+    let url = state
         .storage
-        .upload_bytes(&key, bytes, mime.as_deref())
+        .upload_bytes(&key, bytes, mime.as_deref(), Some(session.user_id))
         .await
-    {
-        Ok(u) => u,
-        Err(err) => {
-            eprintln!("upload failed (storage): {:?}", err);
-            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
-        }
-    };
+        .map_err(ApiError::Upstream)?;
 
-    let record = AudioRecording::insert(
+    let rec = AudioRecording::insert(
         &state.db,
         NewAudioRecording {
             session_id: id,
@@ -69,13 +74,15 @@ pub async fn upload_audio(
             quality_status: None,
         },
     )
+    .await?;
+    audit::record(
+        &state.db,
+        &client,
+        user.map(|CurrentUser(user_id)| user_id),
+        audit::AUDIO_UPLOADED,
+        Target::Session(id),
+        json!({ "recording_id": rec.id, "size_bytes": size }),
+    )
     .await;
-
-    match record {
-        Ok(rec) => (StatusCode::OK, axum::Json(rec)).into_response(),
-        Err(err) => {
-            eprintln!("audio record insert failed: {:?}", err);
-            StatusCode::BAD_REQUEST.into_response()
-        }
-    }
+    Ok((StatusCode::OK, axum::Json(rec)))
 }
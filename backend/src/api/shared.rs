@@ -0,0 +1,52 @@
+use axum::extract::{Path, State};
+use axum::http::{header, StatusCode};
+use axum::response::IntoResponse;
+use axum::routing::get;
+use axum::{Json, Router};
+use tracing::error;
+
+use crate::auth::ClientInfo;
+use crate::services::sharing::{self, ShareError};
+use crate::state::SharedState;
+
+pub fn shared_router() -> Router<SharedState> {
+    Router::new()
+        .route("/shared/:token", get(view_shared))
+        .route("/shared/:token/audio", get(shared_audio))
+}
+
+// Public: no CurrentUser. Unknown, revoked and expired links all look the same.
+pub async fn view_shared(
+    State(state): State<SharedState>,
+    client: ClientInfo,
+    Path(token): Path<String>,
+) -> Result<impl IntoResponse, StatusCode> {
+    match sharing::view_shared(&state.db, &state.storage, &client, &token).await {
+        Ok(shared) => Ok(Json(shared)),
+        Err(ShareError::NotFound) => Err(StatusCode::NOT_FOUND),
+        Err(err) => {
+            error!("shared view failed: {:?}", err);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn shared_audio(
+    State(state): State<SharedState>,
+    client: ClientInfo,
+    Path(token): Path<String>,
+) -> Result<impl IntoResponse, StatusCode> {
+    match sharing::shared_audio(&state.db, &state.storage, &client, &token).await {
+        Ok(audio) => {
+            let mime = audio
+                .mime_type
+                .unwrap_or_else(|| "application/octet-stream".to_string());
+            Ok(([(header::CONTENT_TYPE, mime)], audio.bytes))
+        }
+        Err(ShareError::NotFound) => Err(StatusCode::NOT_FOUND),
+        Err(err) => {
+            error!("shared audio failed: {:?}", err);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use axum::extract::{Query, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::IntoResponse;
+use axum::Json;
+use serde::Deserialize;
+use tracing::error;
+
+use crate::auth::CurrentUser;
+use crate::models::topic::{Topic, TopicFilter};
+use crate::models::topic_translation::localize_topics;
+use crate::services::locale;
+use crate::state::SharedState;
+
+pub const DEFAULT_TOPIC_PAGE_SIZE: i64 = 50;
+pub const MAX_TOPIC_PAGE_SIZE: i64 = 100;
+
+#[derive(Deserialize)]
+pub struct TopicListParams {
+    pub difficulty: Option<String>,
+    pub category: Option<String>,
+    pub tag: Option<String>,
+    pub skill: Option<String>,
+    pub q: Option<String>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+    pub locale: Option<String>,
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_lowercase())
+        .filter(|v| !v.is_empty())
+}
+
+// The body stays a bare array for existing clients; the total for pagination is sent in
+// `x-total-count`. Signed-in users also see their private topics and their organization's
+// catalog.
+pub async fn list_topics(
+    State(state): State<SharedState>,
+    user: Option<CurrentUser>,
+    headers: HeaderMap,
+    Query(params): Query<TopicListParams>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let locale = locale::negotiate(
+        params.locale.as_deref(),
+        headers
+            .get(header::ACCEPT_LANGUAGE)
+            .and_then(|v| v.to_str().ok()),
+    );
+    let filter = TopicFilter {
+        difficulty: non_empty(params.difficulty),
+        category: non_empty(params.category),
+        tag: non_empty(params.tag),
+        target_skill: non_empty(params.skill),
+        query: params
+            .q
+            .map(|q| q.trim().to_string())
+            .filter(|q| !q.is_empty()),
+        viewer: user.map(|CurrentUser(user_id)| user_id),
+    };
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_TOPIC_PAGE_SIZE)
+        .clamp(1, MAX_TOPIC_PAGE_SIZE);
+    let offset = params.offset.unwrap_or_default().max(0);
+
+    let (mut topics, total) = match Topic::search(&state.db, &filter, limit, offset).await {
+        Ok(page) => page,
+        Err(err) => {
+            error!("failed to list topics: {:?}", err);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+    if let Err(err) = localize_topics(&state.db, &mut topics, locale).await {
+        error!("failed to localize topics: {:?}", err);
+        return Err(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    Ok((
+        [
+            ("x-total-count", total.to_string()),
+            (header::CONTENT_LANGUAGE.as_str(), locale.to_string()),
+        ],
+        Json(topics),
+    ))
+}
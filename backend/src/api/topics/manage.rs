@@ -0,0 +1,106 @@
+use axum::extract::{Json, Path, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use serde::{Deserialize, Deserializer};
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::auth::AdminUser;
+use crate::models::topic::{NewTopic, TopicUpdate};
+use crate::services::topics::{self, TopicError};
+use crate::state::SharedState;
+
+// Distinguishes an absent field (`None`) from an explicit `null` (`Some(None)`).
+fn nullable<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+#[derive(Deserialize)]
+pub struct UpdateTopicRequest {
+    pub title: Option<String>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub difficulty: Option<Option<String>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub prompt_hint: Option<Option<String>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub category: Option<Option<String>>,
+    pub tags: Option<Vec<String>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub target_skill: Option<Option<String>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub estimated_duration_seconds: Option<Option<i32>>,
+    pub archived: Option<bool>,
+}
+
+impl From<UpdateTopicRequest> for TopicUpdate {
+    fn from(payload: UpdateTopicRequest) -> Self {
+        TopicUpdate {
+            title: payload.title,
+            difficulty: payload.difficulty,
+            prompt_hint: payload.prompt_hint,
+            category: payload.category,
+            tags: payload.tags,
+            target_skill: payload.target_skill,
+            estimated_duration_seconds: payload.estimated_duration_seconds,
+            archived: payload.archived,
+        }
+    }
+}
+
+pub(super) fn topic_error_response(err: TopicError) -> (StatusCode, String) {
+    let status = match &err {
+        TopicError::NotFound => StatusCode::NOT_FOUND,
+        TopicError::DuplicateTitle => StatusCode::CONFLICT,
+        TopicError::Invalid(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        TopicError::LimitReached(_) => StatusCode::CONFLICT,
+        TopicError::Other(inner) => {
+            error!("topic write failed: {:?}", inner);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "topic write failed".into(),
+            );
+        }
+    };
+    (status, err.to_string())
+}
+
+pub async fn create_topic(
+    State(state): State<SharedState>,
+    AdminUser(admin_id): AdminUser,
+    Json(payload): Json<NewTopic>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let topic = topics::create_topic(&state.db, &payload)
+        .await
+        .map_err(topic_error_response)?;
+    info!("admin {} created topic {}", admin_id, topic.id);
+    Ok((StatusCode::CREATED, Json(topic)))
+}
+
+pub async fn update_topic(
+    State(state): State<SharedState>,
+    AdminUser(admin_id): AdminUser,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<UpdateTopicRequest>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let topic = topics::update_topic(&state.db, id, &payload.into())
+        .await
+        .map_err(topic_error_response)?;
+    info!("admin {} updated topic {}", admin_id, topic.id);
+    Ok(Json(topic))
+}
+
+pub async fn archive_topic(
+    State(state): State<SharedState>,
+    AdminUser(admin_id): AdminUser,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    topics::archive_topic(&state.db, id)
+        .await
+        .map_err(topic_error_response)?;
+    info!("admin {} archived topic {}", admin_id, id);
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,37 @@
+use axum::routing::{get, patch, put};
+use axum::Router;
+
+use crate::state::SharedState;
+
+use self::list::list_topics;
+use self::manage::{archive_topic, create_topic, update_topic};
+use self::private::{
+    create_private_topic, delete_private_topic, list_private_topics, update_private_topic,
+};
+use self::recommended::recommended_topics;
+use self::translations::{delete_translation, put_translation};
+
+mod list;
+mod manage;
+mod private;
+mod recommended;
+mod translations;
+
+pub fn topics_router() -> Router<SharedState> {
+    Router::new()
+        .route("/topics", get(list_topics).post(create_topic))
+        .route("/topics/recommended", get(recommended_topics))
+        .route(
+            "/topics/private",
+            get(list_private_topics).post(create_private_topic),
+        )
+        .route(
+            "/topics/private/:id",
+            patch(update_private_topic).delete(delete_private_topic),
+        )
+        .route("/topics/:id", patch(update_topic).delete(archive_topic))
+        .route(
+            "/topics/:id/translations/:locale",
+            put(put_translation).delete(delete_translation),
+        )
+}
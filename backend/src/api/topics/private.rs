@@ -0,0 +1,60 @@
+use axum::extract::{Json, Path, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use super::manage::{topic_error_response, UpdateTopicRequest};
+use crate::auth::CurrentUser;
+use crate::models::topic::{NewTopic, Topic};
+use crate::services::topics;
+use crate::state::SharedState;
+
+pub async fn list_private_topics(
+    State(state): State<SharedState>,
+    CurrentUser(user_id): CurrentUser,
+) -> Result<impl IntoResponse, StatusCode> {
+    match Topic::list_owned(&state.db, user_id).await {
+        Ok(topics) => Ok(Json(topics)),
+        Err(err) => {
+            error!("failed to list private topics: {:?}", err);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn create_private_topic(
+    State(state): State<SharedState>,
+    CurrentUser(user_id): CurrentUser,
+    Json(payload): Json<NewTopic>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let topic = topics::create_private_topic(&state.db, user_id, &payload)
+        .await
+        .map_err(topic_error_response)?;
+    info!("user {} created private topic {}", user_id, topic.id);
+    Ok((StatusCode::CREATED, Json(topic)))
+}
+
+pub async fn update_private_topic(
+    State(state): State<SharedState>,
+    CurrentUser(user_id): CurrentUser,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<UpdateTopicRequest>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let topic = topics::update_private_topic(&state.db, user_id, id, &payload.into())
+        .await
+        .map_err(topic_error_response)?;
+    Ok(Json(topic))
+}
+
+pub async fn delete_private_topic(
+    State(state): State<SharedState>,
+    CurrentUser(user_id): CurrentUser,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    topics::delete_private_topic(&state.db, user_id, id)
+        .await
+        .map_err(topic_error_response)?;
+    info!("user {} deleted private topic {}", user_id, id);
+    Ok(StatusCode::NO_CONTENT)
+}
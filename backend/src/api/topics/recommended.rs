@@ -0,0 +1,61 @@
+use axum::extract::{Query, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::IntoResponse;
+use axum::Json;
+use serde::Deserialize;
+use tracing::error;
+
+use crate::auth::CurrentUser;
+use crate::models::topic::Topic;
+use crate::models::topic_translation::localize_topics;
+use crate::services::locale;
+use crate::services::recommendations::{
+    recommend_topics, DEFAULT_RECOMMENDATION_LIMIT, MAX_RECOMMENDATION_LIMIT,
+};
+use crate::state::SharedState;
+
+#[derive(Deserialize)]
+pub struct RecommendedParams {
+    pub limit: Option<usize>,
+    pub locale: Option<String>,
+}
+
+pub async fn recommended_topics(
+    State(state): State<SharedState>,
+    CurrentUser(user_id): CurrentUser,
+    headers: HeaderMap,
+    Query(params): Query<RecommendedParams>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_RECOMMENDATION_LIMIT)
+        .clamp(1, MAX_RECOMMENDATION_LIMIT);
+    let locale = locale::negotiate(
+        params.locale.as_deref(),
+        headers
+            .get(header::ACCEPT_LANGUAGE)
+            .and_then(|v| v.to_str().ok()),
+    );
+
+    let mut recommendations = match recommend_topics(&state.db, user_id, limit).await {
+        Ok(recommendations) => recommendations,
+        Err(err) => {
+            error!("failed to recommend topics: {:?}", err);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    let mut topics: Vec<Topic> = recommendations.iter().map(|r| r.topic.clone()).collect();
+    if let Err(err) = localize_topics(&state.db, &mut topics, locale).await {
+        error!("failed to localize topics: {:?}", err);
+        return Err(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+    for (recommendation, topic) in recommendations.iter_mut().zip(topics) {
+        recommendation.topic = topic;
+    }
+
+    Ok((
+        [(header::CONTENT_LANGUAGE, locale.to_string())],
+        Json(recommendations),
+    ))
+}
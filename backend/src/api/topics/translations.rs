@@ -0,0 +1,42 @@
+use axum::extract::{Json, Path, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use tracing::info;
+use uuid::Uuid;
+
+use super::manage::topic_error_response;
+use crate::auth::AdminUser;
+use crate::models::topic_translation::NewTopicTranslation;
+use crate::services::topics;
+use crate::state::SharedState;
+
+pub async fn put_translation(
+    State(state): State<SharedState>,
+    AdminUser(admin_id): AdminUser,
+    Path((id, locale)): Path<(Uuid, String)>,
+    Json(payload): Json<NewTopicTranslation>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let translation = topics::upsert_translation(&state.db, id, &locale, &payload)
+        .await
+        .map_err(topic_error_response)?;
+    info!(
+        "admin {} set {} translation for topic {}",
+        admin_id, translation.locale, id
+    );
+    Ok(Json(translation))
+}
+
+pub async fn delete_translation(
+    State(state): State<SharedState>,
+    AdminUser(admin_id): AdminUser,
+    Path((id, locale)): Path<(Uuid, String)>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    topics::delete_translation(&state.db, id, &locale)
+        .await
+        .map_err(topic_error_response)?;
+    info!(
+        "admin {} removed {} translation for topic {}",
+        admin_id, locale, id
+    );
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -1,18 +1,64 @@
-use axum::http::StatusCode;
+use axum::extract::ConnectInfo;
+use axum::http::{header, HeaderMap};
 use axum::response::IntoResponse;
 use axum::{async_trait, extract::FromRequestParts};
+use std::convert::Infallible;
 use std::fmt;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::OnceLock;
+use tracing::error;
 use uuid::Uuid;
 
+use crate::api::error::ApiError;
+use crate::models::user_role::{UserRole, ADMIN_ROLE};
+use crate::state::SharedState;
+
 #[derive(Clone, Debug)]
 pub struct CurrentUser(pub Uuid);
 
+#[derive(Clone, Debug)]
+pub struct AdminUser(pub Uuid);
+
+// Where a request came from, for the audit log. Never rejects.
+#[derive(Clone, Debug, Default)]
+pub struct ClientInfo {
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+// Proxies whose forwarding headers are believed, as addresses or CIDR ranges.
+#[derive(Clone, Debug, Default)]
+pub struct TrustedProxies(Vec<(IpAddr, u8)>);
+
+static TRUSTED_PROXIES: OnceLock<TrustedProxies> = OnceLock::new();
+
 #[derive(Debug)]
 pub struct AuthError(pub &'static str);
 
+#[derive(Debug)]
+pub enum RoleError {
+    Unauthenticated(AuthError),
+    Forbidden,
+    Lookup,
+}
+
+impl IntoResponse for RoleError {
+    fn into_response(self) -> axum::response::Response {
+        match self {
+            RoleError::Unauthenticated(err) => err.into_response(),
+            RoleError::Forbidden => {
+                ApiError::Forbidden("admin role required".into()).into_response()
+            }
+            RoleError::Lookup => {
+                ApiError::Internal(anyhow::anyhow!("role lookup failed")).into_response()
+            }
+        }
+    }
+}
+
 impl IntoResponse for AuthError {
     fn into_response(self) -> axum::response::Response {
-        (StatusCode::UNAUTHORIZED, self.0).into_response()
+        ApiError::Unauthorized(self.0.into()).into_response()
     }
 }
 
@@ -45,3 +91,145 @@ where
         Ok(CurrentUser(user_id))
     }
 }
+
+#[async_trait]
+impl FromRequestParts<SharedState> for AdminUser {
+    type Rejection = RoleError;
+
+    async fn from_request_parts(
+        parts: &mut axum::http::request::Parts,
+        state: &SharedState,
+    ) -> Result<Self, Self::Rejection> {
+        let CurrentUser(user_id) = CurrentUser::from_request_parts(parts, state)
+            .await
+            .map_err(RoleError::Unauthenticated)?;
+
+        match UserRole::has_role(&state.db, user_id, ADMIN_ROLE).await {
+            Ok(true) => Ok(AdminUser(user_id)),
+            Ok(false) => Err(RoleError::Forbidden),
+            Err(err) => {
+                error!("role lookup failed: {:?}", err);
+                Err(RoleError::Lookup)
+            }
+        }
+    }
+}
+
+impl TrustedProxies {
+    // Comma-separated entries such as `10.0.0.0/8, 192.168.1.4, fd00::/8`.
+    pub fn parse(raw: &str) -> anyhow::Result<Self> {
+        let mut ranges = Vec::new();
+        for entry in raw.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (addr, prefix) = match entry.split_once('/') {
+                Some((addr, prefix)) => (addr, Some(prefix)),
+                None => (entry, None),
+            };
+            let addr: IpAddr = addr
+                .parse()
+                .map_err(|_| anyhow::anyhow!("invalid proxy address: {}", entry))?;
+            let max = if addr.is_ipv4() { 32 } else { 128 };
+            let prefix = match prefix {
+                Some(prefix) => prefix
+                    .parse::<u8>()
+                    .ok()
+                    .filter(|p| *p <= max)
+                    .ok_or_else(|| anyhow::anyhow!("invalid proxy prefix: {}", entry))?,
+                None => max,
+            };
+            ranges.push((addr.to_canonical(), prefix));
+        }
+        Ok(Self(ranges))
+    }
+
+    // From TRUSTED_PROXIES; unset or invalid trusts no proxy, so only the peer address is used.
+    pub fn from_env() -> Self {
+        let raw = std::env::var("TRUSTED_PROXIES").unwrap_or_default();
+        TrustedProxies::parse(&raw).unwrap_or_else(|err| {
+            error!("ignoring TRUSTED_PROXIES: {:?}", err);
+            TrustedProxies::default()
+        })
+    }
+
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        let ip = ip.to_canonical();
+        self.0.iter().any(|(net, prefix)| match (net, ip) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - u32::from(*prefix)).unwrap_or(0);
+                u32::from(*net) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - u32::from(*prefix)).unwrap_or(0);
+                u128::from(*net) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        })
+    }
+}
+
+// Read from the environment once, on first use.
+pub fn trusted_proxies() -> &'static TrustedProxies {
+    TRUSTED_PROXIES.get_or_init(TrustedProxies::from_env)
+}
+
+impl ClientInfo {
+    // The peer address, unless it is a trusted proxy: then `x-forwarded-for` is walked from the
+    // right and the first hop that is not a trusted proxy is the client. Entries left of it
+    // are supplied by the client and ignored.
+    pub fn from_parts(
+        headers: &HeaderMap,
+        peer: Option<SocketAddr>,
+        trusted: &TrustedProxies,
+    ) -> Self {
+        let ip = peer.map(|addr| {
+            let mut client = addr.ip().to_canonical();
+            if !trusted.contains(client) {
+                return client.to_string();
+            }
+            let forwarded: Vec<&str> = headers
+                .get_all("x-forwarded-for")
+                .iter()
+                .filter_map(|h| h.to_str().ok())
+                .flat_map(|v| v.split(','))
+                .map(str::trim)
+                .collect();
+            for hop in forwarded.into_iter().rev() {
+                let Ok(hop) = hop.parse::<IpAddr>() else {
+                    break;
+                };
+                client = hop.to_canonical();
+                if !trusted.contains(client) {
+                    break;
+                }
+            }
+            client.to_string()
+        });
+        let user_agent = headers
+            .get(header::USER_AGENT)
+            .and_then(|h| h.to_str().ok())
+            .map(str::to_string);
+        Self { ip, user_agent }
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for ClientInfo
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(
+        parts: &mut axum::http::request::Parts,
+        _state: &S,
+    ) -> Result<Self, Self::Rejection> {
+        let peer = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| *addr);
+        Ok(ClientInfo::from_parts(
+            &parts.headers,
+            peer,
+            trusted_proxies(),
+        ))
+    }
+}
@@ -0,0 +1,44 @@
+use backend::encryption::{rewrap_data_keys, Keyring};
+use dotenvy::dotenv;
+use sqlx::postgres::PgPoolOptions;
+use std::time::Duration;
+use tracing::info;
+
+const USAGE: &str = "usage: rotate_keys
+
+Re-wraps every user data key with ENCRYPTION_MASTER_KEY. Keys wrapped by an older
+master key are unwrapped with the matching entry in ENCRYPTION_RETIRED_KEYS, which
+can be dropped from the configuration once this has run.";
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    dotenv().ok();
+    backend::telemetry::init_tracing();
+    if let Some(arg) = std::env::args().nth(1) {
+        if arg == "-h" || arg == "--help" {
+            println!("{}", USAGE);
+            return Ok(());
+        }
+        anyhow::bail!("unexpected argument {}\n{}", arg, USAGE);
+    }
+    let keyring = Keyring::from_env()?
+        .ok_or_else(|| anyhow::anyhow!("ENCRYPTION_MASTER_KEY must be set\n{}", USAGE))?;
+
+    let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .acquire_timeout(Duration::from_secs(5))
+        .connect(&database_url)
+        .await?;
+
+    sqlx::migrate!("./migrations").run(&pool).await?;
+
+    let rewrapped = rewrap_data_keys(&pool, &keyring).await?;
+    println!(
+        "{} data keys re-wrapped with master key {}",
+        rewrapped,
+        keyring.current_id()
+    );
+    info!("Key rotation complete");
+    Ok(())
+}
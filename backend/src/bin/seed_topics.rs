@@ -1,14 +1,76 @@
-use backend::models::topic::{NewTopic, Topic};
+use backend::models::topic::Topic;
+use backend::services::topic_catalog::{
+    apply_catalog, parse_catalog, plan_catalog, render_catalog, CatalogFormat, SeedMode,
+};
 use dotenvy::dotenv;
 use sqlx::postgres::PgPoolOptions;
 use std::fs;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 use tracing::info;
 
+const USAGE: &str = "usage: seed_topics [FILE] [--format json|yaml|csv] [--mode upsert|insert] [--dry-run] [--prune]
+       seed_topics --export FILE|- [--format json|yaml|csv]";
+
+struct Args {
+    file: Option<PathBuf>,
+    format: Option<CatalogFormat>,
+    mode: SeedMode,
+    dry_run: bool,
+    prune: bool,
+    export: Option<String>,
+}
+
+fn parse_args() -> anyhow::Result<Args> {
+    let mut args = Args {
+        file: None,
+        format: None,
+        mode: SeedMode::Upsert,
+        dry_run: false,
+        prune: false,
+        export: None,
+    };
+    let mut iter = std::env::args().skip(1);
+    while let Some(arg) = iter.next() {
+        let mut value = |name: &str| {
+            iter.next()
+                .ok_or_else(|| anyhow::anyhow!("{} needs a value\n{}", name, USAGE))
+        };
+        match arg.as_str() {
+            "--format" => args.format = Some(value("--format")?.parse()?),
+            "--mode" => {
+                args.mode = match value("--mode")?.as_str() {
+                    "upsert" => SeedMode::Upsert,
+                    "insert" => SeedMode::Insert,
+                    other => anyhow::bail!("unknown mode {:?}\n{}", other, USAGE),
+                }
+            }
+            "--dry-run" => args.dry_run = true,
+            "--prune" => args.prune = true,
+            "--export" => args.export = Some(value("--export")?),
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                std::process::exit(0);
+            }
+            flag if flag.starts_with("--") => anyhow::bail!("unknown flag {}\n{}", flag, USAGE),
+            _ if args.file.is_none() => args.file = Some(PathBuf::from(arg)),
+            _ => anyhow::bail!("unexpected argument {}\n{}", arg, USAGE),
+        }
+    }
+    Ok(args)
+}
+
+fn format_for(path: &Path, explicit: Option<CatalogFormat>) -> anyhow::Result<CatalogFormat> {
+    explicit
+        .or_else(|| CatalogFormat::from_path(path))
+        .ok_or_else(|| anyhow::anyhow!("cannot infer format of {}; pass --format", path.display()))
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     dotenv().ok();
     backend::telemetry::init_tracing();
+    let args = parse_args()?;
 
     let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
     let pool = PgPoolOptions::new()
@@ -19,14 +81,59 @@ async fn main() -> anyhow::Result<()> {
 
     sqlx::migrate!("./migrations").run(&pool).await?;
 
+    if let Some(target) = args.export.as_deref() {
+        let topics = Topic::list_all(&pool).await?;
+        if target == "-" {
+            let output = render_catalog(&topics, args.format.unwrap_or(CatalogFormat::Json))?;
+            print!("{}", output);
+        } else {
+            let output = render_catalog(&topics, format_for(Path::new(target), args.format)?)?;
+            fs::write(target, output)?;
+            info!("Exported topic catalog to {}", target);
+        }
+        return Ok(());
+    }
+
     let default_fixture = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/topics.json");
-    let fixture_path = std::env::var("TOPICS_FIXTURE").unwrap_or_else(|_| default_fixture.into());
+    let fixture_path = args.file.unwrap_or_else(|| {
+        std::env::var("TOPICS_FIXTURE")
+            .unwrap_or_else(|_| default_fixture.into())
+            .into()
+    });
+    let format = format_for(&fixture_path, args.format)?;
     let data = fs::read_to_string(&fixture_path)
-        .map_err(|e| anyhow::anyhow!("failed to read fixture {}: {}", fixture_path, e))?;
-    let topics: Vec<NewTopic> = serde_json::from_str(&data)?;
+        .map_err(|e| anyhow::anyhow!("failed to read fixture {}: {}", fixture_path.display(), e))?;
+    let topics = match parse_catalog(&data, format) {
+        Ok(topics) => topics,
+        Err(errors) => {
+            for err in &errors {
+                eprintln!("{}: {}", fixture_path.display(), err);
+            }
+            anyhow::bail!("{} invalid topic entries", errors.len());
+        }
+    };
 
-    Topic::insert_many(&pool, &topics).await?;
+    let existing = Topic::list_all(&pool).await?;
+    let plan = plan_catalog(&existing, &topics, args.mode, args.prune);
+    for change in &plan.changes {
+        println!("{}", change);
+    }
+    println!(
+        "{} changes, {} unchanged, {} skipped",
+        plan.changes.len(),
+        plan.unchanged,
+        plan.skipped
+    );
 
-    info!("Seeded {} topics", topics.len());
+    if args.dry_run {
+        info!("Dry run; no changes applied");
+        return Ok(());
+    }
+    apply_catalog(&pool, &plan).await?;
+    info!(
+        "Seeded {} topics from {}",
+        topics.len(),
+        fixture_path.display()
+    );
     Ok(())
 }
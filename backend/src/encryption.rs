@@ -0,0 +1,310 @@
+use std::fmt;
+use std::sync::Arc;
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use anyhow::{anyhow, bail, Context};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::models::user_data_key::UserDataKey;
+
+// Encrypted blobs are MAGIC, the owner's user id, a 96-bit nonce and the AES-256-GCM
+// ciphertext. The magic and owner are authenticated as associated data.
+const MAGIC: &[u8; 8] = b"SPKENC01";
+const HEADER_LEN: usize = MAGIC.len() + 16;
+const NONCE_LEN: usize = 12;
+const KEY_LEN: usize = 32;
+const REWRAP_BATCH: i64 = 500;
+
+// Sealed JSON columns hold {"encrypted": "<base64 blob>"} in place of the plain value.
+const ENCRYPTED_FIELD: &str = "encrypted";
+
+pub struct MasterKey {
+    id: String,
+    cipher: Aes256Gcm,
+}
+
+impl fmt::Debug for MasterKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MasterKey").field("id", &self.id).finish()
+    }
+}
+
+impl MasterKey {
+    pub fn new(id: &str, key: &[u8]) -> anyhow::Result<Self> {
+        let id = id.trim();
+        if id.is_empty() {
+            bail!("master key id must not be empty");
+        }
+        if key.len() != KEY_LEN {
+            bail!("master key {} must be {} bytes", id, KEY_LEN);
+        }
+        Ok(Self {
+            id: id.to_string(),
+            cipher: Aes256Gcm::new_from_slice(key).map_err(|_| anyhow!("invalid master key"))?,
+        })
+    }
+
+    // "<id>:<base64 of 32 bytes>"
+    pub fn parse(raw: &str) -> anyhow::Result<Self> {
+        let (id, encoded) = raw
+            .trim()
+            .split_once(':')
+            .ok_or_else(|| anyhow!("master keys are written as <id>:<base64 key>"))?;
+        let key = STANDARD
+            .decode(encoded.trim())
+            .with_context(|| format!("master key {} is not valid base64", id.trim()))?;
+        MasterKey::new(id, &key)
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    // Data keys are bound to their user so a wrapped key cannot be moved to another row.
+    fn wrap(&self, user_id: Uuid, data_key: &[u8]) -> anyhow::Result<Vec<u8>> {
+        seal(&self.cipher, user_id.as_bytes(), data_key)
+    }
+
+    fn unwrap_key(&self, user_id: Uuid, wrapped: &[u8]) -> anyhow::Result<Vec<u8>> {
+        open(&self.cipher, user_id.as_bytes(), wrapped)
+            .with_context(|| format!("unwrap data key for {} with {}", user_id, self.id))
+    }
+}
+
+fn seal(cipher: &Aes256Gcm, aad: &[u8], plaintext: &[u8]) -> anyhow::Result<Vec<u8>> {
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(
+            &nonce,
+            Payload {
+                msg: plaintext,
+                aad,
+            },
+        )
+        .map_err(|_| anyhow!("encryption failed"))?;
+    let mut out = nonce.to_vec();
+    out.extend_from_slice(&ciphertext);
+    Ok(out)
+}
+
+fn open(cipher: &Aes256Gcm, aad: &[u8], sealed: &[u8]) -> anyhow::Result<Vec<u8>> {
+    if sealed.len() < NONCE_LEN {
+        bail!("ciphertext is truncated");
+    }
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+    let nonce: [u8; NONCE_LEN] = nonce.try_into()?;
+    cipher
+        .decrypt(
+            &Nonce::from(nonce),
+            Payload {
+                msg: ciphertext,
+                aad,
+            },
+        )
+        .map_err(|_| anyhow!("decryption failed: wrong key or tampered data"))
+}
+
+#[derive(Debug)]
+pub struct Keyring {
+    current: MasterKey,
+    // Earlier master keys, kept only to unwrap data keys until rotation re-wraps them.
+    retired: Vec<MasterKey>,
+    pub encrypt_transcripts: bool,
+}
+
+impl Keyring {
+    pub fn new(current: MasterKey, retired: Vec<MasterKey>, encrypt_transcripts: bool) -> Self {
+        Self {
+            current,
+            retired,
+            encrypt_transcripts,
+        }
+    }
+
+    // ENCRYPTION_MASTER_KEY turns encryption on; ENCRYPTION_RETIRED_KEYS (comma separated)
+    // lists the keys it replaced. ENCRYPT_TRANSCRIPTS=true also seals transcript JSON,
+    // which turns off full-text search (the search endpoint answers 501).
+    pub fn from_env() -> anyhow::Result<Option<Self>> {
+        let current = match std::env::var("ENCRYPTION_MASTER_KEY") {
+            Ok(raw) if !raw.trim().is_empty() => MasterKey::parse(&raw)?,
+            _ => return Ok(None),
+        };
+        let retired = std::env::var("ENCRYPTION_RETIRED_KEYS")
+            .unwrap_or_default()
+            .split(',')
+            .filter(|raw| !raw.trim().is_empty())
+            .map(MasterKey::parse)
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        if retired.iter().any(|key| key.id == current.id) {
+            bail!("retired master key reuses the current id {}", current.id);
+        }
+        let encrypt_transcripts = std::env::var("ENCRYPT_TRANSCRIPTS")
+            .map(|raw| matches!(raw.trim(), "1" | "true" | "yes"))
+            .unwrap_or(false);
+        Ok(Some(Keyring::new(current, retired, encrypt_transcripts)))
+    }
+
+    pub fn current_id(&self) -> &str {
+        self.current.id()
+    }
+
+    fn master(&self, id: &str) -> anyhow::Result<&MasterKey> {
+        std::iter::once(&self.current)
+            .chain(&self.retired)
+            .find(|key| key.id == id)
+            .ok_or_else(|| anyhow!("master key {} is not configured", id))
+    }
+}
+
+#[derive(Debug)]
+pub struct KeyStore {
+    pool: PgPool,
+    keyring: Keyring,
+}
+
+// Handle to the key store, carried on AppState and StorageService. Disabled when no master
+// key is configured: audio and transcripts are then written in the clear and reading
+// anything encrypted fails.
+#[derive(Debug, Clone, Default)]
+pub struct Encryption {
+    store: Option<Arc<KeyStore>>,
+}
+
+impl Encryption {
+    pub fn new(pool: PgPool, keyring: Keyring) -> Self {
+        Self {
+            store: Some(Arc::new(KeyStore { pool, keyring })),
+        }
+    }
+
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    pub fn store(&self) -> Option<&KeyStore> {
+        self.store.as_deref()
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.store.is_some()
+    }
+
+    pub fn seals_transcripts(&self) -> bool {
+        self.store()
+            .is_some_and(|store| store.keyring.encrypt_transcripts)
+    }
+
+    // Seals a JSON column value for `owner` when transcript encryption is on.
+    pub async fn seal_json(&self, owner: Uuid, value: Value) -> anyhow::Result<Value> {
+        let Some(store) = self
+            .store()
+            .filter(|store| store.keyring.encrypt_transcripts)
+        else {
+            return Ok(value);
+        };
+        let blob = store.encrypt(owner, &serde_json::to_vec(&value)?).await?;
+        Ok(json!({ ENCRYPTED_FIELD: STANDARD.encode(blob) }))
+    }
+
+    // Plain values pass through, so rows written before encryption was enabled still read.
+    pub async fn open_json(&self, value: Value) -> anyhow::Result<Value> {
+        let Some(encoded) = value.get(ENCRYPTED_FIELD).and_then(Value::as_str) else {
+            return Ok(value);
+        };
+        let store = self
+            .store()
+            .ok_or_else(|| anyhow!("value is encrypted but no master key is configured"))?;
+        let blob = STANDARD
+            .decode(encoded)
+            .context("encrypted value is not base64")?;
+        Ok(serde_json::from_slice(&store.decrypt(&blob).await?)?)
+    }
+}
+
+pub fn encrypted_owner(blob: &[u8]) -> Option<Uuid> {
+    if blob.len() <= HEADER_LEN || !blob.starts_with(MAGIC) {
+        return None;
+    }
+    Uuid::from_slice(&blob[MAGIC.len()..HEADER_LEN]).ok()
+}
+
+impl KeyStore {
+    pub fn keyring(&self) -> &Keyring {
+        &self.keyring
+    }
+
+    async fn data_key(&self, user_id: Uuid, create: bool) -> anyhow::Result<Aes256Gcm> {
+        let stored = match UserDataKey::get(&self.pool, user_id).await? {
+            Some(stored) => stored,
+            None if create => {
+                let fresh = Aes256Gcm::generate_key(&mut OsRng);
+                let wrapped = self.keyring.current.wrap(user_id, &fresh)?;
+                UserDataKey::insert_if_absent(
+                    &self.pool,
+                    user_id,
+                    self.keyring.current_id(),
+                    &wrapped,
+                )
+                .await?
+            }
+            None => bail!("no data key for {}", user_id),
+        };
+        let raw = self
+            .keyring
+            .master(&stored.master_key_id)?
+            .unwrap_key(user_id, &stored.wrapped_key)?;
+        Aes256Gcm::new_from_slice(&raw).map_err(|_| anyhow!("stored data key has the wrong length"))
+    }
+
+    pub async fn encrypt(&self, owner: Uuid, plaintext: &[u8]) -> anyhow::Result<Vec<u8>> {
+        let cipher = self.data_key(owner, true).await?;
+        let mut out = Vec::with_capacity(HEADER_LEN + NONCE_LEN + plaintext.len() + 16);
+        out.extend_from_slice(MAGIC);
+        out.extend_from_slice(owner.as_bytes());
+        let sealed = seal(&cipher, &out, plaintext)?;
+        out.extend_from_slice(&sealed);
+        Ok(out)
+    }
+
+    pub async fn decrypt(&self, blob: &[u8]) -> anyhow::Result<Vec<u8>> {
+        let owner = encrypted_owner(blob).ok_or_else(|| anyhow!("not an encrypted blob"))?;
+        let cipher = self.data_key(owner, false).await?;
+        let (header, sealed) = blob.split_at(HEADER_LEN);
+        open(&cipher, header, sealed)
+    }
+}
+
+// Moves every data key onto the keyring's current master key. Keys wrapped by a master
+// key that is neither current nor retired stop the run.
+pub async fn rewrap_data_keys(pool: &PgPool, keyring: &Keyring) -> anyhow::Result<u64> {
+    let mut rewrapped = 0;
+    loop {
+        let batch =
+            UserDataKey::list_not_wrapped_by(pool, keyring.current_id(), REWRAP_BATCH).await?;
+        if batch.is_empty() {
+            return Ok(rewrapped);
+        }
+        for stored in batch {
+            let raw = keyring
+                .master(&stored.master_key_id)?
+                .unwrap_key(stored.user_id, &stored.wrapped_key)?;
+            let wrapped = keyring.current.wrap(stored.user_id, &raw)?;
+            if UserDataKey::rewrap(
+                pool,
+                stored.user_id,
+                &stored.master_key_id,
+                keyring.current_id(),
+                &wrapped,
+            )
+            .await?
+            {
+                rewrapped += 1;
+            }
+        }
+    }
+}
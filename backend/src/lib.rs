@@ -1,5 +1,6 @@
 pub mod api;
 pub mod auth;
+pub mod encryption;
 pub mod models;
 pub mod services;
 pub mod state;
@@ -1,5 +1,10 @@
 use axum::Router;
 use backend::api;
+use backend::encryption::{Encryption, Keyring};
+use backend::models::user_role::{UserRole, ADMIN_ROLE};
+use backend::services::audit::spawn_audit_purge_worker;
+use backend::services::deletion::spawn_purge_worker;
+use backend::services::retention::spawn_retention_worker;
 use backend::services::storage::StorageService;
 use backend::state::AppState;
 use backend::telemetry;
@@ -23,7 +28,29 @@ async fn main() -> anyhow::Result<()> {
     // Ensure migrations run at startup.
     sqlx::migrate!("./migrations").run(&pool).await?;
 
-    let storage = StorageService::from_env().await?;
+    // Bootstrap admins; further grants are managed in `user_roles`.
+    if let Ok(admins) = std::env::var("ADMIN_USER_IDS") {
+        for raw in admins.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            let user_id = uuid::Uuid::parse_str(raw).expect("Invalid id in ADMIN_USER_IDS");
+            UserRole::grant(&pool, user_id, ADMIN_ROLE).await?;
+        }
+    }
+
+    let encryption = match Keyring::from_env()? {
+        Some(keyring) => {
+            info!("encrypting audio with master key {}", keyring.current_id());
+            Encryption::new(pool.clone(), keyring)
+        }
+        None => Encryption::disabled(),
+    };
+
+    let storage = StorageService::from_env()
+        .await?
+        .with_encryption(encryption);
+
+    spawn_purge_worker(pool.clone(), storage.clone());
+    spawn_retention_worker(pool.clone(), storage.clone());
+    spawn_audit_purge_worker(pool.clone());
 
     let state = AppState::new(pool, storage);
     let app: Router = api::router(state);
@@ -34,6 +61,10 @@ async fn main() -> anyhow::Result<()> {
         .expect("Invalid BIND_ADDR");
 
     info!("Listening on {}", addr);
-    axum::serve(tokio::net::TcpListener::bind(addr).await?, app).await?;
+    axum::serve(
+        tokio::net::TcpListener::bind(addr).await?,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await?;
     Ok(())
 }
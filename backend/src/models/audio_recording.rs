@@ -1,6 +1,6 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, PgPool};
+use sqlx::{FromRow, PgPool, Postgres, Transaction};
 use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -70,4 +70,21 @@ impl AudioRecording {
         .await?;
         Ok(row)
     }
+
+    pub async fn delete(
+        tx: &mut Transaction<'_, Postgres>,
+        recording_id: Uuid,
+    ) -> anyhow::Result<Option<AudioRecording>> {
+        let row = sqlx::query_as::<_, AudioRecording>(
+            r#"
+            DELETE FROM audio_recordings
+            WHERE id = $1
+            RETURNING id, session_id, storage_url, duration_seconds, mime_type, size_bytes, quality_status, created_at
+            "#,
+        )
+        .bind(recording_id)
+        .fetch_optional(&mut **tx)
+        .await?;
+        Ok(row)
+    }
 }
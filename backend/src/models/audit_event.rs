@@ -0,0 +1,121 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{FromRow, PgPool, Postgres, QueryBuilder};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct AuditEvent {
+    pub id: Uuid,
+    pub occurred_at: DateTime<Utc>,
+    pub actor_id: Option<Uuid>,
+    pub action: String,
+    pub target_type: Option<String>,
+    pub target_id: Option<Uuid>,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub details: serde_json::Value,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewAuditEvent {
+    pub actor_id: Option<Uuid>,
+    pub action: String,
+    pub target_type: Option<String>,
+    pub target_id: Option<Uuid>,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub details: serde_json::Value,
+}
+
+#[derive(Debug, Default)]
+pub struct AuditFilter {
+    pub actor_id: Option<Uuid>,
+    pub action: Option<String>,
+    pub target_type: Option<String>,
+    pub target_id: Option<Uuid>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+}
+
+fn push_audit_filters<'a>(qb: &mut QueryBuilder<'a, Postgres>, filter: &'a AuditFilter) {
+    qb.push(" WHERE TRUE");
+    if let Some(actor_id) = filter.actor_id {
+        qb.push(" AND actor_id = ").push_bind(actor_id);
+    }
+    if let Some(action) = filter.action.as_deref() {
+        qb.push(" AND action = ").push_bind(action);
+    }
+    if let Some(target_type) = filter.target_type.as_deref() {
+        qb.push(" AND target_type = ").push_bind(target_type);
+    }
+    if let Some(target_id) = filter.target_id {
+        qb.push(" AND target_id = ").push_bind(target_id);
+    }
+    if let Some(from) = filter.from {
+        qb.push(" AND occurred_at >= ").push_bind(from);
+    }
+    if let Some(to) = filter.to {
+        qb.push(" AND occurred_at < ").push_bind(to);
+    }
+}
+
+impl AuditEvent {
+    pub async fn insert(pool: &PgPool, event: &NewAuditEvent) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO audit_events (actor_id, action, target_type, target_id, ip, user_agent, details)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+        )
+        .bind(event.actor_id)
+        .bind(&event.action)
+        .bind(&event.target_type)
+        .bind(event.target_id)
+        .bind(&event.ip)
+        .bind(&event.user_agent)
+        .bind(&event.details)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn search(
+        pool: &PgPool,
+        filter: &AuditFilter,
+        limit: i64,
+        offset: i64,
+    ) -> anyhow::Result<(Vec<AuditEvent>, i64)> {
+        let mut count_qb = QueryBuilder::<Postgres>::new("SELECT count(*) FROM audit_events");
+        push_audit_filters(&mut count_qb, filter);
+        let total: i64 = count_qb.build_query_scalar().fetch_one(pool).await?;
+
+        let mut qb = QueryBuilder::<Postgres>::new(
+            r#"
+            SELECT id, occurred_at, actor_id, action, target_type, target_id, ip, user_agent, details
+            FROM audit_events
+            "#,
+        );
+        push_audit_filters(&mut qb, filter);
+        qb.push(" ORDER BY occurred_at DESC, id DESC LIMIT ")
+            .push_bind(limit)
+            .push(" OFFSET ")
+            .push_bind(offset);
+        let rows = qb.build_query_as::<AuditEvent>().fetch_all(pool).await?;
+
+        Ok((rows, total))
+    }
+
+    // The table's trigger rejects deletes unless `audit.purge` is set for the transaction.
+    pub async fn purge_before(pool: &PgPool, cutoff: DateTime<Utc>) -> anyhow::Result<u64> {
+        let mut tx = pool.begin().await?;
+        sqlx::query("SET LOCAL audit.purge = 'on'")
+            .execute(&mut *tx)
+            .await?;
+        let res = sqlx::query("DELETE FROM audit_events WHERE occurred_at < $1")
+            .bind(cutoff)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(res.rows_affected())
+    }
+}
@@ -0,0 +1,202 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+pub const LINK_ACCEPTED: &str = "accepted";
+pub const LINK_DECLINED: &str = "declined";
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct CoachLink {
+    pub id: Uuid,
+    pub coach_id: Uuid,
+    pub student_id: Uuid,
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+    pub responded_at: Option<DateTime<Utc>>,
+}
+
+impl CoachLink {
+    // Re-inviting after a decline or revocation reopens the same row; an open
+    // invitation or accepted link is returned unchanged. The flag is true when
+    // a new invitation went out.
+    pub async fn invite(
+        pool: &PgPool,
+        coach_id: Uuid,
+        student_id: Uuid,
+    ) -> anyhow::Result<(CoachLink, bool)> {
+        let reopened = sqlx::query_as::<_, CoachLink>(
+            r#"
+            INSERT INTO coach_links (coach_id, student_id)
+            VALUES ($1, $2)
+            ON CONFLICT (coach_id, student_id) DO UPDATE
+            SET status = 'pending', created_at = now(), responded_at = NULL
+            WHERE coach_links.status IN ('declined', 'revoked')
+            RETURNING id, coach_id, student_id, status, created_at, responded_at
+            "#,
+        )
+        .bind(coach_id)
+        .bind(student_id)
+        .fetch_optional(pool)
+        .await?;
+        if let Some(link) = reopened {
+            return Ok((link, true));
+        }
+        let existing = sqlx::query_as::<_, CoachLink>(
+            r#"
+            SELECT id, coach_id, student_id, status, created_at, responded_at
+            FROM coach_links
+            WHERE coach_id = $1 AND student_id = $2
+            "#,
+        )
+        .bind(coach_id)
+        .bind(student_id)
+        .fetch_one(pool)
+        .await?;
+        Ok((existing, false))
+    }
+
+    pub async fn respond(
+        pool: &PgPool,
+        link_id: Uuid,
+        student_id: Uuid,
+        status: &str,
+    ) -> anyhow::Result<Option<CoachLink>> {
+        let row = sqlx::query_as::<_, CoachLink>(
+            r#"
+            UPDATE coach_links
+            SET status = $3, responded_at = now()
+            WHERE id = $1 AND student_id = $2 AND status = 'pending'
+            RETURNING id, coach_id, student_id, status, created_at, responded_at
+            "#,
+        )
+        .bind(link_id)
+        .bind(student_id)
+        .bind(status)
+        .fetch_optional(pool)
+        .await?;
+        Ok(row)
+    }
+
+    // Either side may end the relationship.
+    pub async fn revoke(
+        pool: &PgPool,
+        link_id: Uuid,
+        user_id: Uuid,
+    ) -> anyhow::Result<Option<CoachLink>> {
+        let row = sqlx::query_as::<_, CoachLink>(
+            r#"
+            UPDATE coach_links
+            SET status = 'revoked', responded_at = now()
+            WHERE id = $1
+              AND (coach_id = $2 OR student_id = $2)
+              AND status IN ('pending', 'accepted')
+            RETURNING id, coach_id, student_id, status, created_at, responded_at
+            "#,
+        )
+        .bind(link_id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?;
+        Ok(row)
+    }
+
+    pub async fn for_coach(pool: &PgPool, coach_id: Uuid) -> anyhow::Result<Vec<CoachLink>> {
+        let rows = sqlx::query_as::<_, CoachLink>(
+            r#"
+            SELECT id, coach_id, student_id, status, created_at, responded_at
+            FROM coach_links
+            WHERE coach_id = $1 AND status IN ('pending', 'accepted')
+            ORDER BY created_at
+            "#,
+        )
+        .bind(coach_id)
+        .fetch_all(pool)
+        .await?;
+        Ok(rows)
+    }
+
+    pub async fn for_student(pool: &PgPool, student_id: Uuid) -> anyhow::Result<Vec<CoachLink>> {
+        let rows = sqlx::query_as::<_, CoachLink>(
+            r#"
+            SELECT id, coach_id, student_id, status, created_at, responded_at
+            FROM coach_links
+            WHERE student_id = $1 AND status IN ('pending', 'accepted')
+            ORDER BY created_at
+            "#,
+        )
+        .bind(student_id)
+        .fetch_all(pool)
+        .await?;
+        Ok(rows)
+    }
+
+    pub async fn is_accepted(
+        pool: &PgPool,
+        coach_id: Uuid,
+        student_id: Uuid,
+    ) -> anyhow::Result<bool> {
+        let row: (bool,) = sqlx::query_as(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM coach_links
+                WHERE coach_id = $1 AND student_id = $2 AND status = 'accepted'
+            )
+            "#,
+        )
+        .bind(coach_id)
+        .bind(student_id)
+        .fetch_one(pool)
+        .await?;
+        Ok(row.0)
+    }
+}
+
+pub struct CoachSessionGrant;
+
+impl CoachSessionGrant {
+    pub async fn grant(pool: &PgPool, session_id: Uuid, coach_id: Uuid) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO coach_session_grants (session_id, coach_id)
+            VALUES ($1, $2)
+            ON CONFLICT (session_id, coach_id) DO NOTHING
+            "#,
+        )
+        .bind(session_id)
+        .bind(coach_id)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn revoke(pool: &PgPool, session_id: Uuid, coach_id: Uuid) -> anyhow::Result<bool> {
+        let res =
+            sqlx::query("DELETE FROM coach_session_grants WHERE session_id = $1 AND coach_id = $2")
+                .bind(session_id)
+                .bind(coach_id)
+                .execute(pool)
+                .await?;
+        Ok(res.rows_affected() > 0)
+    }
+
+    // Drops every grant a student gave a coach, used when the link ends.
+    pub async fn revoke_all(
+        pool: &PgPool,
+        coach_id: Uuid,
+        student_id: Uuid,
+    ) -> anyhow::Result<u64> {
+        let res = sqlx::query(
+            r#"
+            DELETE FROM coach_session_grants g
+            USING sessions s
+            WHERE s.id = g.session_id AND g.coach_id = $1 AND s.user_id = $2
+            "#,
+        )
+        .bind(coach_id)
+        .bind(student_id)
+        .execute(pool)
+        .await?;
+        Ok(res.rows_affected())
+    }
+}
@@ -0,0 +1,279 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Curriculum {
+    pub id: Uuid,
+    pub title: String,
+    pub description: Option<String>,
+    pub archived_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct CurriculumUnit {
+    pub id: Uuid,
+    pub curriculum_id: Uuid,
+    pub position: i32,
+    pub title: String,
+    pub required_sessions: i32,
+    pub min_duration_seconds: Option<i32>,
+    // Ordered by position within the unit.
+    pub topic_ids: Vec<Uuid>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct NewCurriculum {
+    pub title: String,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub units: Vec<NewCurriculumUnit>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct NewCurriculumUnit {
+    pub title: String,
+    #[serde(default = "default_required_sessions")]
+    pub required_sessions: i32,
+    pub min_duration_seconds: Option<i32>,
+    pub topic_ids: Vec<Uuid>,
+}
+
+fn default_required_sessions() -> i32 {
+    1
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Enrollment {
+    pub user_id: Uuid,
+    pub curriculum_id: Uuid,
+    pub enrolled_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+// Qualifying sessions a user has on one topic of a unit since enrolling.
+#[derive(Debug, Clone, FromRow)]
+pub struct UnitTopicCount {
+    pub unit_id: Uuid,
+    pub topic_id: Uuid,
+    pub sessions: i64,
+}
+
+const UNIT_SELECT: &str = r#"
+    SELECT u.id, u.curriculum_id, u.position, u.title, u.required_sessions, u.min_duration_seconds,
+           coalesce(
+               array_agg(ut.topic_id ORDER BY ut.position) FILTER (WHERE ut.topic_id IS NOT NULL),
+               '{}'
+           ) AS topic_ids
+    FROM curriculum_units u
+    LEFT JOIN curriculum_unit_topics ut ON ut.unit_id = u.id
+"#;
+
+impl Curriculum {
+    pub async fn list(pool: &PgPool) -> anyhow::Result<Vec<Curriculum>> {
+        let rows = sqlx::query_as::<_, Curriculum>(
+            r#"
+            SELECT id, title, description, archived_at, created_at, updated_at
+            FROM curricula
+            WHERE archived_at IS NULL
+            ORDER BY title
+            "#,
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows)
+    }
+
+    pub async fn get(pool: &PgPool, curriculum_id: Uuid) -> anyhow::Result<Option<Curriculum>> {
+        let row = sqlx::query_as::<_, Curriculum>(
+            r#"
+            SELECT id, title, description, archived_at, created_at, updated_at
+            FROM curricula
+            WHERE id = $1
+            "#,
+        )
+        .bind(curriculum_id)
+        .fetch_optional(pool)
+        .await?;
+        Ok(row)
+    }
+
+    pub async fn create(pool: &PgPool, curriculum: &NewCurriculum) -> anyhow::Result<Curriculum> {
+        let mut tx = pool.begin().await?;
+        let row = sqlx::query_as::<_, Curriculum>(
+            r#"
+            INSERT INTO curricula (title, description)
+            VALUES ($1, $2)
+            RETURNING id, title, description, archived_at, created_at, updated_at
+            "#,
+        )
+        .bind(&curriculum.title)
+        .bind(&curriculum.description)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        for (position, unit) in curriculum.units.iter().enumerate() {
+            let unit_id: Uuid = sqlx::query_scalar(
+                r#"
+                INSERT INTO curriculum_units (curriculum_id, position, title, required_sessions, min_duration_seconds)
+                VALUES ($1, $2, $3, $4, $5)
+                RETURNING id
+                "#,
+            )
+            .bind(row.id)
+            .bind(position as i32)
+            .bind(&unit.title)
+            .bind(unit.required_sessions)
+            .bind(unit.min_duration_seconds)
+            .fetch_one(&mut *tx)
+            .await?;
+
+            for (topic_position, topic_id) in unit.topic_ids.iter().enumerate() {
+                sqlx::query(
+                    r#"
+                    INSERT INTO curriculum_unit_topics (unit_id, topic_id, position)
+                    VALUES ($1, $2, $3)
+                    "#,
+                )
+                .bind(unit_id)
+                .bind(topic_id)
+                .bind(topic_position as i32)
+                .execute(&mut *tx)
+                .await?;
+            }
+        }
+
+        tx.commit().await?;
+        Ok(row)
+    }
+}
+
+impl CurriculumUnit {
+    pub async fn for_curricula(
+        pool: &PgPool,
+        curriculum_ids: &[Uuid],
+    ) -> anyhow::Result<Vec<CurriculumUnit>> {
+        let sql = format!(
+            "{} WHERE u.curriculum_id = ANY($1) GROUP BY u.id ORDER BY u.curriculum_id, u.position",
+            UNIT_SELECT
+        );
+        let rows = sqlx::query_as::<_, CurriculumUnit>(&sql)
+            .bind(curriculum_ids)
+            .fetch_all(pool)
+            .await?;
+        Ok(rows)
+    }
+}
+
+impl Enrollment {
+    // Returns the enrollment and whether it was created by this call.
+    pub async fn enroll(
+        pool: &PgPool,
+        user_id: Uuid,
+        curriculum_id: Uuid,
+    ) -> anyhow::Result<(Enrollment, bool)> {
+        let inserted = sqlx::query_as::<_, Enrollment>(
+            r#"
+            INSERT INTO curriculum_enrollments (user_id, curriculum_id)
+            VALUES ($1, $2)
+            ON CONFLICT (user_id, curriculum_id) DO NOTHING
+            RETURNING user_id, curriculum_id, enrolled_at, completed_at
+            "#,
+        )
+        .bind(user_id)
+        .bind(curriculum_id)
+        .fetch_optional(pool)
+        .await?;
+        match inserted {
+            Some(enrollment) => Ok((enrollment, true)),
+            None => {
+                let existing = Enrollment::get(pool, user_id, curriculum_id)
+                    .await?
+                    .ok_or_else(|| anyhow::anyhow!("enrollment vanished"))?;
+                Ok((existing, false))
+            }
+        }
+    }
+
+    pub async fn get(
+        pool: &PgPool,
+        user_id: Uuid,
+        curriculum_id: Uuid,
+    ) -> anyhow::Result<Option<Enrollment>> {
+        let row = sqlx::query_as::<_, Enrollment>(
+            r#"
+            SELECT user_id, curriculum_id, enrolled_at, completed_at
+            FROM curriculum_enrollments
+            WHERE user_id = $1 AND curriculum_id = $2
+            "#,
+        )
+        .bind(user_id)
+        .bind(curriculum_id)
+        .fetch_optional(pool)
+        .await?;
+        Ok(row)
+    }
+
+    pub async fn for_user(pool: &PgPool, user_id: Uuid) -> anyhow::Result<Vec<Enrollment>> {
+        let rows = sqlx::query_as::<_, Enrollment>(
+            r#"
+            SELECT user_id, curriculum_id, enrolled_at, completed_at
+            FROM curriculum_enrollments
+            WHERE user_id = $1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+        Ok(rows)
+    }
+
+    pub async fn mark_completed(
+        pool: &PgPool,
+        user_id: Uuid,
+        curriculum_id: Uuid,
+    ) -> anyhow::Result<Option<DateTime<Utc>>> {
+        let completed_at = sqlx::query_scalar(
+            r#"
+            UPDATE curriculum_enrollments
+            SET completed_at = COALESCE(completed_at, now())
+            WHERE user_id = $1 AND curriculum_id = $2
+            RETURNING completed_at
+            "#,
+        )
+        .bind(user_id)
+        .bind(curriculum_id)
+        .fetch_optional(pool)
+        .await?;
+        Ok(completed_at.flatten())
+    }
+
+    // Counts ended sessions started after enrolling that meet each unit's duration bar.
+    pub async fn unit_topic_counts(&self, pool: &PgPool) -> anyhow::Result<Vec<UnitTopicCount>> {
+        let rows = sqlx::query_as::<_, UnitTopicCount>(
+            r#"
+            SELECT u.id AS unit_id, ut.topic_id, count(s.id) AS sessions
+            FROM curriculum_units u
+            JOIN curriculum_unit_topics ut ON ut.unit_id = u.id
+            LEFT JOIN sessions s
+                ON s.topic_id = ut.topic_id
+                AND s.user_id = $1
+                AND s.status = 'ended'
+                AND s.deleted_at IS NULL
+                AND s.start_time >= $3
+                AND (u.min_duration_seconds IS NULL OR s.duration_seconds >= u.min_duration_seconds)
+            WHERE u.curriculum_id = $2
+            GROUP BY u.id, ut.topic_id
+            "#,
+        )
+        .bind(self.user_id)
+        .bind(self.curriculum_id)
+        .bind(self.enrolled_at)
+        .fetch_all(pool)
+        .await?;
+        Ok(rows)
+    }
+}
@@ -0,0 +1,193 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct DataExport {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub status: String,
+    #[serde(skip_serializing)]
+    pub storage_key: Option<String>,
+    pub size_bytes: Option<i64>,
+    pub error: Option<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl DataExport {
+    pub async fn create(pool: &PgPool, user_id: Uuid) -> anyhow::Result<DataExport> {
+        let row = sqlx::query_as::<_, DataExport>(
+            r#"
+            INSERT INTO data_exports (user_id)
+            VALUES ($1)
+            RETURNING id, user_id, status, storage_key, size_bytes, error, expires_at, created_at, completed_at
+            "#,
+        )
+        .bind(user_id)
+        .fetch_one(pool)
+        .await?;
+        Ok(row)
+    }
+
+    pub async fn get_for_user(
+        pool: &PgPool,
+        export_id: Uuid,
+        user_id: Uuid,
+    ) -> anyhow::Result<Option<DataExport>> {
+        let row = sqlx::query_as::<_, DataExport>(
+            r#"
+            SELECT id, user_id, status, storage_key, size_bytes, error, expires_at, created_at, completed_at
+            FROM data_exports
+            WHERE id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(export_id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?;
+        Ok(row)
+    }
+
+    // A pending or running export, so repeated requests do not queue duplicate work.
+    pub async fn in_progress_for_user(
+        pool: &PgPool,
+        user_id: Uuid,
+    ) -> anyhow::Result<Option<DataExport>> {
+        let row = sqlx::query_as::<_, DataExport>(
+            r#"
+            SELECT id, user_id, status, storage_key, size_bytes, error, expires_at, created_at, completed_at
+            FROM data_exports
+            WHERE user_id = $1 AND status IN ('pending', 'running')
+            ORDER BY created_at DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?;
+        Ok(row)
+    }
+
+    // Exports whose worker has gone quiet for a whole lease, usually because the process
+    // restarted while building them, are failed so a new request can queue fresh work.
+    pub async fn fail_stale(pool: &PgPool, cutoff: DateTime<Utc>) -> anyhow::Result<u64> {
+        let res = sqlx::query(
+            r#"
+            UPDATE data_exports
+            SET status = 'failed',
+                error = 'export was interrupted',
+                completed_at = now()
+            WHERE status IN ('pending', 'running') AND COALESCE(heartbeat_at, created_at) < $1
+            "#,
+        )
+        .bind(cutoff)
+        .execute(pool)
+        .await?;
+        Ok(res.rows_affected())
+    }
+
+    // Detaches archives that are past their expiry and returns each owner with the freed key.
+    pub async fn take_expired(
+        tx: &mut Transaction<'_, Postgres>,
+        now: DateTime<Utc>,
+        limit: i64,
+    ) -> anyhow::Result<Vec<(Uuid, String)>> {
+        let rows = sqlx::query_as(
+            r#"
+            WITH expired AS (
+                SELECT id, user_id, storage_key FROM data_exports
+                WHERE storage_key IS NOT NULL AND expires_at <= $1
+                ORDER BY expires_at
+                LIMIT $2
+                FOR UPDATE SKIP LOCKED
+            )
+            UPDATE data_exports d
+            SET storage_key = NULL
+            FROM expired e
+            WHERE d.id = e.id
+            RETURNING e.user_id, e.storage_key
+            "#,
+        )
+        .bind(now)
+        .bind(limit)
+        .fetch_all(&mut **tx)
+        .await?;
+        Ok(rows)
+    }
+
+    // False when the export was failed as stale before its worker picked it up.
+    pub async fn mark_running(pool: &PgPool, export_id: Uuid) -> anyhow::Result<bool> {
+        let res = sqlx::query(
+            r#"
+            UPDATE data_exports
+            SET status = 'running', heartbeat_at = now()
+            WHERE id = $1 AND status = 'pending'
+            "#,
+        )
+        .bind(export_id)
+        .execute(pool)
+        .await?;
+        Ok(res.rows_affected() > 0)
+    }
+
+    // Renews the lease of a running export. False once it was failed as stale or deleted
+    // with its account, so the worker can stop.
+    pub async fn heartbeat(pool: &PgPool, export_id: Uuid) -> anyhow::Result<bool> {
+        let res = sqlx::query(
+            "UPDATE data_exports SET heartbeat_at = now() WHERE id = $1 AND status = 'running'",
+        )
+        .bind(export_id)
+        .execute(pool)
+        .await?;
+        Ok(res.rows_affected() > 0)
+    }
+
+    // Only a still-running export becomes ready; false means the archive has no row to
+    // belong to.
+    pub async fn mark_ready(
+        tx: &mut Transaction<'_, Postgres>,
+        export_id: Uuid,
+        storage_key: &str,
+        size_bytes: i64,
+        expires_at: DateTime<Utc>,
+    ) -> anyhow::Result<bool> {
+        let res = sqlx::query(
+            r#"
+            UPDATE data_exports
+            SET status = 'ready',
+                storage_key = $2,
+                size_bytes = $3,
+                expires_at = $4,
+                completed_at = now()
+            WHERE id = $1 AND status = 'running'
+            "#,
+        )
+        .bind(export_id)
+        .bind(storage_key)
+        .bind(size_bytes)
+        .bind(expires_at)
+        .execute(&mut **tx)
+        .await?;
+        Ok(res.rows_affected() > 0)
+    }
+
+    pub async fn mark_failed(pool: &PgPool, export_id: Uuid, error: &str) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE data_exports
+            SET status = 'failed',
+                error = $2,
+                completed_at = now()
+            WHERE id = $1 AND status IN ('pending', 'running')
+            "#,
+        )
+        .bind(export_id)
+        .bind(error)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
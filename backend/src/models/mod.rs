@@ -1,5 +1,18 @@
 pub mod audio_recording;
+pub mod audit_event;
 pub mod client_secret;
+pub mod coaching;
+pub mod curriculum;
+pub mod data_export;
+pub mod notification;
+pub mod organization;
+pub mod retention_policy;
+pub mod review_comment;
 pub mod session;
+pub mod session_share;
+pub mod storage_deletion;
 pub mod topic;
+pub mod topic_translation;
 pub mod transcript;
+pub mod user_data_key;
+pub mod user_role;
@@ -0,0 +1,79 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Notification {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub kind: String,
+    pub payload: serde_json::Value,
+    pub read_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Notification {
+    pub async fn create(
+        pool: &PgPool,
+        user_id: Uuid,
+        kind: &str,
+        payload: serde_json::Value,
+    ) -> anyhow::Result<Notification> {
+        let row = sqlx::query_as::<_, Notification>(
+            r#"
+            INSERT INTO notifications (user_id, kind, payload)
+            VALUES ($1, $2, $3)
+            RETURNING id, user_id, kind, payload, read_at, created_at
+            "#,
+        )
+        .bind(user_id)
+        .bind(kind)
+        .bind(payload)
+        .fetch_one(pool)
+        .await?;
+        Ok(row)
+    }
+
+    pub async fn list_for_user(
+        pool: &PgPool,
+        user_id: Uuid,
+        unread_only: bool,
+        limit: i64,
+    ) -> anyhow::Result<Vec<Notification>> {
+        let rows = sqlx::query_as::<_, Notification>(
+            r#"
+            SELECT id, user_id, kind, payload, read_at, created_at
+            FROM notifications
+            WHERE user_id = $1 AND (NOT $2 OR read_at IS NULL)
+            ORDER BY created_at DESC, id
+            LIMIT $3
+            "#,
+        )
+        .bind(user_id)
+        .bind(unread_only)
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+        Ok(rows)
+    }
+
+    pub async fn mark_read(
+        pool: &PgPool,
+        notification_id: Uuid,
+        user_id: Uuid,
+    ) -> anyhow::Result<bool> {
+        let res = sqlx::query(
+            r#"
+            UPDATE notifications
+            SET read_at = COALESCE(read_at, now())
+            WHERE id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(notification_id)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+        Ok(res.rows_affected() > 0)
+    }
+}
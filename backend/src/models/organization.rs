@@ -0,0 +1,402 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+pub const ROLE_OWNER: &str = "owner";
+pub const ROLE_ADMIN: &str = "admin";
+pub const ROLE_MEMBER: &str = "member";
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Organization {
+    pub id: Uuid,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct OrganizationMember {
+    pub org_id: Uuid,
+    pub user_id: Uuid,
+    pub role: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct OrganizationInvitation {
+    pub id: Uuid,
+    pub org_id: Uuid,
+    pub user_id: Uuid,
+    pub role: String,
+    pub invited_by: Uuid,
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+    pub responded_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Cohort {
+    pub id: Uuid,
+    pub org_id: Uuid,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Organization {
+    // Creates the organization with `owner_id` as its owner. Returns `None` when that user
+    // already belongs to an organization.
+    pub async fn create(
+        pool: &PgPool,
+        name: &str,
+        owner_id: Uuid,
+    ) -> anyhow::Result<Option<Organization>> {
+        let mut tx = pool.begin().await?;
+        let org = sqlx::query_as::<_, Organization>(
+            r#"
+            INSERT INTO organizations (name)
+            VALUES ($1)
+            RETURNING id, name, created_at
+            "#,
+        )
+        .bind(name)
+        .fetch_one(&mut *tx)
+        .await?;
+        let joined = sqlx::query(
+            r#"
+            INSERT INTO organization_members (org_id, user_id, role)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (user_id) DO NOTHING
+            "#,
+        )
+        .bind(org.id)
+        .bind(owner_id)
+        .bind(ROLE_OWNER)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        if joined == 0 {
+            return Ok(None);
+        }
+        tx.commit().await?;
+        Ok(Some(org))
+    }
+
+    pub async fn get(pool: &PgPool, org_id: Uuid) -> anyhow::Result<Option<Organization>> {
+        let row = sqlx::query_as::<_, Organization>(
+            "SELECT id, name, created_at FROM organizations WHERE id = $1",
+        )
+        .bind(org_id)
+        .fetch_optional(pool)
+        .await?;
+        Ok(row)
+    }
+}
+
+impl OrganizationMember {
+    pub fn is_admin(&self) -> bool {
+        self.role == ROLE_OWNER || self.role == ROLE_ADMIN
+    }
+
+    pub async fn membership(
+        pool: &PgPool,
+        user_id: Uuid,
+    ) -> anyhow::Result<Option<OrganizationMember>> {
+        let row = sqlx::query_as::<_, OrganizationMember>(
+            r#"
+            SELECT org_id, user_id, role, created_at
+            FROM organization_members
+            WHERE user_id = $1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?;
+        Ok(row)
+    }
+
+    pub async fn list(pool: &PgPool, org_id: Uuid) -> anyhow::Result<Vec<OrganizationMember>> {
+        let rows = sqlx::query_as::<_, OrganizationMember>(
+            r#"
+            SELECT org_id, user_id, role, created_at
+            FROM organization_members
+            WHERE org_id = $1
+            ORDER BY created_at, user_id
+            "#,
+        )
+        .bind(org_id)
+        .fetch_all(pool)
+        .await?;
+        Ok(rows)
+    }
+
+    // The owner's role is fixed.
+    pub async fn set_role(
+        pool: &PgPool,
+        org_id: Uuid,
+        user_id: Uuid,
+        role: &str,
+    ) -> anyhow::Result<Option<OrganizationMember>> {
+        let row = sqlx::query_as::<_, OrganizationMember>(
+            r#"
+            UPDATE organization_members
+            SET role = $3
+            WHERE org_id = $1 AND user_id = $2 AND role <> 'owner'
+            RETURNING org_id, user_id, role, created_at
+            "#,
+        )
+        .bind(org_id)
+        .bind(user_id)
+        .bind(role)
+        .fetch_optional(pool)
+        .await?;
+        Ok(row)
+    }
+
+    // Also drops the user from the organization's cohorts. The owner cannot be removed.
+    pub async fn remove(pool: &PgPool, org_id: Uuid, user_id: Uuid) -> anyhow::Result<bool> {
+        let mut tx = pool.begin().await?;
+        let removed = sqlx::query(
+            r#"
+            DELETE FROM organization_members
+            WHERE org_id = $1 AND user_id = $2 AND role <> 'owner'
+            "#,
+        )
+        .bind(org_id)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        sqlx::query(
+            r#"
+            DELETE FROM cohort_members cm
+            USING cohorts c
+            WHERE c.id = cm.cohort_id AND c.org_id = $1 AND cm.user_id = $2
+            "#,
+        )
+        .bind(org_id)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(removed > 0)
+    }
+}
+
+impl OrganizationInvitation {
+    // Re-inviting after a decline reopens the same row with the new role; an open invitation
+    // is returned unchanged. The flag is true when a new invitation went out.
+    pub async fn invite(
+        pool: &PgPool,
+        org_id: Uuid,
+        user_id: Uuid,
+        role: &str,
+        invited_by: Uuid,
+    ) -> anyhow::Result<(OrganizationInvitation, bool)> {
+        let reopened = sqlx::query_as::<_, OrganizationInvitation>(
+            r#"
+            INSERT INTO organization_invitations (org_id, user_id, role, invited_by)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (org_id, user_id) DO UPDATE
+            SET status = 'pending', role = EXCLUDED.role, invited_by = EXCLUDED.invited_by,
+                created_at = now(), responded_at = NULL
+            WHERE organization_invitations.status <> 'pending'
+            RETURNING id, org_id, user_id, role, invited_by, status, created_at, responded_at
+            "#,
+        )
+        .bind(org_id)
+        .bind(user_id)
+        .bind(role)
+        .bind(invited_by)
+        .fetch_optional(pool)
+        .await?;
+        if let Some(invitation) = reopened {
+            return Ok((invitation, true));
+        }
+        let existing = sqlx::query_as::<_, OrganizationInvitation>(
+            r#"
+            SELECT id, org_id, user_id, role, invited_by, status, created_at, responded_at
+            FROM organization_invitations
+            WHERE org_id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(org_id)
+        .bind(user_id)
+        .fetch_one(pool)
+        .await?;
+        Ok((existing, false))
+    }
+
+    pub async fn pending_for_user(
+        pool: &PgPool,
+        user_id: Uuid,
+    ) -> anyhow::Result<Vec<OrganizationInvitation>> {
+        let rows = sqlx::query_as::<_, OrganizationInvitation>(
+            r#"
+            SELECT id, org_id, user_id, role, invited_by, status, created_at, responded_at
+            FROM organization_invitations
+            WHERE user_id = $1 AND status = 'pending'
+            ORDER BY created_at
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+        Ok(rows)
+    }
+
+    pub async fn decline(
+        pool: &PgPool,
+        invitation_id: Uuid,
+        user_id: Uuid,
+    ) -> anyhow::Result<Option<OrganizationInvitation>> {
+        let row = sqlx::query_as::<_, OrganizationInvitation>(
+            r#"
+            UPDATE organization_invitations
+            SET status = 'declined', responded_at = now()
+            WHERE id = $1 AND user_id = $2 AND status = 'pending'
+            RETURNING id, org_id, user_id, role, invited_by, status, created_at, responded_at
+            "#,
+        )
+        .bind(invitation_id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?;
+        Ok(row)
+    }
+
+    // Marks the invitation accepted and joins the organization with the invited role in one
+    // transaction. Returns `None` when there is no pending invitation or the user already
+    // belongs to an organization.
+    pub async fn accept(
+        pool: &PgPool,
+        invitation_id: Uuid,
+        user_id: Uuid,
+    ) -> anyhow::Result<Option<OrganizationMember>> {
+        let mut tx = pool.begin().await?;
+        let invitation = sqlx::query_as::<_, OrganizationInvitation>(
+            r#"
+            UPDATE organization_invitations
+            SET status = 'accepted', responded_at = now()
+            WHERE id = $1 AND user_id = $2 AND status = 'pending'
+            RETURNING id, org_id, user_id, role, invited_by, status, created_at, responded_at
+            "#,
+        )
+        .bind(invitation_id)
+        .bind(user_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some(invitation) = invitation else {
+            return Ok(None);
+        };
+        let member = sqlx::query_as::<_, OrganizationMember>(
+            r#"
+            INSERT INTO organization_members (org_id, user_id, role)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (user_id) DO NOTHING
+            RETURNING org_id, user_id, role, created_at
+            "#,
+        )
+        .bind(invitation.org_id)
+        .bind(user_id)
+        .bind(&invitation.role)
+        .fetch_optional(&mut *tx)
+        .await?;
+        if member.is_some() {
+            tx.commit().await?;
+        }
+        Ok(member)
+    }
+}
+
+impl Cohort {
+    pub async fn create(pool: &PgPool, org_id: Uuid, name: &str) -> anyhow::Result<Cohort> {
+        let row = sqlx::query_as::<_, Cohort>(
+            r#"
+            INSERT INTO cohorts (org_id, name)
+            VALUES ($1, $2)
+            RETURNING id, org_id, name, created_at
+            "#,
+        )
+        .bind(org_id)
+        .bind(name)
+        .fetch_one(pool)
+        .await?;
+        Ok(row)
+    }
+
+    pub async fn get(
+        pool: &PgPool,
+        org_id: Uuid,
+        cohort_id: Uuid,
+    ) -> anyhow::Result<Option<Cohort>> {
+        let row = sqlx::query_as::<_, Cohort>(
+            r#"
+            SELECT id, org_id, name, created_at
+            FROM cohorts
+            WHERE id = $1 AND org_id = $2
+            "#,
+        )
+        .bind(cohort_id)
+        .bind(org_id)
+        .fetch_optional(pool)
+        .await?;
+        Ok(row)
+    }
+
+    pub async fn list(pool: &PgPool, org_id: Uuid) -> anyhow::Result<Vec<Cohort>> {
+        let rows = sqlx::query_as::<_, Cohort>(
+            r#"
+            SELECT id, org_id, name, created_at
+            FROM cohorts
+            WHERE org_id = $1
+            ORDER BY name
+            "#,
+        )
+        .bind(org_id)
+        .fetch_all(pool)
+        .await?;
+        Ok(rows)
+    }
+
+    pub async fn members(pool: &PgPool, cohort_id: Uuid) -> anyhow::Result<Vec<Uuid>> {
+        let rows = sqlx::query_scalar(
+            r#"
+            SELECT user_id FROM cohort_members
+            WHERE cohort_id = $1
+            ORDER BY created_at, user_id
+            "#,
+        )
+        .bind(cohort_id)
+        .fetch_all(pool)
+        .await?;
+        Ok(rows)
+    }
+
+    pub async fn add_member(pool: &PgPool, cohort_id: Uuid, user_id: Uuid) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO cohort_members (cohort_id, user_id)
+            VALUES ($1, $2)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(cohort_id)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn remove_member(
+        pool: &PgPool,
+        cohort_id: Uuid,
+        user_id: Uuid,
+    ) -> anyhow::Result<bool> {
+        let res = sqlx::query("DELETE FROM cohort_members WHERE cohort_id = $1 AND user_id = $2")
+            .bind(cohort_id)
+            .bind(user_id)
+            .execute(pool)
+            .await?;
+        Ok(res.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,65 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct RetentionPolicy {
+    pub user_id: Uuid,
+    pub audio_days: Option<i32>,
+    pub transcript_days: Option<i32>,
+    pub metadata_days: Option<i32>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl RetentionPolicy {
+    pub async fn get(pool: &PgPool, user_id: Uuid) -> anyhow::Result<Option<RetentionPolicy>> {
+        let row = sqlx::query_as::<_, RetentionPolicy>(
+            r#"
+            SELECT user_id, audio_days, transcript_days, metadata_days, updated_at
+            FROM retention_policies
+            WHERE user_id = $1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?;
+        Ok(row)
+    }
+
+    pub async fn upsert(
+        pool: &PgPool,
+        user_id: Uuid,
+        audio_days: Option<i32>,
+        transcript_days: Option<i32>,
+        metadata_days: Option<i32>,
+    ) -> anyhow::Result<RetentionPolicy> {
+        let row = sqlx::query_as::<_, RetentionPolicy>(
+            r#"
+            INSERT INTO retention_policies (user_id, audio_days, transcript_days, metadata_days)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (user_id) DO UPDATE
+            SET audio_days = EXCLUDED.audio_days,
+                transcript_days = EXCLUDED.transcript_days,
+                metadata_days = EXCLUDED.metadata_days,
+                updated_at = now()
+            RETURNING user_id, audio_days, transcript_days, metadata_days, updated_at
+            "#,
+        )
+        .bind(user_id)
+        .bind(audio_days)
+        .bind(transcript_days)
+        .bind(metadata_days)
+        .fetch_one(pool)
+        .await?;
+        Ok(row)
+    }
+
+    pub async fn delete(pool: &PgPool, user_id: Uuid) -> anyhow::Result<bool> {
+        let res = sqlx::query("DELETE FROM retention_policies WHERE user_id = $1")
+            .bind(user_id)
+            .execute(pool)
+            .await?;
+        Ok(res.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,82 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ReviewComment {
+    pub id: Uuid,
+    pub session_id: Uuid,
+    pub author_id: Uuid,
+    pub parent_id: Option<Uuid>,
+    pub body: String,
+    pub segment_index: Option<i32>,
+    pub start_ms: Option<i64>,
+    pub end_ms: Option<i64>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewReviewComment {
+    pub session_id: Uuid,
+    pub author_id: Uuid,
+    pub parent_id: Option<Uuid>,
+    pub body: String,
+    pub segment_index: Option<i32>,
+    pub start_ms: Option<i64>,
+    pub end_ms: Option<i64>,
+}
+
+impl ReviewComment {
+    pub async fn create(pool: &PgPool, new: NewReviewComment) -> anyhow::Result<ReviewComment> {
+        let row = sqlx::query_as::<_, ReviewComment>(
+            r#"
+            INSERT INTO review_comments (session_id, author_id, parent_id, body, segment_index, start_ms, end_ms)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING id, session_id, author_id, parent_id, body, segment_index, start_ms, end_ms, created_at
+            "#,
+        )
+        .bind(new.session_id)
+        .bind(new.author_id)
+        .bind(new.parent_id)
+        .bind(new.body)
+        .bind(new.segment_index)
+        .bind(new.start_ms)
+        .bind(new.end_ms)
+        .fetch_one(pool)
+        .await?;
+        Ok(row)
+    }
+
+    pub async fn get(pool: &PgPool, comment_id: Uuid) -> anyhow::Result<Option<ReviewComment>> {
+        let row = sqlx::query_as::<_, ReviewComment>(
+            r#"
+            SELECT id, session_id, author_id, parent_id, body, segment_index, start_ms, end_ms, created_at
+            FROM review_comments
+            WHERE id = $1
+            "#,
+        )
+        .bind(comment_id)
+        .fetch_optional(pool)
+        .await?;
+        Ok(row)
+    }
+
+    pub async fn list_for_session(
+        pool: &PgPool,
+        session_id: Uuid,
+    ) -> anyhow::Result<Vec<ReviewComment>> {
+        let rows = sqlx::query_as::<_, ReviewComment>(
+            r#"
+            SELECT id, session_id, author_id, parent_id, body, segment_index, start_ms, end_ms, created_at
+            FROM review_comments
+            WHERE session_id = $1
+            ORDER BY created_at, id
+            "#,
+        )
+        .bind(session_id)
+        .fetch_all(pool)
+        .await?;
+        Ok(rows)
+    }
+}
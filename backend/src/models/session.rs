@@ -13,6 +13,10 @@ pub struct Session {
     pub duration_seconds: Option<i32>,
     pub status: String,
     pub privacy: String,
+    pub language: String,
+    pub mode: String,
+    pub mode_params: serde_json::Value,
+    pub deleted_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -22,6 +26,9 @@ pub struct NewSession {
     pub user_id: Uuid,
     pub topic_id: Uuid,
     pub status: String,
+    pub language: String,
+    pub mode: String,
+    pub mode_params: serde_json::Value,
 }
 
 #[derive(Debug, Deserialize)]
@@ -32,17 +39,22 @@ pub struct FinalizeSession {
 }
 
 impl Session {
+    // The session is recorded under the user's organization, if they belong to one.
     pub async fn create(pool: &PgPool, payload: NewSession) -> anyhow::Result<Session> {
         let row = sqlx::query_as::<_, Session>(
             r#"
-            INSERT INTO sessions (user_id, topic_id, status)
-            VALUES ($1, $2, $3)
-            RETURNING id, user_id, topic_id, start_time, end_time, duration_seconds, status, privacy, created_at, updated_at
+            INSERT INTO sessions (user_id, topic_id, status, language, mode, mode_params, org_id)
+            VALUES ($1, $2, $3, $4, $5, $6,
+                    (SELECT org_id FROM organization_members WHERE user_id = $1))
+            RETURNING id, user_id, topic_id, start_time, end_time, duration_seconds, status, privacy, language, mode, mode_params, deleted_at, created_at, updated_at
             "#,
         )
         .bind(payload.user_id)
         .bind(payload.topic_id)
         .bind(payload.status)
+        .bind(payload.language)
+        .bind(payload.mode)
+        .bind(payload.mode_params)
         .fetch_one(pool)
         .await?;
         Ok(row)
@@ -61,7 +73,7 @@ impl Session {
                 status = $4,
                 updated_at = now()
             WHERE id = $1
-            RETURNING id, user_id, topic_id, start_time, end_time, duration_seconds, status, privacy, created_at, updated_at
+            RETURNING id, user_id, topic_id, start_time, end_time, duration_seconds, status, privacy, language, mode, mode_params, deleted_at, created_at, updated_at
             "#,
         )
         .bind(session_id)
@@ -76,7 +88,7 @@ impl Session {
     pub async fn get(pool: &PgPool, session_id: Uuid) -> anyhow::Result<Session> {
         let row = sqlx::query_as::<_, Session>(
             r#"
-            SELECT id, user_id, topic_id, start_time, end_time, duration_seconds, status, privacy, created_at, updated_at
+            SELECT id, user_id, topic_id, start_time, end_time, duration_seconds, status, privacy, language, mode, mode_params, deleted_at, created_at, updated_at
             FROM sessions
             WHERE id = $1
             "#,
@@ -86,4 +98,19 @@ impl Session {
         .await?;
         Ok(row)
     }
+
+    pub async fn list_for_user(pool: &PgPool, user_id: Uuid) -> anyhow::Result<Vec<Session>> {
+        let rows = sqlx::query_as::<_, Session>(
+            r#"
+            SELECT id, user_id, topic_id, start_time, end_time, duration_seconds, status, privacy, language, mode, mode_params, deleted_at, created_at, updated_at
+            FROM sessions
+            WHERE user_id = $1 AND deleted_at IS NULL
+            ORDER BY start_time
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+        Ok(rows)
+    }
 }
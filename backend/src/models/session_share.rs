@@ -0,0 +1,155 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct SessionShare {
+    pub id: Uuid,
+    pub session_id: Uuid,
+    pub token: String,
+    pub scope: String,
+    pub expires_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub view_count: i32,
+    pub last_viewed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+// A share resolved from its token, with the owner kept server-side.
+#[derive(Debug, Clone, FromRow)]
+pub struct ResolvedShare {
+    pub session_id: Uuid,
+    pub owner_id: Uuid,
+    pub scope: String,
+    pub expires_at: DateTime<Utc>,
+    pub view_count: i32,
+}
+
+impl SessionShare {
+    pub async fn create(
+        pool: &PgPool,
+        session_id: Uuid,
+        token: &str,
+        scope: &str,
+        expires_at: DateTime<Utc>,
+    ) -> anyhow::Result<SessionShare> {
+        let row = sqlx::query_as::<_, SessionShare>(
+            r#"
+            INSERT INTO session_shares (session_id, token, scope, expires_at)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id, session_id, token, scope, expires_at, revoked_at, view_count, last_viewed_at, created_at
+            "#,
+        )
+        .bind(session_id)
+        .bind(token)
+        .bind(scope)
+        .bind(expires_at)
+        .fetch_one(pool)
+        .await?;
+        Ok(row)
+    }
+
+    pub async fn list_for_session(
+        pool: &PgPool,
+        session_id: Uuid,
+    ) -> anyhow::Result<Vec<SessionShare>> {
+        let rows = sqlx::query_as::<_, SessionShare>(
+            r#"
+            SELECT id, session_id, token, scope, expires_at, revoked_at, view_count, last_viewed_at, created_at
+            FROM session_shares
+            WHERE session_id = $1
+            ORDER BY created_at DESC
+            "#,
+        )
+        .bind(session_id)
+        .fetch_all(pool)
+        .await?;
+        Ok(rows)
+    }
+
+    pub async fn revoke(pool: &PgPool, session_id: Uuid, share_id: Uuid) -> anyhow::Result<bool> {
+        let res = sqlx::query(
+            r#"
+            UPDATE session_shares
+            SET revoked_at = now()
+            WHERE id = $1 AND session_id = $2 AND revoked_at IS NULL
+            "#,
+        )
+        .bind(share_id)
+        .bind(session_id)
+        .execute(pool)
+        .await?;
+        Ok(res.rows_affected() > 0)
+    }
+
+    // Counts the view and resolves the link in one statement; revoked, expired and
+    // tombstoned sessions resolve to nothing.
+    pub async fn record_view(pool: &PgPool, token: &str) -> anyhow::Result<Option<ResolvedShare>> {
+        let row = sqlx::query_as::<_, ResolvedShare>(
+            r#"
+            UPDATE session_shares sh
+            SET view_count = sh.view_count + 1,
+                last_viewed_at = now()
+            FROM sessions s
+            WHERE sh.token = $1
+              AND s.id = sh.session_id
+              AND sh.revoked_at IS NULL
+              AND sh.expires_at > now()
+              AND s.deleted_at IS NULL
+            RETURNING sh.session_id, s.user_id AS owner_id, sh.scope, sh.expires_at, sh.view_count
+            "#,
+        )
+        .bind(token)
+        .fetch_optional(pool)
+        .await?;
+        Ok(row)
+    }
+
+    // Same checks as record_view, without counting a view.
+    pub async fn resolve(pool: &PgPool, token: &str) -> anyhow::Result<Option<ResolvedShare>> {
+        let row = sqlx::query_as::<_, ResolvedShare>(
+            r#"
+            SELECT sh.session_id, s.user_id AS owner_id, sh.scope, sh.expires_at, sh.view_count
+            FROM session_shares sh
+            JOIN sessions s ON s.id = sh.session_id
+            WHERE sh.token = $1
+              AND sh.revoked_at IS NULL
+              AND sh.expires_at > now()
+              AND s.deleted_at IS NULL
+            "#,
+        )
+        .bind(token)
+        .fetch_optional(pool)
+        .await?;
+        Ok(row)
+    }
+
+    // Recomputes sessions.privacy from active links. With no session given, it sweeps
+    // every session whose flag is stale, e.g. after links expire.
+    pub async fn sync_privacy(pool: &PgPool, session_id: Option<Uuid>) -> anyhow::Result<u64> {
+        let res = sqlx::query(
+            r#"
+            UPDATE sessions s
+            SET privacy = CASE WHEN active.shared THEN 'shared' ELSE 'private' END
+            FROM (
+                SELECT s2.id,
+                       EXISTS (
+                           SELECT 1 FROM session_shares sh
+                           WHERE sh.session_id = s2.id
+                             AND sh.revoked_at IS NULL
+                             AND sh.expires_at > now()
+                       ) AS shared
+                FROM sessions s2
+                WHERE ($1::uuid IS NULL AND s2.privacy = 'shared') OR s2.id = $1
+            ) active
+            WHERE s.id = active.id
+              AND s.privacy <> CASE WHEN active.shared THEN 'shared' ELSE 'private' END
+            "#,
+        )
+        .bind(session_id)
+        .execute(pool)
+        .await?;
+        Ok(res.rows_affected())
+    }
+}
@@ -0,0 +1,181 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct StorageDeletion {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub object_key: String,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub next_attempt_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AccountDeletion {
+    pub user_id: Uuid,
+    pub sessions_deleted: i32,
+    pub objects_queued: i32,
+    pub requested_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+// How long a claimed row stays invisible to other workers before it is retried.
+const CLAIM_LEASE_SECONDS: i64 = 300;
+
+impl StorageDeletion {
+    pub async fn enqueue(
+        tx: &mut Transaction<'_, Postgres>,
+        user_id: Uuid,
+        keys: &[String],
+    ) -> anyhow::Result<()> {
+        if keys.is_empty() {
+            return Ok(());
+        }
+        sqlx::query(
+            r#"
+            INSERT INTO storage_deletions (user_id, object_key)
+            SELECT $1, key FROM UNNEST($2::text[]) AS key
+            "#,
+        )
+        .bind(user_id)
+        .bind(keys)
+        .execute(&mut **tx)
+        .await?;
+        Ok(())
+    }
+
+    // Claims due rows by pushing their next attempt out by a lease, so a crashed
+    // worker's rows are picked up again later.
+    pub async fn claim_due(pool: &PgPool, limit: i64) -> anyhow::Result<Vec<StorageDeletion>> {
+        let rows = sqlx::query_as::<_, StorageDeletion>(
+            r#"
+            UPDATE storage_deletions
+            SET attempts = attempts + 1,
+                next_attempt_at = now() + make_interval(secs => $2)
+            WHERE id IN (
+                SELECT id FROM storage_deletions
+                WHERE completed_at IS NULL AND next_attempt_at <= now()
+                ORDER BY next_attempt_at
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING id, user_id, object_key, attempts, last_error, next_attempt_at, created_at, completed_at
+            "#,
+        )
+        .bind(limit)
+        .bind(CLAIM_LEASE_SECONDS as f64)
+        .fetch_all(pool)
+        .await?;
+        Ok(rows)
+    }
+
+    pub async fn mark_done(pool: &PgPool, id: Uuid) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE storage_deletions
+            SET completed_at = now(), last_error = NULL
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn mark_failed(
+        pool: &PgPool,
+        id: Uuid,
+        error: &str,
+        retry_at: DateTime<Utc>,
+    ) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE storage_deletions
+            SET last_error = $2, next_attempt_at = $3
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(error)
+        .bind(retry_at)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn pending_for_user(pool: &PgPool, user_id: Uuid) -> anyhow::Result<i64> {
+        let (count,): (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM storage_deletions WHERE user_id = $1 AND completed_at IS NULL",
+        )
+        .bind(user_id)
+        .fetch_one(pool)
+        .await?;
+        Ok(count)
+    }
+}
+
+impl AccountDeletion {
+    pub async fn record(
+        tx: &mut Transaction<'_, Postgres>,
+        user_id: Uuid,
+        sessions_deleted: i32,
+        objects_queued: i32,
+    ) -> anyhow::Result<AccountDeletion> {
+        let row = sqlx::query_as::<_, AccountDeletion>(
+            r#"
+            INSERT INTO account_deletions (user_id, sessions_deleted, objects_queued, completed_at)
+            VALUES ($1, $2, $3, CASE WHEN $3 = 0 THEN now() END)
+            ON CONFLICT (user_id) DO UPDATE
+            SET sessions_deleted = account_deletions.sessions_deleted + EXCLUDED.sessions_deleted,
+                objects_queued = account_deletions.objects_queued + EXCLUDED.objects_queued,
+                requested_at = now(),
+                completed_at = NULL
+            RETURNING user_id, sessions_deleted, objects_queued, requested_at, completed_at
+            "#,
+        )
+        .bind(user_id)
+        .bind(sessions_deleted)
+        .bind(objects_queued)
+        .fetch_one(&mut **tx)
+        .await?;
+        Ok(row)
+    }
+
+    pub async fn get(pool: &PgPool, user_id: Uuid) -> anyhow::Result<Option<AccountDeletion>> {
+        let row = sqlx::query_as::<_, AccountDeletion>(
+            r#"
+            SELECT user_id, sessions_deleted, objects_queued, requested_at, completed_at
+            FROM account_deletions
+            WHERE user_id = $1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?;
+        Ok(row)
+    }
+
+    // Closes out account deletions whose queued objects have all been removed.
+    pub async fn complete_drained(pool: &PgPool) -> anyhow::Result<u64> {
+        let res = sqlx::query(
+            r#"
+            UPDATE account_deletions ad
+            SET completed_at = now()
+            WHERE completed_at IS NULL
+              AND NOT EXISTS (
+                SELECT 1 FROM storage_deletions sd
+                WHERE sd.user_id = ad.user_id AND sd.completed_at IS NULL
+              )
+            "#,
+        )
+        .execute(pool)
+        .await?;
+        Ok(res.rows_affected())
+    }
+}
@@ -1,6 +1,6 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, PgPool};
+use sqlx::{FromRow, PgExecutor, PgPool, Postgres, QueryBuilder};
 use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -9,46 +9,378 @@ pub struct Topic {
     pub title: String,
     pub difficulty: Option<String>,
     pub prompt_hint: Option<String>,
+    pub category: Option<String>,
+    pub tags: Vec<String>,
+    pub target_skill: Option<String>,
+    pub estimated_duration_seconds: Option<i32>,
+    pub owner_id: Option<Uuid>,
+    pub org_id: Option<Uuid>,
+    pub archived_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct NewTopic {
     pub title: String,
     pub difficulty: Option<String>,
     pub prompt_hint: Option<String>,
+    #[serde(default)]
+    pub category: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub target_skill: Option<String>,
+    #[serde(default)]
+    pub estimated_duration_seconds: Option<i32>,
+}
+
+// `None` leaves a field untouched; `Some(None)` clears a nullable field.
+#[derive(Debug, Default)]
+pub struct TopicUpdate {
+    pub title: Option<String>,
+    pub difficulty: Option<Option<String>>,
+    pub prompt_hint: Option<Option<String>>,
+    pub category: Option<Option<String>>,
+    pub tags: Option<Vec<String>>,
+    pub target_skill: Option<Option<String>>,
+    pub estimated_duration_seconds: Option<Option<i32>>,
+    pub archived: Option<bool>,
+}
+
+#[derive(Debug, Default)]
+pub struct TopicFilter {
+    pub difficulty: Option<String>,
+    pub category: Option<String>,
+    pub tag: Option<String>,
+    pub target_skill: Option<String>,
+    pub query: Option<String>,
+    // Also include this user's private topics and their organization's catalog.
+    pub viewer: Option<Uuid>,
+}
+
+fn push_topic_filters<'a>(qb: &mut QueryBuilder<'a, Postgres>, filter: &'a TopicFilter) {
+    qb.push(" WHERE archived_at IS NULL");
+    match filter.viewer {
+        Some(viewer) => {
+            qb.push(" AND (owner_id = ")
+                .push_bind(viewer)
+                .push(
+                    " OR (owner_id IS NULL AND (org_id IS NULL OR org_id = \
+                     (SELECT org_id FROM organization_members WHERE user_id = ",
+                )
+                .push_bind(viewer)
+                .push("))))");
+        }
+        None => {
+            qb.push(" AND owner_id IS NULL AND org_id IS NULL");
+        }
+    }
+    if let Some(difficulty) = filter.difficulty.as_deref() {
+        qb.push(" AND difficulty = ").push_bind(difficulty);
+    }
+    if let Some(category) = filter.category.as_deref() {
+        qb.push(" AND category = ").push_bind(category);
+    }
+    if let Some(tag) = filter.tag.as_deref() {
+        qb.push(" AND ").push_bind(tag).push(" = ANY(tags)");
+    }
+    if let Some(skill) = filter.target_skill.as_deref() {
+        qb.push(" AND target_skill = ").push_bind(skill);
+    }
+    if let Some(query) = filter.query.as_deref() {
+        let escaped = query
+            .replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_");
+        let pattern = format!("%{}%", escaped);
+        qb.push(" AND (title ILIKE ")
+            .push_bind(pattern.clone())
+            .push(" OR prompt_hint ILIKE ")
+            .push_bind(pattern.clone())
+            .push(" OR EXISTS (SELECT 1 FROM topic_translations tt WHERE tt.topic_id = topics.id AND (tt.title ILIKE ")
+            .push_bind(pattern.clone())
+            .push(" OR tt.prompt_hint ILIKE ")
+            .push_bind(pattern)
+            .push(")))");
+    }
 }
 
 impl Topic {
     pub async fn list(pool: &PgPool) -> anyhow::Result<Vec<Topic>> {
         let rows = sqlx::query_as::<_, Topic>(
             r#"
-            SELECT id, title, difficulty, prompt_hint, created_at, updated_at
+            SELECT id, title, difficulty, prompt_hint, category, tags, target_skill,
+                   estimated_duration_seconds, owner_id, org_id, archived_at, created_at, updated_at
+            FROM topics
+            WHERE archived_at IS NULL AND owner_id IS NULL AND org_id IS NULL
+            ORDER BY created_at DESC
+            "#,
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows)
+    }
+
+    // Active global topics plus the user's own private ones and their organization's catalog.
+    pub async fn list_visible(pool: &PgPool, user_id: Uuid) -> anyhow::Result<Vec<Topic>> {
+        let rows = sqlx::query_as::<_, Topic>(
+            r#"
+            SELECT id, title, difficulty, prompt_hint, category, tags, target_skill,
+                   estimated_duration_seconds, owner_id, org_id, archived_at, created_at, updated_at
             FROM topics
+            WHERE archived_at IS NULL
+              AND (owner_id = $1 OR (owner_id IS NULL AND (org_id IS NULL OR org_id =
+                   (SELECT org_id FROM organization_members WHERE user_id = $1))))
             ORDER BY created_at DESC
             "#,
         )
+        .bind(user_id)
         .fetch_all(pool)
         .await?;
         Ok(rows)
     }
 
-    pub async fn insert_many(pool: &PgPool, topics: &[NewTopic]) -> anyhow::Result<()> {
-        for topic in topics {
-            sqlx::query(
-                r#"
-                INSERT INTO topics (title, difficulty, prompt_hint)
-                VALUES ($1, $2, $3)
-                ON CONFLICT (title) DO NOTHING
-                "#,
-            )
-            .bind(&topic.title)
-            .bind(&topic.difficulty)
-            .bind(&topic.prompt_hint)
-            .execute(pool)
+    pub async fn list_owned(pool: &PgPool, owner_id: Uuid) -> anyhow::Result<Vec<Topic>> {
+        let rows = sqlx::query_as::<_, Topic>(
+            r#"
+            SELECT id, title, difficulty, prompt_hint, category, tags, target_skill,
+                   estimated_duration_seconds, owner_id, org_id, archived_at, created_at, updated_at
+            FROM topics
+            WHERE owner_id = $1 AND archived_at IS NULL
+            ORDER BY created_at DESC
+            "#,
+        )
+        .bind(owner_id)
+        .fetch_all(pool)
+        .await?;
+        Ok(rows)
+    }
+
+    pub async fn list_for_org(pool: &PgPool, org_id: Uuid) -> anyhow::Result<Vec<Topic>> {
+        let rows = sqlx::query_as::<_, Topic>(
+            r#"
+            SELECT id, title, difficulty, prompt_hint, category, tags, target_skill,
+                   estimated_duration_seconds, owner_id, org_id, archived_at, created_at, updated_at
+            FROM topics
+            WHERE org_id = $1 AND archived_at IS NULL
+            ORDER BY created_at DESC
+            "#,
+        )
+        .bind(org_id)
+        .fetch_all(pool)
+        .await?;
+        Ok(rows)
+    }
+
+    pub async fn count_owned(executor: impl PgExecutor<'_>, owner_id: Uuid) -> anyhow::Result<i64> {
+        let count = sqlx::query_scalar(
+            r#"
+            SELECT count(*) FROM topics
+            WHERE owner_id = $1 AND archived_at IS NULL
+            "#,
+        )
+        .bind(owner_id)
+        .fetch_one(executor)
+        .await?;
+        Ok(count)
+    }
+
+    // Serializes private-topic writes for one owner until the surrounding transaction ends.
+    pub async fn lock_owner(executor: impl PgExecutor<'_>, owner_id: Uuid) -> anyhow::Result<()> {
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtextextended($1::text, 0))")
+            .bind(owner_id)
+            .execute(executor)
             .await?;
-        }
         Ok(())
     }
+
+    pub async fn archived_ids(pool: &PgPool, topic_ids: &[Uuid]) -> anyhow::Result<Vec<Uuid>> {
+        let ids = sqlx::query_scalar(
+            r#"
+            SELECT id FROM topics
+            WHERE id = ANY($1) AND archived_at IS NOT NULL
+            "#,
+        )
+        .bind(topic_ids)
+        .fetch_all(pool)
+        .await?;
+        Ok(ids)
+    }
+
+    // All global topics, including archived ones.
+    pub async fn list_all(pool: &PgPool) -> anyhow::Result<Vec<Topic>> {
+        let rows = sqlx::query_as::<_, Topic>(
+            r#"
+            SELECT id, title, difficulty, prompt_hint, category, tags, target_skill,
+                   estimated_duration_seconds, owner_id, org_id, archived_at, created_at, updated_at
+            FROM topics
+            WHERE owner_id IS NULL AND org_id IS NULL
+            ORDER BY title
+            "#,
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows)
+    }
+
+    pub async fn search(
+        pool: &PgPool,
+        filter: &TopicFilter,
+        limit: i64,
+        offset: i64,
+    ) -> anyhow::Result<(Vec<Topic>, i64)> {
+        let mut count_qb = QueryBuilder::<Postgres>::new("SELECT count(*) FROM topics");
+        push_topic_filters(&mut count_qb, filter);
+        let total: i64 = count_qb.build_query_scalar().fetch_one(pool).await?;
+
+        let mut qb = QueryBuilder::<Postgres>::new(
+            r#"
+            SELECT id, title, difficulty, prompt_hint, category, tags, target_skill,
+                   estimated_duration_seconds, owner_id, org_id, archived_at, created_at, updated_at
+            FROM topics
+            "#,
+        );
+        push_topic_filters(&mut qb, filter);
+        qb.push(" ORDER BY created_at DESC, id DESC LIMIT ")
+            .push_bind(limit)
+            .push(" OFFSET ")
+            .push_bind(offset);
+        let rows = qb.build_query_as::<Topic>().fetch_all(pool).await?;
+
+        Ok((rows, total))
+    }
+
+    pub async fn get(pool: &PgPool, topic_id: Uuid) -> anyhow::Result<Option<Topic>> {
+        let row = sqlx::query_as::<_, Topic>(
+            r#"
+            SELECT id, title, difficulty, prompt_hint, category, tags, target_skill,
+                   estimated_duration_seconds, owner_id, org_id, archived_at, created_at, updated_at
+            FROM topics
+            WHERE id = $1
+            "#,
+        )
+        .bind(topic_id)
+        .fetch_optional(pool)
+        .await?;
+        Ok(row)
+    }
+
+    pub async fn create(executor: impl PgExecutor<'_>, topic: &NewTopic) -> anyhow::Result<Topic> {
+        Topic::insert(executor, None, None, topic).await
+    }
+
+    pub async fn create_for_owner(
+        executor: impl PgExecutor<'_>,
+        owner_id: Option<Uuid>,
+        topic: &NewTopic,
+    ) -> anyhow::Result<Topic> {
+        Topic::insert(executor, owner_id, None, topic).await
+    }
+
+    pub async fn create_for_org(
+        pool: &PgPool,
+        org_id: Uuid,
+        topic: &NewTopic,
+    ) -> anyhow::Result<Topic> {
+        Topic::insert(pool, None, Some(org_id), topic).await
+    }
+
+    async fn insert(
+        executor: impl PgExecutor<'_>,
+        owner_id: Option<Uuid>,
+        org_id: Option<Uuid>,
+        topic: &NewTopic,
+    ) -> anyhow::Result<Topic> {
+        let row = sqlx::query_as::<_, Topic>(
+            r#"
+            INSERT INTO topics (title, difficulty, prompt_hint, category, tags, target_skill, estimated_duration_seconds, owner_id, org_id)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            RETURNING id, title, difficulty, prompt_hint, category, tags, target_skill,
+                      estimated_duration_seconds, owner_id, org_id, archived_at, created_at, updated_at
+            "#,
+        )
+        .bind(&topic.title)
+        .bind(&topic.difficulty)
+        .bind(&topic.prompt_hint)
+        .bind(&topic.category)
+        .bind(&topic.tags)
+        .bind(&topic.target_skill)
+        .bind(topic.estimated_duration_seconds)
+        .bind(owner_id)
+        .bind(org_id)
+        .fetch_one(executor)
+        .await?;
+        Ok(row)
+    }
+
+    // Only touches the topic when it belongs to `owner_id` and `org_id` (both `None` for global
+    // topics).
+    pub async fn update(
+        executor: impl PgExecutor<'_>,
+        topic_id: Uuid,
+        owner_id: Option<Uuid>,
+        org_id: Option<Uuid>,
+        update: &TopicUpdate,
+    ) -> anyhow::Result<Option<Topic>> {
+        let row = sqlx::query_as::<_, Topic>(
+            r#"
+            UPDATE topics
+            SET title = COALESCE($2, title),
+                difficulty = CASE WHEN $3 THEN $4 ELSE difficulty END,
+                prompt_hint = CASE WHEN $5 THEN $6 ELSE prompt_hint END,
+                category = CASE WHEN $7 THEN $8 ELSE category END,
+                tags = COALESCE($9, tags),
+                target_skill = CASE WHEN $10 THEN $11 ELSE target_skill END,
+                estimated_duration_seconds = CASE WHEN $12 THEN $13 ELSE estimated_duration_seconds END,
+                archived_at = CASE
+                    WHEN $14::boolean IS NULL THEN archived_at
+                    WHEN $14 THEN COALESCE(archived_at, now())
+                    ELSE NULL
+                END
+            WHERE id = $1 AND owner_id IS NOT DISTINCT FROM $15 AND org_id IS NOT DISTINCT FROM $16
+            RETURNING id, title, difficulty, prompt_hint, category, tags, target_skill,
+                      estimated_duration_seconds, owner_id, org_id, archived_at, created_at, updated_at
+            "#,
+        )
+        .bind(topic_id)
+        .bind(&update.title)
+        .bind(update.difficulty.is_some())
+        .bind(update.difficulty.clone().flatten())
+        .bind(update.prompt_hint.is_some())
+        .bind(update.prompt_hint.clone().flatten())
+        .bind(update.category.is_some())
+        .bind(update.category.clone().flatten())
+        .bind(&update.tags)
+        .bind(update.target_skill.is_some())
+        .bind(update.target_skill.clone().flatten())
+        .bind(update.estimated_duration_seconds.is_some())
+        .bind(update.estimated_duration_seconds.flatten())
+        .bind(update.archived)
+        .bind(owner_id)
+        .bind(org_id)
+        .fetch_optional(executor)
+        .await?;
+        Ok(row)
+    }
+
+    // Returns `false` when the topic is referenced by sessions and so cannot be removed.
+    pub async fn delete_owned(
+        pool: &PgPool,
+        topic_id: Uuid,
+        owner_id: Uuid,
+    ) -> anyhow::Result<bool> {
+        let res = sqlx::query(
+            r#"
+            DELETE FROM topics
+            WHERE id = $1 AND owner_id = $2
+              AND NOT EXISTS (SELECT 1 FROM sessions WHERE topic_id = $1)
+            "#,
+        )
+        .bind(topic_id)
+        .bind(owner_id)
+        .execute(pool)
+        .await?;
+        Ok(res.rows_affected() > 0)
+    }
 }
@@ -0,0 +1,105 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use crate::models::topic::Topic;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TopicTranslation {
+    pub topic_id: Uuid,
+    pub locale: String,
+    pub title: String,
+    pub prompt_hint: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NewTopicTranslation {
+    pub title: String,
+    pub prompt_hint: Option<String>,
+}
+
+impl TopicTranslation {
+    pub async fn upsert(
+        pool: &PgPool,
+        topic_id: Uuid,
+        locale: &str,
+        payload: &NewTopicTranslation,
+    ) -> anyhow::Result<TopicTranslation> {
+        let row = sqlx::query_as::<_, TopicTranslation>(
+            r#"
+            INSERT INTO topic_translations (topic_id, locale, title, prompt_hint)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (topic_id, locale) DO UPDATE
+            SET title = EXCLUDED.title,
+                prompt_hint = EXCLUDED.prompt_hint
+            RETURNING topic_id, locale, title, prompt_hint, created_at, updated_at
+            "#,
+        )
+        .bind(topic_id)
+        .bind(locale)
+        .bind(&payload.title)
+        .bind(&payload.prompt_hint)
+        .fetch_one(pool)
+        .await?;
+        Ok(row)
+    }
+
+    pub async fn delete(pool: &PgPool, topic_id: Uuid, locale: &str) -> anyhow::Result<bool> {
+        let res = sqlx::query(
+            r#"
+            DELETE FROM topic_translations
+            WHERE topic_id = $1 AND locale = $2
+            "#,
+        )
+        .bind(topic_id)
+        .bind(locale)
+        .execute(pool)
+        .await?;
+        Ok(res.rows_affected() > 0)
+    }
+
+    pub async fn for_topics(
+        pool: &PgPool,
+        topic_ids: &[Uuid],
+        locale: &str,
+    ) -> anyhow::Result<Vec<TopicTranslation>> {
+        let rows = sqlx::query_as::<_, TopicTranslation>(
+            r#"
+            SELECT topic_id, locale, title, prompt_hint, created_at, updated_at
+            FROM topic_translations
+            WHERE topic_id = ANY($1) AND locale = $2
+            "#,
+        )
+        .bind(topic_ids)
+        .bind(locale)
+        .fetch_all(pool)
+        .await?;
+        Ok(rows)
+    }
+}
+
+// Replaces topic text with the translation for `locale` where one exists; untranslated topics
+// keep their default text, and a missing translated hint falls back to the default hint.
+pub async fn localize_topics(
+    pool: &PgPool,
+    topics: &mut [Topic],
+    locale: &str,
+) -> anyhow::Result<()> {
+    if topics.is_empty() {
+        return Ok(());
+    }
+    let ids: Vec<Uuid> = topics.iter().map(|t| t.id).collect();
+    let translations = TopicTranslation::for_topics(pool, &ids, locale).await?;
+    for translation in translations {
+        if let Some(topic) = topics.iter_mut().find(|t| t.id == translation.topic_id) {
+            topic.title = translation.title;
+            if translation.prompt_hint.is_some() {
+                topic.prompt_hint = translation.prompt_hint;
+            }
+        }
+    }
+    Ok(())
+}
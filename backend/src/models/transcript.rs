@@ -1,14 +1,42 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, PgPool};
+use sqlx::{FromRow, PgExecutor, PgPool};
 use uuid::Uuid;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+use crate::encryption::Encryption;
+
+pub const LOW_CONFIDENCE_THRESHOLD: f32 = 0.6;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TranscriptWord {
+    pub word: String,
+    pub start_ms: i64,
+    pub end_ms: i64,
+    pub confidence: Option<f32>,
+    #[serde(default)]
+    pub low_confidence: bool,
+}
+
+impl TranscriptWord {
+    pub fn new(word: String, start_ms: i64, end_ms: i64, confidence: Option<f32>) -> Self {
+        Self {
+            word,
+            start_ms,
+            end_ms,
+            confidence,
+            low_confidence: confidence.is_some_and(|c| c < LOW_CONFIDENCE_THRESHOLD),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TranscriptSegment {
     pub speaker: String,
     pub text: String,
     pub start_ms: i64,
     pub end_ms: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub words: Option<Vec<TranscriptWord>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -17,23 +45,47 @@ pub struct Transcript {
     pub session_id: Uuid,
     pub finalized: bool,
     pub segments: serde_json::Value,
+    pub revision: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TranscriptRevision {
+    pub id: Uuid,
+    pub transcript_id: Uuid,
+    pub revision: i32,
+    pub segments: serde_json::Value,
+    pub author_id: Uuid,
+    pub restored_from: Option<i32>,
     pub created_at: DateTime<Utc>,
 }
 
 pub async fn upsert_transcript(
     pool: &PgPool,
+    encryption: &Encryption,
     session_id: Uuid,
     finalized: bool,
     segments: &[TranscriptSegment],
 ) -> anyhow::Result<Uuid> {
-    let segments_json = serde_json::to_value(segments)?;
+    let mut segments_json = serde_json::to_value(segments)?;
+    if encryption.seals_transcripts() {
+        let owner: Option<Uuid> = sqlx::query_scalar("SELECT user_id FROM sessions WHERE id = $1")
+            .bind(session_id)
+            .fetch_optional(pool)
+            .await?;
+        if let Some(owner) = owner {
+            segments_json = encryption.seal_json(owner, segments_json).await?;
+        }
+    }
     let record: (Uuid,) = sqlx::query_as(
         r#"
-        INSERT INTO transcripts (session_id, finalized, segments)
-        VALUES ($1, $2, $3)
+        INSERT INTO transcripts (session_id, finalized, segments, original_segments)
+        VALUES ($1, $2, $3, $3)
         ON CONFLICT (session_id) DO UPDATE
         SET finalized = EXCLUDED.finalized,
-            segments = EXCLUDED.segments
+            segments = CASE WHEN transcripts.revision > 0 THEN transcripts.segments ELSE EXCLUDED.segments END,
+            redacted_segments = CASE WHEN transcripts.revision > 0 THEN transcripts.redacted_segments ELSE NULL END,
+            original_segments = CASE WHEN transcripts.finalized OR transcripts.revision > 0 THEN transcripts.original_segments ELSE EXCLUDED.segments END
         RETURNING id
         "#,
     )
@@ -47,11 +99,12 @@ pub async fn upsert_transcript(
 
 pub async fn get_transcript_by_session(
     pool: &PgPool,
+    encryption: &Encryption,
     session_id: Uuid,
 ) -> anyhow::Result<Option<Transcript>> {
     let row = sqlx::query_as::<_, Transcript>(
         r#"
-        SELECT id, session_id, finalized, segments, created_at
+        SELECT id, session_id, finalized, segments, revision, created_at
         FROM transcripts
         WHERE session_id = $1
         "#,
@@ -59,5 +112,122 @@ pub async fn get_transcript_by_session(
     .bind(session_id)
     .fetch_optional(pool)
     .await?;
-    Ok(row)
+    match row {
+        Some(mut row) => {
+            row.segments = encryption.open_json(row.segments).await?;
+            Ok(Some(row))
+        }
+        None => Ok(None),
+    }
+}
+
+pub async fn get_redacted_segments(
+    pool: &PgPool,
+    encryption: &Encryption,
+    session_id: Uuid,
+) -> anyhow::Result<Option<Vec<TranscriptSegment>>> {
+    let value: Option<Option<serde_json::Value>> =
+        sqlx::query_scalar("SELECT redacted_segments FROM transcripts WHERE session_id = $1")
+            .bind(session_id)
+            .fetch_optional(pool)
+            .await?;
+    match value.flatten() {
+        Some(value) => Ok(Some(serde_json::from_value(
+            encryption.open_json(value).await?,
+        )?)),
+        None => Ok(None),
+    }
+}
+
+pub async fn set_redacted_segments(
+    pool: &PgPool,
+    encryption: &Encryption,
+    transcript_id: Uuid,
+    segments: &[TranscriptSegment],
+) -> anyhow::Result<()> {
+    let mut redacted_json = serde_json::to_value(segments)?;
+    if encryption.seals_transcripts() {
+        let owner: Option<Uuid> = sqlx::query_scalar(
+            "SELECT s.user_id FROM transcripts t JOIN sessions s ON s.id = t.session_id WHERE t.id = $1",
+        )
+        .bind(transcript_id)
+        .fetch_optional(pool)
+        .await?;
+        if let Some(owner) = owner {
+            redacted_json = encryption.seal_json(owner, redacted_json).await?;
+        }
+    }
+    sqlx::query(
+        r#"
+        UPDATE transcripts
+        SET redacted_segments = $2,
+            redacted_at = now()
+        WHERE id = $1
+        "#,
+    )
+    .bind(transcript_id)
+    .bind(redacted_json)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+pub async fn delete_transcript_by_session(pool: &PgPool, session_id: Uuid) -> anyhow::Result<bool> {
+    let res = sqlx::query("DELETE FROM transcripts WHERE session_id = $1")
+        .bind(session_id)
+        .execute(pool)
+        .await?;
+    Ok(res.rows_affected() > 0)
+}
+
+impl TranscriptRevision {
+    pub async fn list_for_transcript(
+        pool: &PgPool,
+        encryption: &Encryption,
+        transcript_id: Uuid,
+    ) -> anyhow::Result<Vec<TranscriptRevision>> {
+        let rows = sqlx::query_as::<_, TranscriptRevision>(
+            r#"
+            SELECT id, transcript_id, revision, segments, author_id, restored_from, created_at
+            FROM transcript_revisions
+            WHERE transcript_id = $1
+            ORDER BY revision ASC
+            "#,
+        )
+        .bind(transcript_id)
+        .fetch_all(pool)
+        .await?;
+        let mut opened = Vec::with_capacity(rows.len());
+        for mut row in rows {
+            row.segments = encryption.open_json(row.segments).await?;
+            opened.push(row);
+        }
+        Ok(opened)
+    }
+
+    pub async fn get(
+        executor: impl PgExecutor<'_>,
+        encryption: &Encryption,
+        transcript_id: Uuid,
+        revision: i32,
+    ) -> anyhow::Result<Option<TranscriptRevision>> {
+        let row = sqlx::query_as::<_, TranscriptRevision>(
+            r#"
+            SELECT id, transcript_id, revision, segments, author_id, restored_from, created_at
+            FROM transcript_revisions
+            WHERE transcript_id = $1 AND revision = $2
+            "#,
+        )
+        .bind(transcript_id)
+        .bind(revision)
+        .fetch_optional(executor)
+        .await?;
+        match row {
+            Some(mut row) => {
+                row.segments = encryption.open_json(row.segments).await?;
+                Ok(Some(row))
+            }
+            None => Ok(None),
+        }
+    }
 }
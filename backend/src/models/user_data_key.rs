@@ -0,0 +1,101 @@
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+// Never serialized: the wrapped key only leaves the database to be unwrapped.
+#[derive(Debug, Clone, FromRow)]
+pub struct UserDataKey {
+    pub user_id: Uuid,
+    pub master_key_id: String,
+    pub wrapped_key: Vec<u8>,
+    pub created_at: DateTime<Utc>,
+    pub rotated_at: Option<DateTime<Utc>>,
+}
+
+impl UserDataKey {
+    pub async fn get(pool: &PgPool, user_id: Uuid) -> anyhow::Result<Option<UserDataKey>> {
+        let row = sqlx::query_as::<_, UserDataKey>(
+            r#"
+            SELECT user_id, master_key_id, wrapped_key, created_at, rotated_at
+            FROM user_data_keys
+            WHERE user_id = $1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?;
+        Ok(row)
+    }
+
+    // Concurrent first uploads race to create the key; the loser gets the winner's row.
+    pub async fn insert_if_absent(
+        pool: &PgPool,
+        user_id: Uuid,
+        master_key_id: &str,
+        wrapped_key: &[u8],
+    ) -> anyhow::Result<UserDataKey> {
+        sqlx::query(
+            r#"
+            INSERT INTO user_data_keys (user_id, master_key_id, wrapped_key)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (user_id) DO NOTHING
+            "#,
+        )
+        .bind(user_id)
+        .bind(master_key_id)
+        .bind(wrapped_key)
+        .execute(pool)
+        .await?;
+        UserDataKey::get(pool, user_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("data key for {} vanished", user_id))
+    }
+
+    pub async fn list_not_wrapped_by(
+        pool: &PgPool,
+        master_key_id: &str,
+        limit: i64,
+    ) -> anyhow::Result<Vec<UserDataKey>> {
+        let rows = sqlx::query_as::<_, UserDataKey>(
+            r#"
+            SELECT user_id, master_key_id, wrapped_key, created_at, rotated_at
+            FROM user_data_keys
+            WHERE master_key_id <> $1
+            ORDER BY user_id
+            LIMIT $2
+            "#,
+        )
+        .bind(master_key_id)
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+        Ok(rows)
+    }
+
+    // Only replaces the key if it is still wrapped by `from_master`, so two rotations
+    // running at once cannot overwrite each other.
+    pub async fn rewrap(
+        pool: &PgPool,
+        user_id: Uuid,
+        from_master: &str,
+        master_key_id: &str,
+        wrapped_key: &[u8],
+    ) -> anyhow::Result<bool> {
+        let res = sqlx::query(
+            r#"
+            UPDATE user_data_keys
+            SET master_key_id = $3,
+                wrapped_key = $4,
+                rotated_at = now()
+            WHERE user_id = $1 AND master_key_id = $2
+            "#,
+        )
+        .bind(user_id)
+        .bind(from_master)
+        .bind(master_key_id)
+        .bind(wrapped_key)
+        .execute(pool)
+        .await?;
+        Ok(res.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,57 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+pub const ADMIN_ROLE: &str = "admin";
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct UserRole {
+    pub user_id: Uuid,
+    pub role: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl UserRole {
+    pub async fn has_role(pool: &PgPool, user_id: Uuid, role: &str) -> anyhow::Result<bool> {
+        let row: (bool,) = sqlx::query_as(
+            r#"
+            SELECT EXISTS (SELECT 1 FROM user_roles WHERE user_id = $1 AND role = $2)
+            "#,
+        )
+        .bind(user_id)
+        .bind(role)
+        .fetch_one(pool)
+        .await?;
+        Ok(row.0)
+    }
+
+    pub async fn grant(pool: &PgPool, user_id: Uuid, role: &str) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO user_roles (user_id, role)
+            VALUES ($1, $2)
+            ON CONFLICT (user_id, role) DO NOTHING
+            "#,
+        )
+        .bind(user_id)
+        .bind(role)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn revoke(pool: &PgPool, user_id: Uuid, role: &str) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            DELETE FROM user_roles
+            WHERE user_id = $1 AND role = $2
+            "#,
+        )
+        .bind(user_id)
+        .bind(role)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
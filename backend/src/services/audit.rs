@@ -0,0 +1,114 @@
+use std::time::Duration as StdDuration;
+
+use chrono::{DateTime, Duration, Utc};
+use serde_json::Value;
+use sqlx::PgPool;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::auth::ClientInfo;
+use crate::models::audit_event::{AuditEvent, NewAuditEvent};
+
+pub const SESSION_CREATED: &str = "session.create";
+pub const SESSION_FINALIZED: &str = "session.finalize";
+pub const SESSION_DELETED: &str = "session.delete";
+pub const AUDIO_UPLOADED: &str = "audio.upload";
+pub const AUDIO_ACCESSED: &str = "audio.download";
+pub const SHARE_CREATED: &str = "share.create";
+pub const EXPORT_REQUESTED: &str = "export.request";
+pub const EXPORT_DOWNLOADED: &str = "export.download";
+pub const CLIENT_SECRET_ISSUED: &str = "client_secret.issue";
+pub const AUTH_FAILED: &str = "auth.failure";
+
+pub const DEFAULT_AUDIT_RETENTION_DAYS: i64 = 365;
+pub const AUDIT_PURGE_INTERVAL: StdDuration = StdDuration::from_secs(60 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    None,
+    Session(Uuid),
+    Share(Uuid),
+    Export(Uuid),
+}
+
+impl Target {
+    fn parts(self) -> (Option<&'static str>, Option<Uuid>) {
+        match self {
+            Target::None => (None, None),
+            Target::Session(id) => (Some("session"), Some(id)),
+            Target::Share(id) => (Some("session_share"), Some(id)),
+            Target::Export(id) => (Some("data_export"), Some(id)),
+        }
+    }
+}
+
+pub fn event(
+    client: &ClientInfo,
+    actor_id: Option<Uuid>,
+    action: &str,
+    target: Target,
+    details: Value,
+) -> NewAuditEvent {
+    let (target_type, target_id) = target.parts();
+    NewAuditEvent {
+        actor_id,
+        action: action.to_string(),
+        target_type: target_type.map(str::to_string),
+        target_id,
+        ip: client.ip.clone(),
+        user_agent: client.user_agent.clone(),
+        details: if details.is_null() {
+            Value::Object(Default::default())
+        } else {
+            details
+        },
+    }
+}
+
+// Auditing never fails the request it describes; a lost event is logged instead.
+pub async fn record(
+    pool: &PgPool,
+    client: &ClientInfo,
+    actor_id: Option<Uuid>,
+    action: &str,
+    target: Target,
+    details: Value,
+) {
+    let event = event(client, actor_id, action, target, details);
+    if let Err(err) = AuditEvent::insert(pool, &event).await {
+        error!("failed to record audit event {}: {:?}", event.action, err);
+    }
+}
+
+// Configurable through AUDIT_RETENTION_DAYS.
+pub fn retention() -> Duration {
+    std::env::var("AUDIT_RETENTION_DAYS")
+        .ok()
+        .and_then(|raw| raw.trim().parse::<i64>().ok())
+        .filter(|days| *days > 0)
+        .map(Duration::days)
+        .unwrap_or_else(|| Duration::days(DEFAULT_AUDIT_RETENTION_DAYS))
+}
+
+pub async fn purge_expired(
+    pool: &PgPool,
+    retention: Duration,
+    now: DateTime<Utc>,
+) -> anyhow::Result<u64> {
+    AuditEvent::purge_before(pool, now - retention).await
+}
+
+pub fn spawn_audit_purge_worker(pool: PgPool) {
+    tokio::spawn(async move {
+        let retention = retention();
+        let mut ticker = tokio::time::interval(AUDIT_PURGE_INTERVAL);
+        loop {
+            ticker.tick().await;
+            match purge_expired(&pool, retention, Utc::now()).await {
+                Ok(0) => {}
+                Ok(purged) => info!("purged {} expired audit events", purged),
+                Err(err) => error!("audit purge failed: {:?}", err),
+            }
+        }
+    });
+}
@@ -0,0 +1,376 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use crate::encryption::Encryption;
+use crate::models::audio_recording::AudioRecording;
+use crate::models::coaching::{CoachLink, CoachSessionGrant, LINK_ACCEPTED, LINK_DECLINED};
+use crate::models::notification::Notification;
+use crate::models::review_comment::{NewReviewComment, ReviewComment};
+use crate::services::history::{session_detail_for_user, SessionDetail};
+use crate::services::redaction;
+use crate::services::sharing::SharedAudio;
+use crate::services::storage::StorageService;
+
+pub const MAX_COMMENT_CHARS: usize = 5000;
+pub const NOTIFY_REVIEW_COMMENT: &str = "review_comment";
+pub const NOTIFY_REVIEW_REPLY: &str = "review_reply";
+pub const NOTIFY_COACH_INVITATION: &str = "coach_invitation";
+
+#[derive(Debug, thiserror::Error)]
+pub enum CoachingError {
+    #[error("not found")]
+    NotFound,
+    #[error("{0}")]
+    Invalid(String),
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+impl From<sqlx::Error> for CoachingError {
+    fn from(err: sqlx::Error) -> Self {
+        CoachingError::Other(err.into())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionAccess {
+    Owner,
+    Coach,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CommentInput {
+    pub body: String,
+    pub parent_id: Option<Uuid>,
+    pub segment_index: Option<i32>,
+    pub start_ms: Option<i64>,
+    pub end_ms: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct CoachedSession {
+    pub id: Uuid,
+    pub student_id: Uuid,
+    pub topic_id: Uuid,
+    pub topic_title: String,
+    pub start_time: DateTime<Utc>,
+    pub duration_seconds: Option<i32>,
+    pub status: String,
+    pub language: String,
+    pub mode: String,
+    pub comment_count: i64,
+}
+
+// Replies inherit their parent's anchor; top-level comments may point at a
+// transcript segment, an audio range, both, or neither.
+pub fn validate_comment(input: &CommentInput, segment_count: usize) -> Result<(), CoachingError> {
+    let body = input.body.trim();
+    if body.is_empty() {
+        return Err(CoachingError::Invalid("comment body is required".into()));
+    }
+    if body.chars().count() > MAX_COMMENT_CHARS {
+        return Err(CoachingError::Invalid(format!(
+            "comment body is limited to {} characters",
+            MAX_COMMENT_CHARS
+        )));
+    }
+    let anchored =
+        input.segment_index.is_some() || input.start_ms.is_some() || input.end_ms.is_some();
+    if input.parent_id.is_some() && anchored {
+        return Err(CoachingError::Invalid(
+            "replies cannot carry their own anchor".into(),
+        ));
+    }
+    if let Some(index) = input.segment_index {
+        if index < 0 || index as usize >= segment_count {
+            return Err(CoachingError::Invalid(format!(
+                "segment_index {} is outside the transcript",
+                index
+            )));
+        }
+    }
+    match (input.start_ms, input.end_ms) {
+        (None, None) => {}
+        (Some(start), Some(end)) if start >= 0 && end >= start => {}
+        (Some(_), Some(_)) => {
+            return Err(CoachingError::Invalid(
+                "audio range must satisfy 0 <= start_ms <= end_ms".into(),
+            ))
+        }
+        _ => {
+            return Err(CoachingError::Invalid(
+                "start_ms and end_ms must be given together".into(),
+            ))
+        }
+    }
+    Ok(())
+}
+
+pub async fn invite_student(
+    pool: &PgPool,
+    coach_id: Uuid,
+    student_id: Uuid,
+) -> Result<CoachLink, CoachingError> {
+    if coach_id == student_id {
+        return Err(CoachingError::Invalid("you cannot coach yourself".into()));
+    }
+    let (link, invited) = CoachLink::invite(pool, coach_id, student_id).await?;
+    if invited {
+        Notification::create(
+            pool,
+            student_id,
+            NOTIFY_COACH_INVITATION,
+            json!({ "link_id": link.id, "coach_id": coach_id }),
+        )
+        .await?;
+    }
+    Ok(link)
+}
+
+pub async fn respond_to_invitation(
+    pool: &PgPool,
+    link_id: Uuid,
+    student_id: Uuid,
+    accept: bool,
+) -> Result<CoachLink, CoachingError> {
+    let status = if accept { LINK_ACCEPTED } else { LINK_DECLINED };
+    CoachLink::respond(pool, link_id, student_id, status)
+        .await?
+        .ok_or(CoachingError::NotFound)
+}
+
+pub async fn end_link(pool: &PgPool, link_id: Uuid, user_id: Uuid) -> Result<(), CoachingError> {
+    let link = CoachLink::revoke(pool, link_id, user_id)
+        .await?
+        .ok_or(CoachingError::NotFound)?;
+    CoachSessionGrant::revoke_all(pool, link.coach_id, link.student_id).await?;
+    Ok(())
+}
+
+async fn session_owner(pool: &PgPool, session_id: Uuid) -> Result<Uuid, CoachingError> {
+    let owner: Option<Uuid> =
+        sqlx::query_scalar("SELECT user_id FROM sessions WHERE id = $1 AND deleted_at IS NULL")
+            .bind(session_id)
+            .fetch_optional(pool)
+            .await?;
+    owner.ok_or(CoachingError::NotFound)
+}
+
+pub async fn grant_session(
+    pool: &PgPool,
+    session_id: Uuid,
+    student_id: Uuid,
+    coach_id: Uuid,
+) -> Result<(), CoachingError> {
+    if session_owner(pool, session_id).await? != student_id {
+        return Err(CoachingError::NotFound);
+    }
+    if !CoachLink::is_accepted(pool, coach_id, student_id).await? {
+        return Err(CoachingError::Invalid(
+            "this user is not one of your coaches".into(),
+        ));
+    }
+    CoachSessionGrant::grant(pool, session_id, coach_id).await?;
+    Ok(())
+}
+
+pub async fn revoke_session(
+    pool: &PgPool,
+    session_id: Uuid,
+    student_id: Uuid,
+    coach_id: Uuid,
+) -> Result<(), CoachingError> {
+    if session_owner(pool, session_id).await? != student_id {
+        return Err(CoachingError::NotFound);
+    }
+    if !CoachSessionGrant::revoke(pool, session_id, coach_id).await? {
+        return Err(CoachingError::NotFound);
+    }
+    Ok(())
+}
+
+// A coach sees a session only while it is granted and the link is still accepted.
+pub async fn session_access(
+    pool: &PgPool,
+    session_id: Uuid,
+    user_id: Uuid,
+) -> Result<(Uuid, SessionAccess), CoachingError> {
+    let owner = session_owner(pool, session_id).await?;
+    if owner == user_id {
+        return Ok((owner, SessionAccess::Owner));
+    }
+    let granted: bool = sqlx::query_scalar(
+        r#"
+        SELECT EXISTS (
+            SELECT 1
+            FROM coach_session_grants g
+            JOIN coach_links l ON l.coach_id = g.coach_id AND l.student_id = $3
+            WHERE g.session_id = $1 AND g.coach_id = $2 AND l.status = 'accepted'
+        )
+        "#,
+    )
+    .bind(session_id)
+    .bind(user_id)
+    .bind(owner)
+    .fetch_one(pool)
+    .await?;
+    if granted {
+        Ok((owner, SessionAccess::Coach))
+    } else {
+        Err(CoachingError::NotFound)
+    }
+}
+
+pub async fn coached_sessions(
+    pool: &PgPool,
+    coach_id: Uuid,
+    student_id: Option<Uuid>,
+) -> Result<Vec<CoachedSession>, CoachingError> {
+    let rows = sqlx::query_as::<_, CoachedSession>(
+        r#"
+        SELECT s.id, s.user_id AS student_id, s.topic_id, t.title AS topic_title,
+               s.start_time, s.duration_seconds, s.status, s.language, s.mode,
+               (SELECT COUNT(*) FROM review_comments c WHERE c.session_id = s.id) AS comment_count
+        FROM coach_session_grants g
+        JOIN sessions s ON s.id = g.session_id
+        JOIN topics t ON t.id = s.topic_id
+        JOIN coach_links l ON l.coach_id = g.coach_id AND l.student_id = s.user_id
+        WHERE g.coach_id = $1
+          AND l.status = 'accepted'
+          AND s.deleted_at IS NULL
+          AND ($2::uuid IS NULL OR s.user_id = $2)
+        ORDER BY s.start_time DESC, s.id
+        "#,
+    )
+    .bind(coach_id)
+    .bind(student_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+pub async fn coached_session_detail(
+    pool: &PgPool,
+    encryption: &Encryption,
+    session_id: Uuid,
+    coach_id: Uuid,
+) -> Result<SessionDetail, CoachingError> {
+    let (owner, access) = session_access(pool, session_id, coach_id).await?;
+    if access != SessionAccess::Coach {
+        return Err(CoachingError::NotFound);
+    }
+    let mut detail = session_detail_for_user(pool, encryption, session_id, owner).await?;
+    // The unredacted transcript stays with its owner.
+    detail.transcript =
+        redaction::redacted_transcript(pool, encryption, session_id, &detail.transcript).await?;
+    Ok(detail)
+}
+
+// The owner and granted coaches play the recording through here, decrypted, instead of
+// reading ciphertext from the bucket.
+pub async fn session_audio(
+    pool: &PgPool,
+    storage: &StorageService,
+    session_id: Uuid,
+    user_id: Uuid,
+) -> Result<(SessionAccess, SharedAudio), CoachingError> {
+    let (owner, access) = session_access(pool, session_id, user_id).await?;
+    let recording = AudioRecording::get_by_session(pool, session_id)
+        .await?
+        .ok_or(CoachingError::NotFound)?;
+    let bytes = storage
+        .get_bytes_from_url(&recording.storage_url, owner)
+        .await?;
+    Ok((
+        access,
+        SharedAudio {
+            bytes,
+            mime_type: recording.mime_type,
+        },
+    ))
+}
+
+pub async fn list_comments(
+    pool: &PgPool,
+    session_id: Uuid,
+    user_id: Uuid,
+) -> Result<Vec<ReviewComment>, CoachingError> {
+    session_access(pool, session_id, user_id).await?;
+    Ok(ReviewComment::list_for_session(pool, session_id).await?)
+}
+
+pub async fn add_comment(
+    pool: &PgPool,
+    encryption: &Encryption,
+    session_id: Uuid,
+    author_id: Uuid,
+    input: CommentInput,
+) -> Result<ReviewComment, CoachingError> {
+    let (owner, access) = session_access(pool, session_id, author_id).await?;
+    let segment_count = session_detail_for_user(pool, encryption, session_id, owner)
+        .await?
+        .transcript
+        .len();
+    validate_comment(&input, segment_count)?;
+
+    let parent = match input.parent_id {
+        Some(parent_id) => {
+            let parent = ReviewComment::get(pool, parent_id)
+                .await?
+                .filter(|parent| parent.session_id == session_id)
+                .ok_or(CoachingError::NotFound)?;
+            if parent.parent_id.is_some() {
+                return Err(CoachingError::Invalid(
+                    "reply to the top-level comment instead".into(),
+                ));
+            }
+            Some(parent)
+        }
+        None => None,
+    };
+    // Only coaches start threads; students answer them.
+    if parent.is_none() && access == SessionAccess::Owner {
+        return Err(CoachingError::Invalid(
+            "students can only reply to review comments".into(),
+        ));
+    }
+
+    let comment = ReviewComment::create(
+        pool,
+        NewReviewComment {
+            session_id,
+            author_id,
+            parent_id: input.parent_id,
+            body: input.body.trim().to_string(),
+            segment_index: input.segment_index,
+            start_ms: input.start_ms,
+            end_ms: input.end_ms,
+        },
+    )
+    .await?;
+
+    let recipient = match access {
+        SessionAccess::Coach => Some((owner, NOTIFY_REVIEW_COMMENT)),
+        SessionAccess::Owner => parent
+            .map(|parent| parent.author_id)
+            .filter(|id| *id != author_id)
+            .map(|id| (id, NOTIFY_REVIEW_REPLY)),
+    };
+    if let Some((recipient, kind)) = recipient {
+        Notification::create(
+            pool,
+            recipient,
+            kind,
+            json!({
+                "session_id": session_id,
+                "comment_id": comment.id,
+                "author_id": author_id,
+            }),
+        )
+        .await?;
+    }
+    Ok(comment)
+}
@@ -0,0 +1,327 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::models::curriculum::{
+    Curriculum, CurriculumUnit, Enrollment, NewCurriculum, NewCurriculumUnit, UnitTopicCount,
+};
+use crate::models::topic::Topic;
+use crate::services::db::is_unique_violation;
+use crate::services::topics::normalize_title;
+
+pub const MAX_UNITS: usize = 50;
+pub const MAX_TOPICS_PER_UNIT: usize = 20;
+pub const MAX_REQUIRED_SESSIONS: i32 = 100;
+pub const MAX_MIN_DURATION_SECONDS: i32 = 3600;
+
+#[derive(Debug, thiserror::Error)]
+pub enum CurriculumError {
+    #[error("curriculum not found")]
+    NotFound,
+    #[error("not enrolled in this curriculum")]
+    NotEnrolled,
+    #[error("a curriculum with this title already exists")]
+    DuplicateTitle,
+    #[error("{0}")]
+    Invalid(String),
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UnitStatus {
+    Locked,
+    Available,
+    Completed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UnitProgress {
+    pub unit_id: Uuid,
+    pub position: i32,
+    pub title: String,
+    pub required_sessions: i32,
+    pub completed_sessions: i64,
+    pub status: UnitStatus,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct NextTopic {
+    pub unit_id: Uuid,
+    pub topic_id: Uuid,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CurriculumDetail {
+    #[serde(flatten)]
+    pub curriculum: Curriculum,
+    pub units: Vec<CurriculumUnit>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CurriculumProgress {
+    pub curriculum_id: Uuid,
+    pub enrolled_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub completed: bool,
+    pub units: Vec<UnitProgress>,
+    pub next: Option<NextTopic>,
+}
+
+fn sessions_for(counts: &[UnitTopicCount], unit_id: Uuid, topic_id: Uuid) -> i64 {
+    counts
+        .iter()
+        .find(|c| c.unit_id == unit_id && c.topic_id == topic_id)
+        .map(|c| c.sessions)
+        .unwrap_or_default()
+}
+
+// Units unlock strictly in order: a unit is available once every earlier unit is completed.
+pub fn compute_progress(units: &[CurriculumUnit], counts: &[UnitTopicCount]) -> Vec<UnitProgress> {
+    let mut ordered: Vec<&CurriculumUnit> = units.iter().collect();
+    ordered.sort_by_key(|unit| unit.position);
+
+    let mut unlocked = true;
+    ordered
+        .into_iter()
+        .map(|unit| {
+            let completed_sessions: i64 = counts
+                .iter()
+                .filter(|c| c.unit_id == unit.id)
+                .map(|c| c.sessions)
+                .sum();
+            let status = if !unlocked {
+                UnitStatus::Locked
+            } else if completed_sessions >= i64::from(unit.required_sessions) {
+                UnitStatus::Completed
+            } else {
+                unlocked = false;
+                UnitStatus::Available
+            };
+            UnitProgress {
+                unit_id: unit.id,
+                position: unit.position,
+                title: unit.title.clone(),
+                required_sessions: unit.required_sessions,
+                completed_sessions,
+                status,
+            }
+        })
+        .collect()
+}
+
+// Within the first available unit, recommends the topic practiced least so far, preferring
+// earlier topics on ties. Archived topics are never recommended.
+pub fn next_topic(
+    units: &[CurriculumUnit],
+    progress: &[UnitProgress],
+    counts: &[UnitTopicCount],
+    archived: &[Uuid],
+) -> Option<NextTopic> {
+    let current = progress
+        .iter()
+        .find(|p| p.status == UnitStatus::Available)?;
+    let unit = units.iter().find(|u| u.id == current.unit_id)?;
+    unit.topic_ids
+        .iter()
+        .enumerate()
+        .filter(|(_, topic_id)| !archived.contains(topic_id))
+        .min_by_key(|(idx, topic_id)| (sessions_for(counts, unit.id, **topic_id), *idx))
+        .map(|(_, topic_id)| NextTopic {
+            unit_id: unit.id,
+            topic_id: *topic_id,
+        })
+}
+
+fn validate_unit(unit: &NewCurriculumUnit) -> Result<NewCurriculumUnit, CurriculumError> {
+    let title = unit.title.trim();
+    if title.is_empty() {
+        return Err(CurriculumError::Invalid(
+            "unit title must not be empty".into(),
+        ));
+    }
+    if !(1..=MAX_REQUIRED_SESSIONS).contains(&unit.required_sessions) {
+        return Err(CurriculumError::Invalid(format!(
+            "required_sessions must be between 1 and {}",
+            MAX_REQUIRED_SESSIONS
+        )));
+    }
+    if let Some(seconds) = unit.min_duration_seconds {
+        if !(1..=MAX_MIN_DURATION_SECONDS).contains(&seconds) {
+            return Err(CurriculumError::Invalid(format!(
+                "min_duration_seconds must be between 1 and {}",
+                MAX_MIN_DURATION_SECONDS
+            )));
+        }
+    }
+    let mut topic_ids: Vec<Uuid> = Vec::with_capacity(unit.topic_ids.len());
+    for topic_id in &unit.topic_ids {
+        if !topic_ids.contains(topic_id) {
+            topic_ids.push(*topic_id);
+        }
+    }
+    if topic_ids.is_empty() || topic_ids.len() > MAX_TOPICS_PER_UNIT {
+        return Err(CurriculumError::Invalid(format!(
+            "each unit needs between 1 and {} topics",
+            MAX_TOPICS_PER_UNIT
+        )));
+    }
+    Ok(NewCurriculumUnit {
+        title: title.to_string(),
+        required_sessions: unit.required_sessions,
+        min_duration_seconds: unit.min_duration_seconds,
+        topic_ids,
+    })
+}
+
+pub fn validate_new_curriculum(
+    curriculum: &NewCurriculum,
+) -> Result<NewCurriculum, CurriculumError> {
+    let title = normalize_title(&curriculum.title)
+        .map_err(|err| CurriculumError::Invalid(err.to_string()))?;
+    if curriculum.units.is_empty() || curriculum.units.len() > MAX_UNITS {
+        return Err(CurriculumError::Invalid(format!(
+            "a curriculum needs between 1 and {} units",
+            MAX_UNITS
+        )));
+    }
+    Ok(NewCurriculum {
+        title,
+        description: curriculum
+            .description
+            .as_deref()
+            .map(str::trim)
+            .filter(|d| !d.is_empty())
+            .map(str::to_string),
+        units: curriculum
+            .units
+            .iter()
+            .map(validate_unit)
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+pub async fn create_curriculum(
+    pool: &PgPool,
+    curriculum: &NewCurriculum,
+) -> Result<CurriculumDetail, CurriculumError> {
+    let curriculum = validate_new_curriculum(curriculum)?;
+    for topic_id in curriculum.units.iter().flat_map(|u| &u.topic_ids) {
+        match Topic::get(pool, *topic_id).await? {
+            Some(topic)
+                if topic.archived_at.is_none()
+                    && topic.owner_id.is_none()
+                    && topic.org_id.is_none() => {}
+            _ => {
+                return Err(CurriculumError::Invalid(format!(
+                    "topic {} is not an active global topic",
+                    topic_id
+                )))
+            }
+        }
+    }
+    let created = Curriculum::create(pool, &curriculum).await.map_err(|err| {
+        if is_unique_violation(&err) {
+            CurriculumError::DuplicateTitle
+        } else {
+            CurriculumError::Other(err)
+        }
+    })?;
+    get_curriculum(pool, created.id).await
+}
+
+pub async fn list_curricula(pool: &PgPool) -> anyhow::Result<Vec<CurriculumDetail>> {
+    let curricula = Curriculum::list(pool).await?;
+    let ids: Vec<Uuid> = curricula.iter().map(|c| c.id).collect();
+    let mut units = CurriculumUnit::for_curricula(pool, &ids).await?;
+    Ok(curricula
+        .into_iter()
+        .map(|curriculum| {
+            let (own, rest) = units
+                .drain(..)
+                .partition(|u: &CurriculumUnit| u.curriculum_id == curriculum.id);
+            units = rest;
+            CurriculumDetail {
+                curriculum,
+                units: own,
+            }
+        })
+        .collect())
+}
+
+pub async fn get_curriculum(
+    pool: &PgPool,
+    curriculum_id: Uuid,
+) -> Result<CurriculumDetail, CurriculumError> {
+    let curriculum = Curriculum::get(pool, curriculum_id)
+        .await?
+        .filter(|c| c.archived_at.is_none())
+        .ok_or(CurriculumError::NotFound)?;
+    let units = CurriculumUnit::for_curricula(pool, &[curriculum_id]).await?;
+    Ok(CurriculumDetail { curriculum, units })
+}
+
+pub async fn enroll(
+    pool: &PgPool,
+    user_id: Uuid,
+    curriculum_id: Uuid,
+) -> Result<(Enrollment, bool), CurriculumError> {
+    get_curriculum(pool, curriculum_id).await?;
+    Ok(Enrollment::enroll(pool, user_id, curriculum_id).await?)
+}
+
+pub async fn progress(
+    pool: &PgPool,
+    user_id: Uuid,
+    curriculum_id: Uuid,
+) -> Result<CurriculumProgress, CurriculumError> {
+    let detail = get_curriculum(pool, curriculum_id).await?;
+    let enrollment = Enrollment::get(pool, user_id, curriculum_id)
+        .await?
+        .ok_or(CurriculumError::NotEnrolled)?;
+    let counts = enrollment.unit_topic_counts(pool).await?;
+    let topic_ids: Vec<Uuid> = detail
+        .units
+        .iter()
+        .flat_map(|u| u.topic_ids.iter().copied())
+        .collect();
+    let archived = Topic::archived_ids(pool, &topic_ids).await?;
+    let units = compute_progress(&detail.units, &counts);
+    let next = next_topic(&detail.units, &units, &counts, &archived);
+    let completed = units.iter().all(|u| u.status == UnitStatus::Completed);
+
+    Ok(CurriculumProgress {
+        curriculum_id,
+        enrolled_at: enrollment.enrolled_at,
+        completed_at: enrollment.completed_at,
+        completed,
+        units,
+        next,
+    })
+}
+
+// Runs when a session is finalized, so reading progress never writes.
+pub async fn record_completions(pool: &PgPool, user_id: Uuid) -> anyhow::Result<()> {
+    for enrollment in Enrollment::for_user(pool, user_id).await? {
+        if enrollment.completed_at.is_some() {
+            continue;
+        }
+        let active = Curriculum::get(pool, enrollment.curriculum_id)
+            .await?
+            .is_some_and(|c| c.archived_at.is_none());
+        if !active {
+            continue;
+        }
+        let units = CurriculumUnit::for_curricula(pool, &[enrollment.curriculum_id]).await?;
+        let counts = enrollment.unit_topic_counts(pool).await?;
+        if compute_progress(&units, &counts)
+            .iter()
+            .all(|u| u.status == UnitStatus::Completed)
+        {
+            Enrollment::mark_completed(pool, user_id, enrollment.curriculum_id).await?;
+        }
+    }
+    Ok(())
+}
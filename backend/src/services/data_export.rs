@@ -0,0 +1,308 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::io::Write;
+
+use anyhow::bail;
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+use crate::encryption::Encryption;
+use crate::models::audio_recording::AudioRecording;
+use crate::models::curriculum::Enrollment;
+use crate::models::data_export::DataExport;
+use crate::models::session::Session;
+use crate::models::storage_deletion::StorageDeletion;
+use crate::models::topic::Topic;
+use crate::models::transcript::{get_transcript_by_session, TranscriptSegment};
+use crate::services::storage::StorageService;
+use crate::services::transcript_export::{self, ExportFormat, TranscriptDocument};
+
+// How long a finished archive stays downloadable.
+pub const EXPORT_TTL_HOURS: i64 = 24;
+// How long a pending or running export may go without a heartbeat before it is failed.
+pub const EXPORT_LEASE_MINUTES: i64 = 30;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportSession {
+    #[serde(flatten)]
+    pub session: Session,
+    pub topic_title: String,
+    #[serde(skip)]
+    pub transcript: Option<Vec<TranscriptSegment>>,
+    pub audio: Option<AudioRecording>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportBundle {
+    pub user_id: Uuid,
+    pub generated_at: DateTime<Utc>,
+    pub sessions: Vec<ExportSession>,
+    pub private_topics: Vec<Topic>,
+    pub curriculum_enrollments: Vec<Enrollment>,
+}
+
+pub struct AudioFile {
+    pub session_id: Uuid,
+    pub extension: &'static str,
+    pub bytes: Vec<u8>,
+}
+
+#[derive(Serialize)]
+struct Manifest<'a> {
+    user_id: Uuid,
+    generated_at: DateTime<Utc>,
+    session_count: usize,
+    files: &'a [String],
+    // Sessions whose audio could not be fetched from storage.
+    missing_audio: &'a [Uuid],
+}
+
+pub fn audio_extension(mime_type: Option<&str>, storage_url: &str) -> &'static str {
+    match mime_type.map(|m| m.split(';').next().unwrap_or(m).trim()) {
+        Some("audio/webm") => "webm",
+        Some("audio/ogg") => "ogg",
+        Some("audio/mpeg") => "mp3",
+        Some("audio/wav") | Some("audio/x-wav") => "wav",
+        Some("audio/mp4") | Some("audio/m4a") => "m4a",
+        _ => match storage_url.rsplit('.').next() {
+            Some("webm") => "webm",
+            Some("ogg") => "ogg",
+            Some("mp3") => "mp3",
+            Some("wav") => "wav",
+            Some("m4a") => "m4a",
+            _ => "bin",
+        },
+    }
+}
+
+pub fn export_key(user_id: Uuid, export_id: Uuid) -> String {
+    format!("exports/{}/{}.zip", user_id, export_id)
+}
+
+// Layout: manifest.json, sessions.json, topics.json, curricula.json and, per session,
+// sessions/<id>/transcript.{json,txt} plus the original audio.
+pub fn build_archive(
+    bundle: &ExportBundle,
+    audio: &[AudioFile],
+    missing_audio: &[Uuid],
+) -> anyhow::Result<Vec<u8>> {
+    let mut zip = ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default();
+    let mut files: Vec<String> = Vec::new();
+    let mut add = |zip: &mut ZipWriter<_>, name: String, bytes: &[u8]| -> anyhow::Result<()> {
+        zip.start_file(name.as_str(), options)?;
+        zip.write_all(bytes)?;
+        files.push(name);
+        Ok(())
+    };
+
+    add(
+        &mut zip,
+        "sessions.json".into(),
+        &serde_json::to_vec_pretty(&bundle.sessions)?,
+    )?;
+    add(
+        &mut zip,
+        "topics.json".into(),
+        &serde_json::to_vec_pretty(&bundle.private_topics)?,
+    )?;
+    add(
+        &mut zip,
+        "curricula.json".into(),
+        &serde_json::to_vec_pretty(&bundle.curriculum_enrollments)?,
+    )?;
+
+    for entry in &bundle.sessions {
+        let Some(segments) = entry.transcript.as_deref() else {
+            continue;
+        };
+        let doc = TranscriptDocument {
+            topic_title: &entry.topic_title,
+            start_time: entry.session.start_time,
+            segments,
+        };
+        for format in [ExportFormat::Json, ExportFormat::Txt] {
+            let rendered = transcript_export::render(format, &doc)?;
+            add(
+                &mut zip,
+                format!(
+                    "sessions/{}/transcript.{}",
+                    entry.session.id,
+                    format.extension()
+                ),
+                rendered.as_bytes(),
+            )?;
+        }
+    }
+
+    for file in audio {
+        add(
+            &mut zip,
+            format!("sessions/{}/audio.{}", file.session_id, file.extension),
+            &file.bytes,
+        )?;
+    }
+
+    let manifest = Manifest {
+        user_id: bundle.user_id,
+        generated_at: bundle.generated_at,
+        session_count: bundle.sessions.len(),
+        files: &files,
+        missing_audio,
+    };
+    zip.start_file("manifest.json", options)?;
+    zip.write_all(&serde_json::to_vec_pretty(&manifest)?)?;
+
+    Ok(zip.finish()?.into_inner())
+}
+
+pub async fn collect_bundle(
+    pool: &PgPool,
+    encryption: &Encryption,
+    user_id: Uuid,
+) -> anyhow::Result<ExportBundle> {
+    let sessions = Session::list_for_user(pool, user_id).await?;
+    let mut titles: HashMap<Uuid, String> = HashMap::new();
+    let mut entries = Vec::with_capacity(sessions.len());
+    for session in sessions {
+        if let Entry::Vacant(slot) = titles.entry(session.topic_id) {
+            let title = Topic::get(pool, session.topic_id)
+                .await?
+                .map(|t| t.title)
+                .unwrap_or_default();
+            slot.insert(title);
+        }
+        let transcript = get_transcript_by_session(pool, encryption, session.id)
+            .await?
+            .map(|t| serde_json::from_value(t.segments))
+            .transpose()?;
+        let audio = AudioRecording::get_by_session(pool, session.id).await?;
+        entries.push(ExportSession {
+            topic_title: titles[&session.topic_id].clone(),
+            session,
+            transcript,
+            audio,
+        });
+    }
+
+    Ok(ExportBundle {
+        user_id,
+        generated_at: Utc::now(),
+        sessions: entries,
+        private_topics: Topic::list_owned(pool, user_id).await?,
+        curriculum_enrollments: Enrollment::for_user(pool, user_id).await?,
+    })
+}
+
+// Reuses an export that is still being built instead of queueing another.
+pub async fn request_export(pool: &PgPool, user_id: Uuid) -> anyhow::Result<(DataExport, bool)> {
+    fail_stale_exports(pool, Utc::now()).await?;
+    if let Some(existing) = DataExport::in_progress_for_user(pool, user_id).await? {
+        return Ok((existing, false));
+    }
+    Ok((DataExport::create(pool, user_id).await?, true))
+}
+
+async fn build_and_upload(
+    pool: &PgPool,
+    storage: &StorageService,
+    export: &DataExport,
+) -> anyhow::Result<()> {
+    if !DataExport::mark_running(pool, export.id).await? {
+        bail!("export was abandoned before it started");
+    }
+    let bundle = collect_bundle(pool, storage.encryption(), export.user_id).await?;
+
+    let mut audio = Vec::new();
+    let mut missing = Vec::new();
+    for entry in &bundle.sessions {
+        let Some(recording) = entry.audio.as_ref() else {
+            continue;
+        };
+        // Fetching audio is the slow part, so the lease is renewed per recording.
+        if !DataExport::heartbeat(pool, export.id).await? {
+            bail!("export was abandoned while it was being built");
+        }
+        match storage
+            .get_bytes_from_url(&recording.storage_url, export.user_id)
+            .await
+        {
+            Ok(bytes) => audio.push(AudioFile {
+                session_id: entry.session.id,
+                extension: audio_extension(recording.mime_type.as_deref(), &recording.storage_url),
+                bytes,
+            }),
+            Err(err) => {
+                error!(
+                    "export {}: failed to fetch audio for session {}: {:?}",
+                    export.id, entry.session.id, err
+                );
+                missing.push(entry.session.id);
+            }
+        }
+    }
+
+    let archive = build_archive(&bundle, &audio, &missing)?;
+    let key = export_key(export.user_id, export.id);
+    let size = archive.len() as i64;
+    // Sealed like the data it copies; encrypted archives are served through download_export.
+    storage
+        .upload_bytes(&key, archive, Some("application/zip"), Some(export.user_id))
+        .await?;
+    let expires_at = Utc::now() + Duration::hours(EXPORT_TTL_HOURS);
+    let mut tx = pool.begin().await?;
+    if !DataExport::mark_ready(&mut tx, export.id, &key, size, expires_at).await? {
+        // Failed as stale or deleted with the account meanwhile: nothing will hand out or
+        // expire this archive, so it goes straight to the deletion queue.
+        StorageDeletion::enqueue(&mut tx, export.user_id, std::slice::from_ref(&key)).await?;
+        tx.commit().await?;
+        warn!(
+            "export {} was abandoned; queued its archive for deletion",
+            export.id
+        );
+        return Ok(());
+    }
+    tx.commit().await?;
+    info!("export {} ready ({} bytes)", export.id, size);
+    Ok(())
+}
+
+pub fn spawn_export(pool: PgPool, storage: StorageService, export: DataExport) {
+    tokio::spawn(async move {
+        if let Err(err) = build_and_upload(&pool, &storage, &export).await {
+            error!("export {} failed: {:?}", export.id, err);
+            if let Err(err) = DataExport::mark_failed(&pool, export.id, &err.to_string()).await {
+                error!("failed to record export failure: {:?}", err);
+            }
+        }
+    });
+}
+
+pub async fn fail_stale_exports(pool: &PgPool, now: DateTime<Utc>) -> anyhow::Result<u64> {
+    DataExport::fail_stale(pool, now - Duration::minutes(EXPORT_LEASE_MINUTES)).await
+}
+
+// Hands expired archives to the storage deletion queue, so they only outlive their link until
+// the next purge run.
+pub async fn purge_expired_archives(
+    pool: &PgPool,
+    now: DateTime<Utc>,
+    limit: i64,
+) -> anyhow::Result<usize> {
+    let mut tx = pool.begin().await?;
+    let expired = DataExport::take_expired(&mut tx, now, limit).await?;
+    for (user_id, key) in &expired {
+        StorageDeletion::enqueue(&mut tx, *user_id, std::slice::from_ref(key)).await?;
+    }
+    tx.commit().await?;
+    Ok(expired.len())
+}
+
+pub fn is_expired(export: &DataExport, now: DateTime<Utc>) -> bool {
+    export.expires_at.is_some_and(|at| at <= now)
+}
@@ -0,0 +1,7 @@
+// True when a unique constraint rejected the write, wherever the sqlx error sits in the chain.
+pub fn is_unique_violation(err: &anyhow::Error) -> bool {
+    err.chain()
+        .filter_map(|cause| cause.downcast_ref::<sqlx::Error>())
+        .filter_map(sqlx::Error::as_database_error)
+        .any(|db| db.is_unique_violation())
+}
@@ -0,0 +1,365 @@
+use std::time::Duration as StdDuration;
+
+use anyhow::Context;
+use chrono::{DateTime, Duration, Utc};
+use sqlx::PgPool;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::models::session_share::SessionShare;
+use crate::models::storage_deletion::{AccountDeletion, StorageDeletion};
+use crate::services::data_export;
+use crate::services::storage::StorageService;
+
+pub const PURGE_BATCH_SIZE: i64 = 100;
+pub const DEFAULT_RESTORE_WINDOW_HOURS: i64 = 72;
+pub const PURGE_INTERVAL: StdDuration = StdDuration::from_secs(60);
+
+const RETRY_BASE_SECONDS: i64 = 30;
+const RETRY_MAX_SECONDS: i64 = 6 * 60 * 60;
+
+#[derive(Debug, thiserror::Error)]
+pub enum RestoreError {
+    #[error("session not found")]
+    NotFound,
+    #[error("session is not deleted")]
+    NotDeleted,
+    #[error("restore window has passed")]
+    Expired,
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PurgeStats {
+    pub deleted: usize,
+    pub failed: usize,
+}
+
+// Exponential backoff after the given number of attempts, capped at six hours.
+pub fn retry_delay(attempts: i32) -> Duration {
+    let exponent = attempts.saturating_sub(1).clamp(0, 20) as u32;
+    let seconds = RETRY_BASE_SECONDS.saturating_mul(1 << exponent);
+    Duration::seconds(seconds.min(RETRY_MAX_SECONDS))
+}
+
+pub fn next_attempt(now: DateTime<Utc>, attempts: i32) -> DateTime<Utc> {
+    now + retry_delay(attempts)
+}
+
+// Configurable through SESSION_RESTORE_WINDOW_HOURS.
+pub fn restore_window() -> Duration {
+    std::env::var("SESSION_RESTORE_WINDOW_HOURS")
+        .ok()
+        .and_then(|raw| raw.trim().parse::<i64>().ok())
+        .filter(|hours| *hours >= 0)
+        .map(Duration::hours)
+        .unwrap_or_else(|| Duration::hours(DEFAULT_RESTORE_WINDOW_HOURS))
+}
+
+pub fn within_restore_window(
+    deleted_at: DateTime<Utc>,
+    now: DateTime<Utc>,
+    window: Duration,
+) -> bool {
+    now < deleted_at + window
+}
+
+// Fails on any url that does not resolve to a key, so callers roll back instead of dropping
+// a row whose object could then never be removed.
+pub(crate) fn object_keys(
+    storage: &StorageService,
+    urls: Vec<String>,
+) -> anyhow::Result<Vec<String>> {
+    urls.into_iter()
+        .map(|url| {
+            storage
+                .key_from_url(&url)
+                .with_context(|| format!("unrecognised storage url {}", url))
+        })
+        .collect()
+}
+
+// Hides the session from history; it can be restored until the window passes.
+pub async fn delete_session(pool: &PgPool, session_id: Uuid, user_id: Uuid) -> anyhow::Result<()> {
+    let res = sqlx::query(
+        r#"
+        UPDATE sessions
+        SET deleted_at = now()
+        WHERE id = $1 AND user_id = $2 AND deleted_at IS NULL
+        "#,
+    )
+    .bind(session_id)
+    .bind(user_id)
+    .execute(pool)
+    .await?;
+    if res.rows_affected() == 0 {
+        return Err(sqlx::Error::RowNotFound.into());
+    }
+    Ok(())
+}
+
+pub async fn restore_session(
+    pool: &PgPool,
+    session_id: Uuid,
+    user_id: Uuid,
+    window: Duration,
+) -> Result<(), RestoreError> {
+    let deleted_at: Option<Option<DateTime<Utc>>> =
+        sqlx::query_scalar("SELECT deleted_at FROM sessions WHERE id = $1 AND user_id = $2")
+            .bind(session_id)
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await
+            .map_err(anyhow::Error::from)?;
+    let deleted_at = match deleted_at {
+        None => return Err(RestoreError::NotFound),
+        Some(None) => return Err(RestoreError::NotDeleted),
+        Some(Some(at)) => at,
+    };
+    if !within_restore_window(deleted_at, Utc::now(), window) {
+        return Err(RestoreError::Expired);
+    }
+
+    let res = sqlx::query(
+        r#"
+        UPDATE sessions
+        SET deleted_at = NULL
+        WHERE id = $1 AND user_id = $2 AND deleted_at = $3
+        "#,
+    )
+    .bind(session_id)
+    .bind(user_id)
+    .bind(deleted_at)
+    .execute(pool)
+    .await
+    .map_err(anyhow::Error::from)?;
+    if res.rows_affected() == 0 {
+        return Err(RestoreError::NotFound);
+    }
+    Ok(())
+}
+
+// Hard-deletes sessions whose restore window has passed (transcripts and
+// recordings cascade) and queues their audio objects in the same transaction.
+pub async fn purge_tombstoned_sessions(
+    pool: &PgPool,
+    storage: &StorageService,
+    window: Duration,
+    limit: i64,
+) -> anyhow::Result<usize> {
+    let cutoff = Utc::now() - window;
+    let expired: Vec<(Uuid, Uuid)> = sqlx::query_as(
+        r#"
+        SELECT id, user_id FROM sessions
+        WHERE deleted_at <= $1 AND (purge_retry_at IS NULL OR purge_retry_at <= now())
+        ORDER BY deleted_at
+        LIMIT $2
+        "#,
+    )
+    .bind(cutoff)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    let mut purged = 0;
+    for (session_id, user_id) in expired {
+        let mut tx = pool.begin().await?;
+        let urls: Vec<String> =
+            sqlx::query_scalar("SELECT storage_url FROM audio_recordings WHERE session_id = $1")
+                .bind(session_id)
+                .fetch_all(&mut *tx)
+                .await?;
+        let keys = match object_keys(storage, urls) {
+            Ok(keys) => keys,
+            Err(err) => {
+                tx.rollback().await?;
+                error!(
+                    "keeping session {} until its audio can be queued: {:?}",
+                    session_id, err
+                );
+                back_off_purge(pool, SESSIONS, session_id, &err).await?;
+                continue;
+            }
+        };
+        let res = sqlx::query("DELETE FROM sessions WHERE id = $1 AND deleted_at <= $2")
+            .bind(session_id)
+            .bind(cutoff)
+            .execute(&mut *tx)
+            .await?;
+        if res.rows_affected() == 0 {
+            tx.rollback().await?;
+            continue;
+        }
+        StorageDeletion::enqueue(&mut tx, user_id, &keys).await?;
+        tx.commit().await?;
+        purged += 1;
+    }
+    Ok(purged)
+}
+
+// Tables whose rows back off after a failed purge (see the purge_* columns).
+pub(crate) const SESSIONS: &str = "sessions";
+pub(crate) const AUDIO_RECORDINGS: &str = "audio_recordings";
+
+// Pushes a row that failed to purge out by the usual backoff, so it no longer holds up the
+// rows ordered behind it.
+pub(crate) async fn back_off_purge(
+    pool: &PgPool,
+    table: &'static str,
+    id: Uuid,
+    err: &anyhow::Error,
+) -> anyhow::Result<()> {
+    let attempts: i32 = sqlx::query_scalar(&format!(
+        r#"
+        UPDATE {table}
+        SET purge_attempts = purge_attempts + 1, purge_error = $2
+        WHERE id = $1
+        RETURNING purge_attempts
+        "#
+    ))
+    .bind(id)
+    .bind(format!("{:#}", err))
+    .fetch_one(pool)
+    .await?;
+    sqlx::query(&format!(
+        "UPDATE {table} SET purge_retry_at = $2 WHERE id = $1"
+    ))
+    .bind(id)
+    .bind(next_attempt(Utc::now(), attempts))
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+// Removes everything stored for a user and queues their audio and export
+// archives for removal from storage.
+pub async fn delete_account(
+    pool: &PgPool,
+    storage: &StorageService,
+    user_id: Uuid,
+) -> anyhow::Result<AccountDeletion> {
+    let mut tx = pool.begin().await?;
+    let audio_urls: Vec<String> = sqlx::query_scalar(
+        r#"
+        SELECT ar.storage_url
+        FROM audio_recordings ar
+        JOIN sessions s ON s.id = ar.session_id
+        WHERE s.user_id = $1
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(&mut *tx)
+    .await?;
+    let export_keys: Vec<String> = sqlx::query_scalar(
+        "SELECT storage_key FROM data_exports WHERE user_id = $1 AND storage_key IS NOT NULL",
+    )
+    .bind(user_id)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let sessions = sqlx::query("DELETE FROM sessions WHERE user_id = $1")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+    for statement in [
+        "DELETE FROM data_exports WHERE user_id = $1",
+        "DELETE FROM curriculum_enrollments WHERE user_id = $1",
+        "DELETE FROM topics WHERE owner_id = $1",
+        "DELETE FROM user_roles WHERE user_id = $1",
+        "DELETE FROM retention_policies WHERE user_id = $1",
+        "DELETE FROM coach_links WHERE coach_id = $1 OR student_id = $1",
+        "DELETE FROM coach_session_grants WHERE coach_id = $1",
+        "DELETE FROM review_comments WHERE author_id = $1",
+        "DELETE FROM notifications WHERE user_id = $1",
+        "DELETE FROM cohort_members WHERE user_id = $1",
+        "DELETE FROM organization_members WHERE user_id = $1",
+        "DELETE FROM organization_invitations WHERE user_id = $1",
+        // Without the data key, anything still queued for deletion is already unreadable.
+        "DELETE FROM user_data_keys WHERE user_id = $1",
+    ] {
+        sqlx::query(statement)
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    let mut keys = object_keys(storage, audio_urls)?;
+    keys.extend(export_keys);
+    StorageDeletion::enqueue(&mut tx, user_id, &keys).await?;
+    let record =
+        AccountDeletion::record(&mut tx, user_id, sessions as i32, keys.len() as i32).await?;
+    tx.commit().await?;
+
+    info!(
+        "deleted account {}: {} sessions, {} objects queued",
+        user_id,
+        sessions,
+        keys.len()
+    );
+    Ok(record)
+}
+
+pub async fn process_due(
+    pool: &PgPool,
+    storage: &StorageService,
+    limit: i64,
+) -> anyhow::Result<PurgeStats> {
+    let mut stats = PurgeStats::default();
+    for job in StorageDeletion::claim_due(pool, limit).await? {
+        match storage.delete_object(&job.object_key).await {
+            Ok(()) => {
+                StorageDeletion::mark_done(pool, job.id).await?;
+                stats.deleted += 1;
+            }
+            Err(err) => {
+                error!(
+                    "failed to delete {} (attempt {}): {:?}",
+                    job.object_key, job.attempts, err
+                );
+                let retry_at = next_attempt(Utc::now(), job.attempts);
+                StorageDeletion::mark_failed(pool, job.id, &err.to_string(), retry_at).await?;
+                stats.failed += 1;
+            }
+        }
+    }
+    AccountDeletion::complete_drained(pool).await?;
+    Ok(stats)
+}
+
+pub fn spawn_purge_worker(pool: PgPool, storage: StorageService) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(PURGE_INTERVAL);
+        let window = restore_window();
+        loop {
+            ticker.tick().await;
+            match purge_tombstoned_sessions(&pool, &storage, window, PURGE_BATCH_SIZE).await {
+                Ok(0) => {}
+                Ok(purged) => info!("purged {} deleted sessions", purged),
+                Err(err) => error!("session purge run failed: {:?}", err),
+            }
+            if let Err(err) = SessionShare::sync_privacy(&pool, None).await {
+                error!("share privacy sync failed: {:?}", err);
+            }
+            match data_export::fail_stale_exports(&pool, Utc::now()).await {
+                Ok(0) => {}
+                Ok(failed) => info!("failed {} interrupted exports", failed),
+                Err(err) => error!("export lease check failed: {:?}", err),
+            }
+            match data_export::purge_expired_archives(&pool, Utc::now(), PURGE_BATCH_SIZE).await {
+                Ok(0) => {}
+                Ok(queued) => info!("queued {} expired export archives", queued),
+                Err(err) => error!("export expiry run failed: {:?}", err),
+            }
+            match process_due(&pool, &storage, PURGE_BATCH_SIZE).await {
+                Ok(stats) if stats.deleted + stats.failed > 0 => info!(
+                    "storage purge: {} deleted, {} failed",
+                    stats.deleted, stats.failed
+                ),
+                Ok(_) => {}
+                Err(err) => error!("storage purge run failed: {:?}", err),
+            }
+        }
+    });
+}
@@ -0,0 +1,167 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+// Lifetime of a realtime client secret when the mode sets no earlier deadline.
+pub const SECRET_LIFETIME_MINUTES: i64 = 10;
+
+pub const DEFAULT_PREP_SECONDS: i32 = 60;
+pub const DEFAULT_IMPROMPTU_SPEAK_SECONDS: i32 = 120;
+pub const DEFAULT_TIMED_SPEECH_SECONDS: i32 = 300;
+pub const DEFAULT_QA_QUESTIONS: i32 = 5;
+pub const DEFAULT_SECONDS_PER_QUESTION: i32 = 60;
+
+const MAX_PREP_SECONDS: i32 = 600;
+const MAX_SPEAK_SECONDS: i32 = 1800;
+const MAX_QA_QUESTIONS: i32 = 20;
+const MAX_SECONDS_PER_QUESTION: i32 = 300;
+
+fn default_prep_seconds() -> i32 {
+    DEFAULT_PREP_SECONDS
+}
+
+fn default_impromptu_speak_seconds() -> i32 {
+    DEFAULT_IMPROMPTU_SPEAK_SECONDS
+}
+
+fn default_timed_speech_seconds() -> i32 {
+    DEFAULT_TIMED_SPEECH_SECONDS
+}
+
+fn default_qa_questions() -> i32 {
+    DEFAULT_QA_QUESTIONS
+}
+
+fn default_seconds_per_question() -> i32 {
+    DEFAULT_SECONDS_PER_QUESTION
+}
+
+// Stored as `sessions.mode` (the tag) plus `sessions.mode_params` (the remaining fields).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SessionMode {
+    #[default]
+    Free,
+    Impromptu {
+        #[serde(default = "default_prep_seconds")]
+        prep_seconds: i32,
+        #[serde(default = "default_impromptu_speak_seconds")]
+        speak_seconds: i32,
+    },
+    TimedSpeech {
+        #[serde(default = "default_timed_speech_seconds")]
+        speak_seconds: i32,
+    },
+    QaRound {
+        #[serde(default = "default_qa_questions")]
+        questions: i32,
+        #[serde(default = "default_seconds_per_question")]
+        seconds_per_question: i32,
+    },
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("{0}")]
+pub struct InvalidMode(pub String);
+
+fn check_range(field: &str, value: i32, min: i32, max: i32) -> Result<(), InvalidMode> {
+    if (min..=max).contains(&value) {
+        Ok(())
+    } else {
+        Err(InvalidMode(format!(
+            "{} must be between {} and {}",
+            field, min, max
+        )))
+    }
+}
+
+impl SessionMode {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SessionMode::Free => "free",
+            SessionMode::Impromptu { .. } => "impromptu",
+            SessionMode::TimedSpeech { .. } => "timed_speech",
+            SessionMode::QaRound { .. } => "qa_round",
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), InvalidMode> {
+        match *self {
+            SessionMode::Free => Ok(()),
+            SessionMode::Impromptu {
+                prep_seconds,
+                speak_seconds,
+            } => {
+                check_range("prep_seconds", prep_seconds, 0, MAX_PREP_SECONDS)?;
+                check_range("speak_seconds", speak_seconds, 10, MAX_SPEAK_SECONDS)
+            }
+            SessionMode::TimedSpeech { speak_seconds } => {
+                check_range("speak_seconds", speak_seconds, 10, MAX_SPEAK_SECONDS)
+            }
+            SessionMode::QaRound {
+                questions,
+                seconds_per_question,
+            } => {
+                check_range("questions", questions, 1, MAX_QA_QUESTIONS)?;
+                check_range(
+                    "seconds_per_question",
+                    seconds_per_question,
+                    10,
+                    MAX_SECONDS_PER_QUESTION,
+                )
+            }
+        }
+    }
+
+    // Parameters without the `type` tag, as stored in `sessions.mode_params`.
+    pub fn params(&self) -> Value {
+        match serde_json::to_value(self) {
+            Ok(Value::Object(mut map)) => {
+                map.remove("type");
+                Value::Object(map)
+            }
+            _ => Value::Object(Default::default()),
+        }
+    }
+
+    pub fn from_parts(mode: &str, params: &Value) -> anyhow::Result<SessionMode> {
+        let mut map = match params {
+            Value::Object(map) => map.clone(),
+            _ => Default::default(),
+        };
+        map.insert("type".into(), Value::String(mode.to_string()));
+        Ok(serde_json::from_value(Value::Object(map))?)
+    }
+
+    // Total speaking time the server allows, including preparation; `None` is open-ended.
+    pub fn time_limit_seconds(&self) -> Option<i64> {
+        match *self {
+            SessionMode::Free => None,
+            SessionMode::Impromptu {
+                prep_seconds,
+                speak_seconds,
+            } => Some(i64::from(prep_seconds) + i64::from(speak_seconds)),
+            SessionMode::TimedSpeech { speak_seconds } => Some(i64::from(speak_seconds)),
+            SessionMode::QaRound {
+                questions,
+                seconds_per_question,
+            } => Some(i64::from(questions) * i64::from(seconds_per_question)),
+        }
+    }
+
+    pub fn deadline(&self, start_time: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.time_limit_seconds()
+            .map(|limit| start_time + Duration::seconds(limit))
+    }
+}
+
+// Client secrets never outlive the mode's hard cut-off. Returns `None` once the deadline has
+// passed, when no new secret may be issued.
+pub fn secret_expiry(now: DateTime<Utc>, deadline: Option<DateTime<Utc>>) -> Option<DateTime<Utc>> {
+    let default = now + Duration::minutes(SECRET_LIFETIME_MINUTES);
+    match deadline {
+        None => Some(default),
+        Some(deadline) if deadline <= now => None,
+        Some(deadline) => Some(default.min(deadline)),
+    }
+}
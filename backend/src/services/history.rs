@@ -1,8 +1,9 @@
+use crate::encryption::Encryption;
 use crate::models::transcript::TranscriptSegment;
 use anyhow::{anyhow, Context};
 use chrono::{DateTime, Utc};
-use serde::Serialize;
-use sqlx::{types::Json, FromRow, PgPool};
+use serde::{Deserialize, Serialize};
+use sqlx::{types::Json, FromRow, PgPool, Postgres, QueryBuilder};
 use uuid::Uuid;
 
 #[derive(Debug, Serialize, FromRow)]
@@ -14,6 +15,8 @@ pub struct SessionListItem {
     pub duration_seconds: Option<i32>,
     pub status: String,
     pub privacy: String,
+    pub language: String,
+    pub mode: String,
     pub audio_url: Option<String>,
     pub has_audio: bool,
     pub has_transcript: bool,
@@ -29,8 +32,12 @@ struct SessionDetailRow {
     duration_seconds: Option<i32>,
     status: String,
     privacy: String,
+    language: String,
+    mode: String,
+    mode_params: serde_json::Value,
     audio_url: Option<String>,
     transcript_segments: Option<Json<serde_json::Value>>,
+    transcript_revision: Option<i32>,
 }
 
 #[derive(Debug, Serialize)]
@@ -43,15 +50,185 @@ pub struct SessionDetail {
     pub duration_seconds: Option<i32>,
     pub status: String,
     pub privacy: String,
+    pub language: String,
+    pub mode: String,
+    pub mode_params: serde_json::Value,
     pub audio_url: Option<String>,
     pub transcript: Vec<TranscriptSegment>,
+    // Current edit revision, handed out as the ETag for transcript edits.
+    pub transcript_revision: Option<i32>,
+}
+
+pub const DEFAULT_PAGE_SIZE: i64 = 20;
+pub const MAX_PAGE_SIZE: i64 = 100;
+
+#[derive(Debug, Default)]
+pub struct HistoryFilter {
+    pub topic_id: Option<Uuid>,
+    pub status: Option<String>,
+    pub difficulty: Option<String>,
+    pub language: Option<String>,
+    pub mode: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub has_audio: Option<bool>,
+    pub has_transcript: Option<bool>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HistorySort {
+    #[default]
+    Newest,
+    Oldest,
+    Longest,
+    Shortest,
+}
+
+impl HistorySort {
+    fn descending(self) -> bool {
+        matches!(self, HistorySort::Newest | HistorySort::Longest)
+    }
+
+    fn by_duration(self) -> bool {
+        matches!(self, HistorySort::Longest | HistorySort::Shortest)
+    }
+}
+
+// Keyset position of the last row on a page. Duration sorts carry the duration as the leading
+// key; `start_time` and `id` always follow so the order is total and stable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryCursor {
+    pub duration_seconds: Option<i32>,
+    pub start_time: DateTime<Utc>,
+    pub id: Uuid,
+}
+
+impl HistoryCursor {
+    fn from_item(item: &SessionListItem, sort: HistorySort) -> Self {
+        Self {
+            duration_seconds: sort
+                .by_duration()
+                .then(|| item.duration_seconds.unwrap_or_default()),
+            start_time: item.start_time,
+            id: item.id,
+        }
+    }
+
+    pub fn encode(&self) -> String {
+        let micros = self.start_time.timestamp_micros();
+        match self.duration_seconds {
+            Some(duration) => format!("{}_{}_{}", duration, micros, self.id),
+            None => format!("{}_{}", micros, self.id),
+        }
+    }
+
+    pub fn decode(raw: &str, sort: HistorySort) -> anyhow::Result<Self> {
+        let parts: Vec<&str> = raw.split('_').collect();
+        let (duration_seconds, micros, id) = match (sort.by_duration(), parts.as_slice()) {
+            (true, [duration, micros, id]) => (Some(duration.parse::<i32>()?), *micros, *id),
+            (false, [micros, id]) => (None, *micros, *id),
+            _ => return Err(anyhow!("malformed cursor")),
+        };
+        let start_time = DateTime::<Utc>::from_timestamp_micros(micros.parse::<i64>()?)
+            .ok_or_else(|| anyhow!("cursor timestamp out of range"))?;
+        Ok(Self {
+            duration_seconds,
+            start_time,
+            id: Uuid::parse_str(id)?,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct HistoryPageRequest {
+    pub filter: HistoryFilter,
+    pub sort: HistorySort,
+    pub cursor: Option<HistoryCursor>,
+    pub limit: i64,
+}
+
+#[derive(Debug)]
+pub struct HistoryPage {
+    pub sessions: Vec<SessionListItem>,
+    pub next_cursor: Option<String>,
+    pub total: i64,
+}
+
+const HISTORY_FROM: &str = r#"
+        FROM sessions s
+        JOIN topics t ON t.id = s.topic_id
+        LEFT JOIN audio_recordings ar ON ar.session_id = s.id
+        LEFT JOIN transcripts tr ON tr.session_id = s.id
+        "#;
+
+// Tenant isolation: a session's topic must be global, private or from the organization the session
+// was recorded under. Sessions stay with their owner after they leave the organization.
+pub(crate) const TENANT_SCOPE: &str = " AND (t.org_id IS NULL OR t.org_id = s.org_id)";
+
+fn push_history_filters<'a>(
+    qb: &mut QueryBuilder<'a, Postgres>,
+    user_id: Uuid,
+    filter: &'a HistoryFilter,
+) {
+    qb.push(" WHERE s.deleted_at IS NULL AND s.user_id = ")
+        .push_bind(user_id);
+    qb.push(TENANT_SCOPE);
+    if let Some(topic_id) = filter.topic_id {
+        qb.push(" AND s.topic_id = ").push_bind(topic_id);
+    }
+    if let Some(status) = filter.status.as_deref() {
+        qb.push(" AND s.status = ").push_bind(status);
+    }
+    if let Some(difficulty) = filter.difficulty.as_deref() {
+        qb.push(" AND t.difficulty = ").push_bind(difficulty);
+    }
+    if let Some(language) = filter.language.as_deref() {
+        qb.push(" AND s.language = ").push_bind(language);
+    }
+    if let Some(mode) = filter.mode.as_deref() {
+        qb.push(" AND s.mode = ").push_bind(mode);
+    }
+    if let Some(from) = filter.from {
+        qb.push(" AND s.start_time >= ").push_bind(from);
+    }
+    if let Some(to) = filter.to {
+        qb.push(" AND s.start_time < ").push_bind(to);
+    }
+    if let Some(has_audio) = filter.has_audio {
+        qb.push(" AND (ar.id IS NOT NULL) = ").push_bind(has_audio);
+    }
+    if let Some(has_transcript) = filter.has_transcript {
+        qb.push(" AND (tr.id IS NOT NULL) = ")
+            .push_bind(has_transcript);
+    }
+}
+
+// Sealed recordings are ciphertext in the bucket, so owners and coaches get the decrypting
+// session audio route instead of the object URL.
+pub fn playable_audio_url(
+    encryption: &Encryption,
+    session_id: Uuid,
+    storage_url: Option<String>,
+) -> Option<String> {
+    match storage_url {
+        Some(_) if encryption.enabled() => Some(format!("/api/sessions/{}/audio", session_id)),
+        url => url,
+    }
 }
 
 pub async fn list_sessions_for_user(
     pool: &PgPool,
+    encryption: &Encryption,
     user_id: Uuid,
-) -> anyhow::Result<Vec<SessionListItem>> {
-    let rows = sqlx::query_as::<_, SessionListItem>(
+    page: &HistoryPageRequest,
+) -> anyhow::Result<HistoryPage> {
+    let mut count_qb = QueryBuilder::<Postgres>::new("SELECT count(*)");
+    count_qb.push(HISTORY_FROM);
+    push_history_filters(&mut count_qb, user_id, &page.filter);
+    let total: i64 = count_qb.build_query_scalar().fetch_one(pool).await?;
+
+    let mut qb = QueryBuilder::<Postgres>::new(
         r#"
         SELECT
             s.id,
@@ -61,30 +238,84 @@ pub async fn list_sessions_for_user(
             s.duration_seconds,
             s.status,
             s.privacy,
+            s.language,
+            s.mode,
             ar.storage_url as audio_url,
             (ar.id IS NOT NULL) AS has_audio,
             (tr.id IS NOT NULL) AS has_transcript
-        FROM sessions s
-        JOIN topics t ON t.id = s.topic_id
-        LEFT JOIN audio_recordings ar ON ar.session_id = s.id
-        LEFT JOIN transcripts tr ON tr.session_id = s.id
-        WHERE s.user_id = $1
-        ORDER BY s.start_time DESC
         "#,
-    )
-    .bind(user_id)
-    .fetch_all(pool)
-    .await?;
+    );
+    qb.push(HISTORY_FROM);
+    push_history_filters(&mut qb, user_id, &page.filter);
 
-    Ok(rows)
+    let direction = if page.sort.descending() {
+        "DESC"
+    } else {
+        "ASC"
+    };
+    let comparator = if page.sort.descending() { "<" } else { ">" };
+    if let Some(cursor) = page.cursor.as_ref() {
+        if let Some(duration) = cursor.duration_seconds {
+            qb.push(format!(
+                " AND (COALESCE(s.duration_seconds, 0), s.start_time, s.id) {} (",
+                comparator
+            ))
+            .push_bind(duration)
+            .push(", ");
+        } else {
+            qb.push(format!(" AND (s.start_time, s.id) {} (", comparator));
+        }
+        qb.push_bind(cursor.start_time)
+            .push(", ")
+            .push_bind(cursor.id)
+            .push(")");
+    }
+
+    if page.sort.by_duration() {
+        qb.push(format!(
+            " ORDER BY COALESCE(s.duration_seconds, 0) {dir}, s.start_time {dir}, s.id {dir}",
+            dir = direction
+        ));
+    } else {
+        qb.push(format!(
+            " ORDER BY s.start_time {dir}, s.id {dir}",
+            dir = direction
+        ));
+    }
+    // Fetch one extra row to learn whether another page exists.
+    qb.push(" LIMIT ").push_bind(page.limit + 1);
+
+    let mut sessions = qb
+        .build_query_as::<SessionListItem>()
+        .fetch_all(pool)
+        .await?;
+    for item in &mut sessions {
+        item.audio_url = playable_audio_url(encryption, item.id, item.audio_url.take());
+    }
+
+    let next_cursor = if sessions.len() as i64 > page.limit {
+        sessions.truncate(page.limit as usize);
+        sessions
+            .last()
+            .map(|last| HistoryCursor::from_item(last, page.sort).encode())
+    } else {
+        None
+    };
+
+    Ok(HistoryPage {
+        sessions,
+        next_cursor,
+        total,
+    })
 }
 
 pub async fn session_detail_for_user(
     pool: &PgPool,
+    encryption: &Encryption,
     session_id: Uuid,
     user_id: Uuid,
 ) -> anyhow::Result<SessionDetail> {
-    let row = sqlx::query_as::<_, SessionDetailRow>(
+    let mut sql = String::from(
         r#"
         SELECT
             s.id,
@@ -95,24 +326,32 @@ pub async fn session_detail_for_user(
             s.duration_seconds,
             s.status,
             s.privacy,
+            s.language,
+            s.mode,
+            s.mode_params,
             ar.storage_url as audio_url,
-            tr.segments as transcript_segments
+            tr.segments as transcript_segments,
+            tr.revision as transcript_revision
         FROM sessions s
         JOIN topics t ON t.id = s.topic_id
         LEFT JOIN audio_recordings ar ON ar.session_id = s.id
         LEFT JOIN transcripts tr ON tr.session_id = s.id
-        WHERE s.id = $1 AND s.user_id = $2
+        WHERE s.id = $1 AND s.user_id = $2 AND s.deleted_at IS NULL
         "#,
-    )
-    .bind(session_id)
-    .bind(user_id)
-    .fetch_optional(pool)
-    .await?
-    .ok_or_else(|| anyhow!("session not found"))?;
+    );
+    sql.push_str(TENANT_SCOPE);
+    let row = sqlx::query_as::<_, SessionDetailRow>(&sql)
+        .bind(session_id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or(sqlx::Error::RowNotFound)?;
 
     let transcript = match row.transcript_segments {
-        Some(Json(value)) => serde_json::from_value::<Vec<TranscriptSegment>>(value)
-            .context("parse transcript segments")?,
+        Some(Json(value)) => {
+            serde_json::from_value::<Vec<TranscriptSegment>>(encryption.open_json(value).await?)
+                .context("parse transcript segments")?
+        }
         None => Vec::new(),
     };
 
@@ -125,30 +364,112 @@ pub async fn session_detail_for_user(
         duration_seconds: row.duration_seconds,
         status: row.status,
         privacy: row.privacy,
-        audio_url: row.audio_url,
+        language: row.language,
+        mode: row.mode,
+        mode_params: row.mode_params,
+        audio_url: playable_audio_url(encryption, row.id, row.audio_url),
         transcript,
+        transcript_revision: row.transcript_revision,
     })
 }
 
-pub async fn delete_session_for_user(
+#[derive(Debug, Serialize)]
+pub struct CohortStats {
+    pub cohort_id: Uuid,
+    pub member_count: i64,
+    pub active_members: i64,
+    pub session_count: i64,
+    pub total_duration_seconds: i64,
+    pub average_duration_seconds: Option<f64>,
+    pub sessions_by_mode: Vec<ModeCount>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct ModeCount {
+    pub mode: String,
+    pub sessions: i64,
+}
+
+#[derive(FromRow)]
+struct CohortTotals {
+    active_members: i64,
+    session_count: i64,
+    total_duration_seconds: i64,
+    average_duration_seconds: Option<f64>,
+}
+
+// Aggregates only: nothing here identifies a member, a session or its transcript. Sessions
+// count toward the cohort only when they were recorded under `org_id`.
+pub async fn cohort_stats(
     pool: &PgPool,
-    session_id: Uuid,
-    user_id: Uuid,
-) -> anyhow::Result<()> {
-    let res = sqlx::query(
+    org_id: Uuid,
+    cohort_id: Uuid,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+) -> anyhow::Result<CohortStats> {
+    const COHORT_SESSIONS: &str = r#"
+        FROM sessions s
+        JOIN cohort_members cm ON cm.user_id = s.user_id AND cm.cohort_id = $2
+        JOIN cohorts c ON c.id = cm.cohort_id AND c.org_id = $1
+        WHERE s.org_id = $1
+          AND s.deleted_at IS NULL
+          AND ($3::timestamptz IS NULL OR s.start_time >= $3)
+          AND ($4::timestamptz IS NULL OR s.start_time < $4)
+        "#;
+
+    let member_count: i64 = sqlx::query_scalar(
         r#"
-        DELETE FROM sessions
-        WHERE id = $1 AND user_id = $2
+        SELECT count(*)
+        FROM cohort_members cm
+        JOIN cohorts c ON c.id = cm.cohort_id
+        WHERE cm.cohort_id = $2 AND c.org_id = $1
         "#,
     )
-    .bind(session_id)
-    .bind(user_id)
-    .execute(pool)
+    .bind(org_id)
+    .bind(cohort_id)
+    .fetch_one(pool)
     .await?;
 
-    if res.rows_affected() == 0 {
-        return Err(anyhow!("session not found"));
-    }
+    let totals = sqlx::query_as::<_, CohortTotals>(&format!(
+        r#"
+        SELECT count(DISTINCT s.user_id) AS active_members,
+               count(*) AS session_count,
+               COALESCE(sum(s.duration_seconds), 0)::bigint AS total_duration_seconds,
+               avg(s.duration_seconds)::float8 AS average_duration_seconds
+        {}
+        "#,
+        COHORT_SESSIONS
+    ))
+    .bind(org_id)
+    .bind(cohort_id)
+    .bind(from)
+    .bind(to)
+    .fetch_one(pool)
+    .await?;
 
-    Ok(())
+    let sessions_by_mode = sqlx::query_as::<_, ModeCount>(&format!(
+        r#"
+        SELECT s.mode, count(*) AS sessions
+        {}
+        GROUP BY s.mode
+        ORDER BY s.mode
+        "#,
+        COHORT_SESSIONS
+    ))
+    .bind(org_id)
+    .bind(cohort_id)
+    .bind(from)
+    .bind(to)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(CohortStats {
+        cohort_id,
+        member_count,
+        active_members: totals.active_members,
+        session_count: totals.session_count,
+        total_duration_seconds: totals.total_duration_seconds,
+        average_duration_seconds: totals.average_duration_seconds,
+        sessions_by_mode,
+    })
 }
@@ -0,0 +1,42 @@
+pub const DEFAULT_LOCALE: &str = "en";
+pub const SUPPORTED_LOCALES: &[&str] = &["en", "ja", "es"];
+
+// Maps a tag such as `ja-JP` or `ES` to a supported locale.
+pub fn normalize_locale(tag: &str) -> Option<&'static str> {
+    let primary = tag.trim().split(['-', '_']).next()?.to_ascii_lowercase();
+    SUPPORTED_LOCALES
+        .iter()
+        .copied()
+        .find(|locale| *locale == primary)
+}
+
+// Picks the best supported locale from an `Accept-Language` header, honouring q-values.
+pub fn from_accept_language(header: &str) -> Option<&'static str> {
+    let mut candidates: Vec<(f32, usize, &'static str)> = header
+        .split(',')
+        .enumerate()
+        .filter_map(|(idx, part)| {
+            let mut pieces = part.split(';');
+            let tag = pieces.next()?.trim();
+            let quality = pieces
+                .filter_map(|p| p.trim().strip_prefix("q="))
+                .find_map(|q| q.parse::<f32>().ok())
+                .unwrap_or(1.0);
+            if quality <= 0.0 {
+                return None;
+            }
+            normalize_locale(tag).map(|locale| (quality, idx, locale))
+        })
+        .collect();
+    candidates.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.cmp(&b.1)));
+    candidates.first().map(|(_, _, locale)| *locale)
+}
+
+// An explicit `locale` query parameter wins over `Accept-Language`; anything unsupported
+// falls back to the default locale.
+pub fn negotiate(query: Option<&str>, accept_language: Option<&str>) -> &'static str {
+    query
+        .and_then(normalize_locale)
+        .or_else(|| accept_language.and_then(from_accept_language))
+        .unwrap_or(DEFAULT_LOCALE)
+}
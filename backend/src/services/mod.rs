@@ -1,4 +1,23 @@
+pub mod audit;
+pub mod coaching;
+pub mod curricula;
+pub mod data_export;
+pub mod db;
+pub mod deletion;
+pub mod drills;
 pub mod history;
+pub mod locale;
+pub mod organizations;
+pub mod recommendations;
+pub mod redaction;
+pub mod retention;
+pub mod search;
 pub mod sessions;
+pub mod sharing;
 pub mod storage;
+pub mod topic_catalog;
+pub mod topics;
+pub mod transcript_edits;
+pub mod transcript_export;
+pub mod transcript_merge;
 pub mod transcription;
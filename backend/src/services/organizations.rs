@@ -0,0 +1,370 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::models::notification::Notification;
+use crate::models::organization::{
+    Cohort, Organization, OrganizationInvitation, OrganizationMember, ROLE_ADMIN, ROLE_MEMBER,
+};
+use crate::models::topic::{NewTopic, Topic, TopicUpdate};
+use crate::services::db::is_unique_violation;
+use crate::services::history::{self, CohortStats};
+use crate::services::topics::{self, TopicError};
+
+pub const MAX_NAME_LEN: usize = 100;
+pub const NOTIFY_ORG_INVITATION: &str = "org_invitation";
+
+#[derive(Debug, thiserror::Error)]
+pub enum OrgError {
+    #[error("not found")]
+    NotFound,
+    #[error("organization admin role required")]
+    Forbidden,
+    #[error("{0}")]
+    Conflict(String),
+    #[error("{0}")]
+    Invalid(String),
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+impl From<TopicError> for OrgError {
+    fn from(err: TopicError) -> Self {
+        match err {
+            TopicError::NotFound => OrgError::NotFound,
+            TopicError::DuplicateTitle => OrgError::Conflict(err.to_string()),
+            TopicError::Invalid(_) | TopicError::LimitReached(_) => {
+                OrgError::Invalid(err.to_string())
+            }
+            TopicError::Other(inner) => OrgError::Other(inner),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct MyOrganization {
+    pub organization: Organization,
+    pub role: String,
+}
+
+pub fn normalize_name(name: &str) -> Result<String, OrgError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(OrgError::Invalid("name is required".into()));
+    }
+    if name.chars().count() > MAX_NAME_LEN {
+        return Err(OrgError::Invalid(format!(
+            "name is limited to {} characters",
+            MAX_NAME_LEN
+        )));
+    }
+    Ok(name.to_string())
+}
+
+// Owners are only ever created with their organization.
+pub fn validate_role(role: &str) -> Result<&'static str, OrgError> {
+    match role {
+        ROLE_ADMIN => Ok(ROLE_ADMIN),
+        ROLE_MEMBER => Ok(ROLE_MEMBER),
+        other => Err(OrgError::Invalid(format!(
+            "role must be one of: {}, {} (got {})",
+            ROLE_ADMIN, ROLE_MEMBER, other
+        ))),
+    }
+}
+
+// Members of other organizations get `NotFound` so tenants cannot probe each other's ids.
+async fn require_member(
+    pool: &PgPool,
+    org_id: Uuid,
+    user_id: Uuid,
+) -> Result<OrganizationMember, OrgError> {
+    match OrganizationMember::membership(pool, user_id).await? {
+        Some(member) if member.org_id == org_id => Ok(member),
+        _ => Err(OrgError::NotFound),
+    }
+}
+
+async fn require_admin(
+    pool: &PgPool,
+    org_id: Uuid,
+    user_id: Uuid,
+) -> Result<OrganizationMember, OrgError> {
+    let member = require_member(pool, org_id, user_id).await?;
+    if !member.is_admin() {
+        return Err(OrgError::Forbidden);
+    }
+    Ok(member)
+}
+
+async fn org_cohort(pool: &PgPool, org_id: Uuid, cohort_id: Uuid) -> Result<Cohort, OrgError> {
+    Cohort::get(pool, org_id, cohort_id)
+        .await?
+        .ok_or(OrgError::NotFound)
+}
+
+pub async fn create_organization(
+    pool: &PgPool,
+    owner_id: Uuid,
+    name: &str,
+) -> Result<Organization, OrgError> {
+    let name = normalize_name(name)?;
+    match Organization::create(pool, &name, owner_id).await {
+        Ok(Some(org)) => Ok(org),
+        Ok(None) => Err(OrgError::Conflict(
+            "you already belong to an organization".into(),
+        )),
+        Err(err) if is_unique_violation(&err) => Err(OrgError::Conflict(
+            "an organization with this name already exists".into(),
+        )),
+        Err(err) => Err(err.into()),
+    }
+}
+
+pub async fn my_organization(pool: &PgPool, user_id: Uuid) -> Result<MyOrganization, OrgError> {
+    let member = OrganizationMember::membership(pool, user_id)
+        .await?
+        .ok_or(OrgError::NotFound)?;
+    let organization = Organization::get(pool, member.org_id)
+        .await?
+        .ok_or(OrgError::NotFound)?;
+    Ok(MyOrganization {
+        organization,
+        role: member.role,
+    })
+}
+
+pub async fn list_members(
+    pool: &PgPool,
+    org_id: Uuid,
+    actor_id: Uuid,
+) -> Result<Vec<OrganizationMember>, OrgError> {
+    require_admin(pool, org_id, actor_id).await?;
+    Ok(OrganizationMember::list(pool, org_id).await?)
+}
+
+// Membership only starts once the invited user accepts, so admins cannot pull users into
+// their organization on their own.
+pub async fn invite_member(
+    pool: &PgPool,
+    org_id: Uuid,
+    actor_id: Uuid,
+    user_id: Uuid,
+    role: &str,
+) -> Result<OrganizationInvitation, OrgError> {
+    require_admin(pool, org_id, actor_id).await?;
+    let role = validate_role(role)?;
+    if let Some(member) = OrganizationMember::membership(pool, user_id).await? {
+        if member.org_id == org_id {
+            return Err(OrgError::Conflict(
+                "user is already a member of this organization".into(),
+            ));
+        }
+    }
+    let (invitation, invited) =
+        OrganizationInvitation::invite(pool, org_id, user_id, role, actor_id).await?;
+    if invited {
+        Notification::create(
+            pool,
+            user_id,
+            NOTIFY_ORG_INVITATION,
+            json!({ "invitation_id": invitation.id, "org_id": org_id }),
+        )
+        .await?;
+    }
+    Ok(invitation)
+}
+
+pub async fn my_invitations(
+    pool: &PgPool,
+    user_id: Uuid,
+) -> Result<Vec<OrganizationInvitation>, OrgError> {
+    Ok(OrganizationInvitation::pending_for_user(pool, user_id).await?)
+}
+
+pub async fn accept_invitation(
+    pool: &PgPool,
+    invitation_id: Uuid,
+    user_id: Uuid,
+) -> Result<OrganizationMember, OrgError> {
+    if OrganizationMember::membership(pool, user_id)
+        .await?
+        .is_some()
+    {
+        return Err(OrgError::Conflict(
+            "you already belong to an organization".into(),
+        ));
+    }
+    OrganizationInvitation::accept(pool, invitation_id, user_id)
+        .await?
+        .ok_or(OrgError::NotFound)
+}
+
+pub async fn decline_invitation(
+    pool: &PgPool,
+    invitation_id: Uuid,
+    user_id: Uuid,
+) -> Result<OrganizationInvitation, OrgError> {
+    OrganizationInvitation::decline(pool, invitation_id, user_id)
+        .await?
+        .ok_or(OrgError::NotFound)
+}
+
+pub async fn update_member_role(
+    pool: &PgPool,
+    org_id: Uuid,
+    actor_id: Uuid,
+    user_id: Uuid,
+    role: &str,
+) -> Result<OrganizationMember, OrgError> {
+    require_admin(pool, org_id, actor_id).await?;
+    let role = validate_role(role)?;
+    OrganizationMember::set_role(pool, org_id, user_id, role)
+        .await?
+        .ok_or(OrgError::NotFound)
+}
+
+// Admins remove members; anyone but the owner may leave on their own.
+pub async fn remove_member(
+    pool: &PgPool,
+    org_id: Uuid,
+    actor_id: Uuid,
+    user_id: Uuid,
+) -> Result<(), OrgError> {
+    if actor_id == user_id {
+        require_member(pool, org_id, actor_id).await?;
+    } else {
+        require_admin(pool, org_id, actor_id).await?;
+    }
+    if !OrganizationMember::remove(pool, org_id, user_id).await? {
+        return Err(OrgError::NotFound);
+    }
+    Ok(())
+}
+
+pub async fn create_cohort(
+    pool: &PgPool,
+    org_id: Uuid,
+    actor_id: Uuid,
+    name: &str,
+) -> Result<Cohort, OrgError> {
+    require_admin(pool, org_id, actor_id).await?;
+    let name = normalize_name(name)?;
+    Cohort::create(pool, org_id, &name).await.map_err(|err| {
+        if is_unique_violation(&err) {
+            OrgError::Conflict("a cohort with this name already exists".into())
+        } else {
+            OrgError::Other(err)
+        }
+    })
+}
+
+pub async fn list_cohorts(
+    pool: &PgPool,
+    org_id: Uuid,
+    actor_id: Uuid,
+) -> Result<Vec<Cohort>, OrgError> {
+    require_member(pool, org_id, actor_id).await?;
+    Ok(Cohort::list(pool, org_id).await?)
+}
+
+pub async fn cohort_members(
+    pool: &PgPool,
+    org_id: Uuid,
+    actor_id: Uuid,
+    cohort_id: Uuid,
+) -> Result<Vec<Uuid>, OrgError> {
+    require_admin(pool, org_id, actor_id).await?;
+    let cohort = org_cohort(pool, org_id, cohort_id).await?;
+    Ok(Cohort::members(pool, cohort.id).await?)
+}
+
+pub async fn add_cohort_member(
+    pool: &PgPool,
+    org_id: Uuid,
+    actor_id: Uuid,
+    cohort_id: Uuid,
+    user_id: Uuid,
+) -> Result<(), OrgError> {
+    require_admin(pool, org_id, actor_id).await?;
+    let cohort = org_cohort(pool, org_id, cohort_id).await?;
+    match OrganizationMember::membership(pool, user_id).await? {
+        Some(member) if member.org_id == org_id => {}
+        _ => {
+            return Err(OrgError::Invalid(
+                "user is not a member of this organization".into(),
+            ))
+        }
+    }
+    Ok(Cohort::add_member(pool, cohort.id, user_id).await?)
+}
+
+pub async fn remove_cohort_member(
+    pool: &PgPool,
+    org_id: Uuid,
+    actor_id: Uuid,
+    cohort_id: Uuid,
+    user_id: Uuid,
+) -> Result<(), OrgError> {
+    require_admin(pool, org_id, actor_id).await?;
+    let cohort = org_cohort(pool, org_id, cohort_id).await?;
+    if !Cohort::remove_member(pool, cohort.id, user_id).await? {
+        return Err(OrgError::NotFound);
+    }
+    Ok(())
+}
+
+pub async fn cohort_stats(
+    pool: &PgPool,
+    org_id: Uuid,
+    actor_id: Uuid,
+    cohort_id: Uuid,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+) -> Result<CohortStats, OrgError> {
+    require_admin(pool, org_id, actor_id).await?;
+    let cohort = org_cohort(pool, org_id, cohort_id).await?;
+    if let (Some(from), Some(to)) = (from, to) {
+        if from >= to {
+            return Err(OrgError::Invalid("`from` must be before `to`".into()));
+        }
+    }
+    Ok(history::cohort_stats(pool, org_id, cohort.id, from, to).await?)
+}
+
+pub async fn create_org_topic(
+    pool: &PgPool,
+    org_id: Uuid,
+    actor_id: Uuid,
+    topic: &NewTopic,
+) -> Result<Topic, OrgError> {
+    require_admin(pool, org_id, actor_id).await?;
+    Ok(topics::create_org_topic(pool, org_id, topic).await?)
+}
+
+pub async fn list_org_topics(
+    pool: &PgPool,
+    org_id: Uuid,
+    actor_id: Uuid,
+) -> Result<Vec<Topic>, OrgError> {
+    require_member(pool, org_id, actor_id).await?;
+    Ok(Topic::list_for_org(pool, org_id).await?)
+}
+
+pub async fn archive_org_topic(
+    pool: &PgPool,
+    org_id: Uuid,
+    actor_id: Uuid,
+    topic_id: Uuid,
+) -> Result<Topic, OrgError> {
+    require_admin(pool, org_id, actor_id).await?;
+    let update = TopicUpdate {
+        archived: Some(true),
+        ..TopicUpdate::default()
+    };
+    Topic::update(pool, topic_id, None, Some(org_id), &update)
+        .await?
+        .ok_or(OrgError::NotFound)
+}
@@ -0,0 +1,221 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use crate::models::topic::Topic;
+
+pub const DEFAULT_RECOMMENDATION_LIMIT: usize = 10;
+pub const MAX_RECOMMENDATION_LIMIT: usize = 50;
+// How many of the latest sessions decide the target difficulty.
+pub const RECENT_WINDOW: usize = 10;
+// Without a topic estimate, an ended session shorter than this counts as a struggle.
+pub const MIN_SUCCESS_SECONDS: i32 = 60;
+// Review intervals after one, two, three and four-or-more struggles in a row.
+pub const REVIEW_INTERVALS_DAYS: [i64; 4] = [1, 3, 7, 14];
+
+const DIFFICULTY_LADDER: [&str; 3] = ["easy", "medium", "hard"];
+
+#[derive(Debug, Clone, FromRow)]
+pub struct PastSession {
+    pub topic_id: Uuid,
+    pub status: String,
+    pub duration_seconds: Option<i32>,
+    pub start_time: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Success,
+    Struggle,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Recommendation {
+    pub topic: Topic,
+    pub score: f64,
+    pub reason: String,
+}
+
+// Sessions carry no score, so performance is judged by whether the session ended normally and
+// lasted at least half the topic's estimated duration. Active sessions have no outcome yet.
+pub fn session_outcome(session: &PastSession, estimated_seconds: Option<i32>) -> Option<Outcome> {
+    match session.status.as_str() {
+        "failed" => Some(Outcome::Struggle),
+        "ended" => {
+            let bar = estimated_seconds
+                .map(|s| s / 2)
+                .unwrap_or(MIN_SUCCESS_SECONDS);
+            match session.duration_seconds {
+                Some(d) if d >= bar => Some(Outcome::Success),
+                _ => Some(Outcome::Struggle),
+            }
+        }
+        _ => None,
+    }
+}
+
+fn difficulty_rank(difficulty: Option<&str>) -> Option<usize> {
+    DIFFICULTY_LADDER
+        .iter()
+        .position(|d| Some(*d) == difficulty)
+}
+
+// Steps up after a strong recent run, down after a weak one; new users start at easy.
+pub fn target_difficulty(topics: &[Topic], sessions: &[PastSession]) -> &'static str {
+    let recent: Vec<(usize, Outcome)> = sessions
+        .iter()
+        .filter_map(|s| {
+            let topic = topics.iter().find(|t| t.id == s.topic_id)?;
+            let outcome = session_outcome(s, topic.estimated_duration_seconds)?;
+            let rank = difficulty_rank(topic.difficulty.as_deref()).unwrap_or(1);
+            Some((rank, outcome))
+        })
+        .take(RECENT_WINDOW)
+        .collect();
+    if recent.is_empty() {
+        return DIFFICULTY_LADDER[0];
+    }
+
+    let successes = recent
+        .iter()
+        .filter(|(_, o)| *o == Outcome::Success)
+        .count();
+    let rate = successes as f64 / recent.len() as f64;
+    let current = recent.iter().map(|(rank, _)| rank).sum::<usize>() as f64 / recent.len() as f64;
+    let current = current.round() as usize;
+    let rank = if rate >= 0.8 {
+        (current + 1).min(DIFFICULTY_LADDER.len() - 1)
+    } else if rate <= 0.5 {
+        current.saturating_sub(1)
+    } else {
+        current
+    };
+    DIFFICULTY_LADDER[rank]
+}
+
+fn days_between(earlier: DateTime<Utc>, later: DateTime<Utc>) -> f64 {
+    (later - earlier).num_seconds().max(0) as f64 / 86_400.0
+}
+
+// `sessions` must be ordered newest first. Ties are broken by title and then id so the ranking
+// is stable across requests.
+pub fn rank_topics(
+    topics: &[Topic],
+    sessions: &[PastSession],
+    now: DateTime<Utc>,
+    limit: usize,
+) -> Vec<Recommendation> {
+    let target = target_difficulty(topics, sessions);
+    let target_rank = difficulty_rank(Some(target)).unwrap_or(0);
+
+    let mut ranked: Vec<Recommendation> = topics
+        .iter()
+        .filter(|t| t.archived_at.is_none())
+        .map(|topic| {
+            let history: Vec<&PastSession> =
+                sessions.iter().filter(|s| s.topic_id == topic.id).collect();
+            let (score, reason) = score_topic(topic, &history, target, target_rank, now);
+            Recommendation {
+                topic: topic.clone(),
+                score: (score * 100.0).round() / 100.0,
+                reason,
+            }
+        })
+        .collect();
+
+    ranked.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.topic.title.cmp(&b.topic.title))
+            .then_with(|| a.topic.id.cmp(&b.topic.id))
+    });
+    ranked.truncate(limit);
+    ranked
+}
+
+fn score_topic(
+    topic: &Topic,
+    history: &[&PastSession],
+    target: &str,
+    target_rank: usize,
+    now: DateTime<Utc>,
+) -> (f64, String) {
+    let difficulty_score = match difficulty_rank(topic.difficulty.as_deref()) {
+        Some(rank) if rank == target_rank => 2.0,
+        Some(rank) if rank.abs_diff(target_rank) == 1 => 0.5,
+        Some(_) => -1.0,
+        None => 0.5,
+    };
+    let matches_level = difficulty_score >= 2.0;
+
+    let Some(last) = history.first() else {
+        let reason = if matches_level {
+            format!("New {} topic matched to your level", target)
+        } else {
+            "New topic you haven't tried yet".to_string()
+        };
+        return (3.0 + difficulty_score, reason);
+    };
+
+    let days_since = days_between(last.start_time, now);
+    let struggles = history
+        .iter()
+        .filter_map(|s| session_outcome(s, topic.estimated_duration_seconds))
+        .take_while(|o| *o == Outcome::Struggle)
+        .count();
+
+    if struggles > 0 {
+        let interval = REVIEW_INTERVALS_DAYS[(struggles - 1).min(REVIEW_INTERVALS_DAYS.len() - 1)];
+        if days_since >= interval as f64 {
+            return (
+                4.0 + difficulty_score,
+                format!(
+                    "Due for review: last attempt was difficult ({} day interval)",
+                    interval
+                ),
+            );
+        }
+        return (
+            -2.0 + difficulty_score,
+            format!("Review scheduled in {} day(s)", interval),
+        );
+    }
+
+    let recency_score = (days_since / 7.0).min(2.0);
+    let penalty = if days_since < 1.0 { -1.0 } else { 0.0 };
+    let reason = if days_since >= 7.0 {
+        format!("Not practiced in {} days", days_since.floor() as i64)
+    } else if matches_level {
+        format!("Matches your current {} level", target)
+    } else {
+        "Practiced recently".to_string()
+    };
+    (difficulty_score + recency_score + penalty, reason)
+}
+
+pub async fn recent_sessions(pool: &PgPool, user_id: Uuid) -> anyhow::Result<Vec<PastSession>> {
+    let rows = sqlx::query_as::<_, PastSession>(
+        r#"
+        SELECT topic_id, status, duration_seconds, start_time
+        FROM sessions
+        WHERE user_id = $1 AND deleted_at IS NULL
+        ORDER BY start_time DESC, id DESC
+        LIMIT 500
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+pub async fn recommend_topics(
+    pool: &PgPool,
+    user_id: Uuid,
+    limit: usize,
+) -> anyhow::Result<Vec<Recommendation>> {
+    let topics = Topic::list_visible(pool, user_id).await?;
+    let sessions = recent_sessions(pool, user_id).await?;
+    Ok(rank_topics(&topics, &sessions, Utc::now(), limit))
+}
@@ -0,0 +1,221 @@
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+use anyhow::{anyhow, Context};
+use regex::{Captures, Regex};
+use sqlx::PgPool;
+use tracing::error;
+use uuid::Uuid;
+
+use crate::encryption::Encryption;
+use crate::models::transcript::{
+    get_redacted_segments, get_transcript_by_session, set_redacted_segments, Transcript,
+    TranscriptSegment,
+};
+
+pub const BUILTIN_DETECTORS: &[&str] = &["email", "credit_card", "phone", "address"];
+
+const EMAIL_PATTERN: &str = r"(?i)\b[a-z0-9._%+-]+@[a-z0-9.-]+\.[a-z]{2,}\b";
+// 13-19 digits, optionally grouped by spaces or dashes; candidates must also pass Luhn.
+const CREDIT_CARD_PATTERN: &str = r"\b\d(?:[ -]?\d){12,18}\b";
+// Needs a leading `+`, a bracketed area code or separators between the groups, so bare digit
+// runs such as byte counts, durations and ids are left alone.
+const PHONE_PATTERN: &str = r"(?:\+\d{1,3}[\s.-]?(?:\(\d{1,4}\)|\d{2,4})[\s.-]?\d{3,4}[\s.-]?\d{3,4}|\(\d{2,4}\)[\s.-]?\d{3,4}[\s.-]?\d{3,4}|\b\d{2,4}[\s.-]\d{3,4}[\s.-]\d{3,4})\b";
+const ADDRESS_PATTERN: &str = r"(?i)\b\d{1,5}\s+(?:[a-z0-9.'-]+\s+){1,4}(?:street|st|avenue|ave|road|rd|boulevard|blvd|lane|ln|drive|dr|court|ct|way|place|pl|terrace|crescent|square|sq)\b\.?";
+
+#[derive(Debug, Clone)]
+struct Detector {
+    label: String,
+    pattern: Regex,
+    luhn: bool,
+}
+
+impl Detector {
+    fn new(label: &str, pattern: &str) -> anyhow::Result<Self> {
+        Ok(Self {
+            label: label.to_uppercase(),
+            pattern: Regex::new(pattern).with_context(|| format!("detector {}", label))?,
+            luhn: false,
+        })
+    }
+
+    fn builtin(name: &str) -> anyhow::Result<Self> {
+        match name {
+            "email" => Detector::new(name, EMAIL_PATTERN),
+            "phone" => Detector::new(name, PHONE_PATTERN),
+            "address" => Detector::new(name, ADDRESS_PATTERN),
+            "credit_card" => Ok(Detector {
+                luhn: true,
+                ..Detector::new(name, CREDIT_CARD_PATTERN)?
+            }),
+            other => Err(anyhow!("unknown redaction detector {}", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Redactor {
+    detectors: Vec<Detector>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Redacted {
+    pub text: String,
+    // Replacements made, by detector label.
+    pub counts: BTreeMap<String, usize>,
+}
+
+pub fn passes_luhn(candidate: &str) -> bool {
+    let digits: Vec<u32> = candidate.chars().filter_map(|c| c.to_digit(10)).collect();
+    if digits.len() < 13 {
+        return false;
+    }
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &d)| match (i % 2 == 1, d * 2) {
+            (true, doubled) if doubled > 9 => doubled - 9,
+            (true, doubled) => doubled,
+            (false, _) => d,
+        })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
+impl Redactor {
+    // Built-in detectors run in the order given, then custom patterns by name. Card numbers
+    // go before phones so long digit runs are not half-claimed as phone numbers.
+    pub fn new(builtins: &[&str], custom: &BTreeMap<String, String>) -> anyhow::Result<Self> {
+        let mut detectors = builtins
+            .iter()
+            .map(|name| Detector::builtin(name))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        for (name, pattern) in custom {
+            detectors.push(Detector::new(name, pattern)?);
+        }
+        Ok(Self { detectors })
+    }
+
+    // REDACTION_DETECTORS picks built-ins (comma separated, default all of them);
+    // REDACTION_CUSTOM_PATTERNS adds named regexes as a JSON object.
+    pub fn from_env() -> anyhow::Result<Self> {
+        let builtins: Vec<String> = match std::env::var("REDACTION_DETECTORS") {
+            Ok(raw) => raw
+                .split(',')
+                .map(|s| s.trim().to_lowercase())
+                .filter(|s| !s.is_empty())
+                .collect(),
+            Err(_) => BUILTIN_DETECTORS.iter().map(|s| s.to_string()).collect(),
+        };
+        let ordered: Vec<&str> = BUILTIN_DETECTORS
+            .iter()
+            .copied()
+            .filter(|name| builtins.iter().any(|b| b == name))
+            .collect();
+        if let Some(unknown) = builtins
+            .iter()
+            .find(|b| !BUILTIN_DETECTORS.contains(&b.as_str()))
+        {
+            return Err(anyhow!("unknown redaction detector {}", unknown));
+        }
+        let custom = match std::env::var("REDACTION_CUSTOM_PATTERNS") {
+            Ok(raw) if !raw.trim().is_empty() => serde_json::from_str(&raw)
+                .context("REDACTION_CUSTOM_PATTERNS must be a JSON object of name to regex")?,
+            _ => BTreeMap::new(),
+        };
+        Redactor::new(&ordered, &custom)
+    }
+
+    pub fn redact(&self, text: &str) -> Redacted {
+        let mut out = Redacted {
+            text: text.to_string(),
+            counts: BTreeMap::new(),
+        };
+        for detector in &self.detectors {
+            let mut hits = 0;
+            let replacement = format!("[{}]", detector.label);
+            let replaced = detector.pattern.replace_all(&out.text, |caps: &Captures| {
+                if detector.luhn && !passes_luhn(&caps[0]) {
+                    return caps[0].to_string();
+                }
+                hits += 1;
+                replacement.clone()
+            });
+            if hits > 0 {
+                out.text = replaced.into_owned();
+                *out.counts.entry(detector.label.clone()).or_default() += hits;
+            }
+        }
+        out
+    }
+
+    // Word timings cannot be mapped onto replacement tokens, so a segment that had anything
+    // redacted loses its words.
+    pub fn redact_segments(&self, segments: &[TranscriptSegment]) -> Vec<TranscriptSegment> {
+        segments
+            .iter()
+            .map(|segment| {
+                let redacted = self.redact(&segment.text);
+                if redacted.counts.is_empty() {
+                    return segment.clone();
+                }
+                TranscriptSegment {
+                    text: redacted.text,
+                    words: None,
+                    ..segment.clone()
+                }
+            })
+            .collect()
+    }
+}
+
+// A bad configuration must not leave PII unredacted, so it falls back to every built-in.
+pub fn redactor() -> &'static Redactor {
+    static REDACTOR: OnceLock<Redactor> = OnceLock::new();
+    REDACTOR.get_or_init(|| {
+        Redactor::from_env().unwrap_or_else(|err| {
+            error!(
+                "invalid redaction config, using built-in detectors: {:?}",
+                err
+            );
+            Redactor::new(BUILTIN_DETECTORS, &BTreeMap::new()).expect("built-in detectors")
+        })
+    })
+}
+
+pub async fn store_redacted_view(
+    pool: &PgPool,
+    encryption: &Encryption,
+    session_id: Uuid,
+) -> anyhow::Result<()> {
+    let Some(transcript) = get_transcript_by_session(pool, encryption, session_id).await? else {
+        return Ok(());
+    };
+    let segments = parse_segments(&transcript)?;
+    set_redacted_segments(
+        pool,
+        encryption,
+        transcript.id,
+        &redactor().redact_segments(&segments),
+    )
+    .await
+}
+
+// The stored view when there is one; transcripts written before redaction existed are
+// redacted on the fly.
+pub async fn redacted_transcript(
+    pool: &PgPool,
+    encryption: &Encryption,
+    session_id: Uuid,
+    original: &[TranscriptSegment],
+) -> anyhow::Result<Vec<TranscriptSegment>> {
+    match get_redacted_segments(pool, encryption, session_id).await? {
+        Some(segments) => Ok(segments),
+        None => Ok(redactor().redact_segments(original)),
+    }
+}
+
+fn parse_segments(transcript: &Transcript) -> anyhow::Result<Vec<TranscriptSegment>> {
+    serde_json::from_value(transcript.segments.clone()).context("parse transcript segments")
+}
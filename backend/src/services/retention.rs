@@ -0,0 +1,366 @@
+use std::time::Duration as StdDuration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::models::audio_recording::AudioRecording;
+use crate::models::retention_policy::RetentionPolicy;
+use crate::models::storage_deletion::StorageDeletion;
+use crate::models::transcript::delete_transcript_by_session;
+use crate::services::deletion::{back_off_purge, object_keys, AUDIO_RECORDINGS, SESSIONS};
+use crate::services::storage::StorageService;
+
+pub const MAX_RETENTION_DAYS: i32 = 36_500;
+pub const RETENTION_BATCH_SIZE: i64 = 500;
+pub const RETENTION_INTERVAL: StdDuration = StdDuration::from_secs(60 * 60);
+
+#[derive(Debug, thiserror::Error)]
+pub enum RetentionError {
+    #[error("{0}")]
+    Invalid(String),
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+// Days to keep each kind of data, counted from the session start. None keeps it forever.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetentionRule {
+    #[serde(default)]
+    pub audio_days: Option<i32>,
+    #[serde(default)]
+    pub transcript_days: Option<i32>,
+    #[serde(default)]
+    pub metadata_days: Option<i32>,
+}
+
+impl RetentionRule {
+    // Deployment default from RETENTION_AUDIO_DAYS, RETENTION_TRANSCRIPT_DAYS and
+    // RETENTION_METADATA_DAYS.
+    pub fn from_env() -> Self {
+        let days = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .and_then(|raw| raw.trim().parse::<i32>().ok())
+                .filter(|days| *days > 0)
+        };
+        Self {
+            audio_days: days("RETENTION_AUDIO_DAYS"),
+            transcript_days: days("RETENTION_TRANSCRIPT_DAYS"),
+            metadata_days: days("RETENTION_METADATA_DAYS"),
+        }
+    }
+
+    pub fn from_policy(policy: &RetentionPolicy) -> Self {
+        Self {
+            audio_days: policy.audio_days,
+            transcript_days: policy.transcript_days,
+            metadata_days: policy.metadata_days,
+        }
+    }
+
+    // A user override can only shorten the deployment default, never extend it.
+    pub fn effective(self, user: Option<RetentionRule>) -> RetentionRule {
+        let user = user.unwrap_or_default();
+        RetentionRule {
+            audio_days: shortest(self.audio_days, user.audio_days),
+            transcript_days: shortest(self.transcript_days, user.transcript_days),
+            metadata_days: shortest(self.metadata_days, user.metadata_days),
+        }
+    }
+
+    pub fn validate_override(&self, defaults: &RetentionRule) -> Result<(), RetentionError> {
+        for (name, days, limit) in [
+            ("audio_days", self.audio_days, defaults.audio_days),
+            (
+                "transcript_days",
+                self.transcript_days,
+                defaults.transcript_days,
+            ),
+            ("metadata_days", self.metadata_days, defaults.metadata_days),
+        ] {
+            let Some(days) = days else { continue };
+            if !(1..=MAX_RETENTION_DAYS).contains(&days) {
+                return Err(RetentionError::Invalid(format!(
+                    "{} must be between 1 and {}",
+                    name, MAX_RETENTION_DAYS
+                )));
+            }
+            if let Some(limit) = limit.filter(|limit| days > *limit) {
+                return Err(RetentionError::Invalid(format!(
+                    "{} cannot exceed the deployment limit of {} days",
+                    name, limit
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn shortest(a: Option<i32>, b: Option<i32>) -> Option<i32> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UserRetention {
+    pub default: RetentionRule,
+    #[serde(rename = "override")]
+    pub user_override: Option<RetentionRule>,
+    pub effective: RetentionRule,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct RetentionCandidate {
+    pub session_id: Uuid,
+    pub user_id: Uuid,
+    pub start_time: DateTime<Utc>,
+    pub retention_days: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recording_id: Option<Uuid>,
+    #[serde(skip)]
+    pub storage_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RetentionReport {
+    pub generated_at: DateTime<Utc>,
+    pub defaults: RetentionRule,
+    pub sessions: Vec<RetentionCandidate>,
+    pub audio: Vec<RetentionCandidate>,
+    pub transcripts: Vec<RetentionCandidate>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct RetentionSummary {
+    pub sessions: usize,
+    pub audio: usize,
+    pub transcripts: usize,
+}
+
+pub async fn user_retention(
+    pool: &PgPool,
+    defaults: RetentionRule,
+    user_id: Uuid,
+) -> anyhow::Result<UserRetention> {
+    let user_override = RetentionPolicy::get(pool, user_id)
+        .await?
+        .map(|policy| RetentionRule::from_policy(&policy));
+    Ok(UserRetention {
+        default: defaults,
+        user_override,
+        effective: defaults.effective(user_override),
+    })
+}
+
+pub async fn set_user_override(
+    pool: &PgPool,
+    defaults: RetentionRule,
+    user_id: Uuid,
+    rule: RetentionRule,
+) -> Result<UserRetention, RetentionError> {
+    rule.validate_override(&defaults)?;
+    RetentionPolicy::upsert(
+        pool,
+        user_id,
+        rule.audio_days,
+        rule.transcript_days,
+        rule.metadata_days,
+    )
+    .await?;
+    Ok(user_retention(pool, defaults, user_id).await?)
+}
+
+// LEAST ignores NULLs, so a missing default or override falls back to the other
+// and rows with neither are never selected.
+async fn session_candidates(
+    pool: &PgPool,
+    defaults: &RetentionRule,
+    now: DateTime<Utc>,
+    limit: i64,
+) -> anyhow::Result<Vec<RetentionCandidate>> {
+    let rows = sqlx::query_as::<_, RetentionCandidate>(
+        r#"
+        SELECT s.id AS session_id, s.user_id, s.start_time,
+               LEAST($1::int, rp.metadata_days) AS retention_days,
+               NULL::uuid AS recording_id, NULL::text AS storage_url
+        FROM sessions s
+        LEFT JOIN retention_policies rp ON rp.user_id = s.user_id
+        WHERE s.start_time < $2 - make_interval(days => LEAST($1::int, rp.metadata_days))
+          AND (s.purge_retry_at IS NULL OR s.purge_retry_at <= $2)
+        ORDER BY s.start_time
+        LIMIT $3
+        "#,
+    )
+    .bind(defaults.metadata_days)
+    .bind(now)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+async fn audio_candidates(
+    pool: &PgPool,
+    defaults: &RetentionRule,
+    now: DateTime<Utc>,
+    limit: i64,
+) -> anyhow::Result<Vec<RetentionCandidate>> {
+    let rows = sqlx::query_as::<_, RetentionCandidate>(
+        r#"
+        SELECT s.id AS session_id, s.user_id, s.start_time,
+               LEAST($1::int, rp.audio_days) AS retention_days,
+               ar.id AS recording_id, ar.storage_url
+        FROM audio_recordings ar
+        JOIN sessions s ON s.id = ar.session_id
+        LEFT JOIN retention_policies rp ON rp.user_id = s.user_id
+        WHERE s.start_time < $2 - make_interval(days => LEAST($1::int, rp.audio_days))
+          AND (ar.purge_retry_at IS NULL OR ar.purge_retry_at <= $2)
+        ORDER BY s.start_time
+        LIMIT $3
+        "#,
+    )
+    .bind(defaults.audio_days)
+    .bind(now)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+async fn transcript_candidates(
+    pool: &PgPool,
+    defaults: &RetentionRule,
+    now: DateTime<Utc>,
+    limit: i64,
+) -> anyhow::Result<Vec<RetentionCandidate>> {
+    let rows = sqlx::query_as::<_, RetentionCandidate>(
+        r#"
+        SELECT s.id AS session_id, s.user_id, s.start_time,
+               LEAST($1::int, rp.transcript_days) AS retention_days,
+               NULL::uuid AS recording_id, NULL::text AS storage_url
+        FROM transcripts tr
+        JOIN sessions s ON s.id = tr.session_id
+        LEFT JOIN retention_policies rp ON rp.user_id = s.user_id
+        WHERE s.start_time < $2 - make_interval(days => LEAST($1::int, rp.transcript_days))
+        ORDER BY s.start_time
+        LIMIT $3
+        "#,
+    )
+    .bind(defaults.transcript_days)
+    .bind(now)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+// Dry run: lists what `enforce` would remove without touching anything.
+pub async fn report(
+    pool: &PgPool,
+    defaults: RetentionRule,
+    now: DateTime<Utc>,
+    limit: i64,
+) -> anyhow::Result<RetentionReport> {
+    Ok(RetentionReport {
+        generated_at: now,
+        defaults,
+        sessions: session_candidates(pool, &defaults, now, limit).await?,
+        audio: audio_candidates(pool, &defaults, now, limit).await?,
+        transcripts: transcript_candidates(pool, &defaults, now, limit).await?,
+    })
+}
+
+// Whole sessions go first so their audio and transcripts are not processed twice.
+// Audio objects are handed to the storage deletion queue.
+pub async fn enforce(
+    pool: &PgPool,
+    storage: &StorageService,
+    defaults: RetentionRule,
+    now: DateTime<Utc>,
+    limit: i64,
+) -> anyhow::Result<RetentionSummary> {
+    let mut summary = RetentionSummary::default();
+
+    for candidate in session_candidates(pool, &defaults, now, limit).await? {
+        let mut tx = pool.begin().await?;
+        let urls: Vec<String> =
+            sqlx::query_scalar("SELECT storage_url FROM audio_recordings WHERE session_id = $1")
+                .bind(candidate.session_id)
+                .fetch_all(&mut *tx)
+                .await?;
+        let keys = match object_keys(storage, urls) {
+            Ok(keys) => keys,
+            Err(err) => {
+                tx.rollback().await?;
+                error!("retention kept session {}: {:?}", candidate.session_id, err);
+                back_off_purge(pool, SESSIONS, candidate.session_id, &err).await?;
+                continue;
+            }
+        };
+        let res = sqlx::query("DELETE FROM sessions WHERE id = $1")
+            .bind(candidate.session_id)
+            .execute(&mut *tx)
+            .await?;
+        if res.rows_affected() == 0 {
+            tx.rollback().await?;
+            continue;
+        }
+        StorageDeletion::enqueue(&mut tx, candidate.user_id, &keys).await?;
+        tx.commit().await?;
+        summary.sessions += 1;
+    }
+
+    for candidate in audio_candidates(pool, &defaults, now, limit).await? {
+        let Some(recording_id) = candidate.recording_id else {
+            continue;
+        };
+        let mut tx = pool.begin().await?;
+        let Some(recording) = AudioRecording::delete(&mut tx, recording_id).await? else {
+            tx.rollback().await?;
+            continue;
+        };
+        let keys = match object_keys(storage, vec![recording.storage_url]) {
+            Ok(keys) => keys,
+            Err(err) => {
+                tx.rollback().await?;
+                error!("retention kept recording {}: {:?}", recording_id, err);
+                back_off_purge(pool, AUDIO_RECORDINGS, recording_id, &err).await?;
+                continue;
+            }
+        };
+        StorageDeletion::enqueue(&mut tx, candidate.user_id, &keys).await?;
+        tx.commit().await?;
+        summary.audio += 1;
+    }
+
+    for candidate in transcript_candidates(pool, &defaults, now, limit).await? {
+        if delete_transcript_by_session(pool, candidate.session_id).await? {
+            summary.transcripts += 1;
+        }
+    }
+
+    Ok(summary)
+}
+
+pub fn spawn_retention_worker(pool: PgPool, storage: StorageService) {
+    tokio::spawn(async move {
+        let defaults = RetentionRule::from_env();
+        let mut ticker = tokio::time::interval(RETENTION_INTERVAL);
+        loop {
+            ticker.tick().await;
+            match enforce(&pool, &storage, defaults, Utc::now(), RETENTION_BATCH_SIZE).await {
+                Ok(summary) if summary != RetentionSummary::default() => info!(
+                    "retention purged {} sessions, {} recordings, {} transcripts",
+                    summary.sessions, summary.audio, summary.transcripts
+                ),
+                Ok(_) => {}
+                Err(err) => error!("retention run failed: {:?}", err),
+            }
+        }
+    });
+}
@@ -0,0 +1,114 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use crate::services::history::TENANT_SCOPE;
+
+pub const DEFAULT_SEARCH_LIMIT: i64 = 20;
+pub const MAX_SEARCH_LIMIT: i64 = 50;
+
+#[derive(FromRow)]
+struct SearchMatchRow {
+    session_id: Uuid,
+    topic_id: Uuid,
+    topic_title: String,
+    start_time: DateTime<Utc>,
+    rank: f32,
+    speaker: Option<String>,
+    start_ms: Option<i64>,
+    snippet: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SearchMatch {
+    pub speaker: Option<String>,
+    pub start_ms: Option<i64>,
+    pub snippet: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SessionSearchResult {
+    pub session_id: Uuid,
+    pub topic_id: Uuid,
+    pub topic_title: String,
+    pub start_time: DateTime<Utc>,
+    pub rank: f32,
+    pub matches: Vec<SearchMatch>,
+}
+
+pub async fn search_transcripts_for_user(
+    pool: &PgPool,
+    user_id: Uuid,
+    query: &str,
+    limit: i64,
+) -> anyhow::Result<Vec<SessionSearchResult>> {
+    let sql = format!(
+        r#"
+        WITH matched AS (
+            SELECT
+                tr.session_id,
+                tr.segments,
+                tr.search_config,
+                q.query,
+                ts_rank(tr.search_vector, q.query) AS rank
+            FROM transcripts tr
+            JOIN sessions s ON s.id = tr.session_id
+            JOIN topics t ON t.id = s.topic_id
+            CROSS JOIN LATERAL websearch_to_tsquery(tr.search_config, $2) AS q(query)
+            WHERE s.user_id = $1 AND s.deleted_at IS NULL AND tr.search_vector @@ q.query
+              {TENANT_SCOPE}
+            ORDER BY rank DESC, s.start_time DESC
+            LIMIT $3
+        )
+        SELECT
+            s.id as session_id,
+            s.topic_id,
+            t.title as topic_title,
+            s.start_time,
+            m.rank,
+            seg.value ->> 'speaker' as speaker,
+            (seg.value ->> 'start_ms')::bigint as start_ms,
+            ts_headline(
+                m.search_config,
+                seg.value ->> 'text',
+                m.query,
+                'StartSel=<mark>, StopSel=</mark>, MaxWords=20, MinWords=8'
+            ) as snippet
+        FROM matched m
+        JOIN sessions s ON s.id = m.session_id
+        JOIN topics t ON t.id = s.topic_id
+        CROSS JOIN LATERAL jsonb_array_elements(m.segments) WITH ORDINALITY AS seg(value, ord)
+        WHERE to_tsvector(m.search_config, coalesce(seg.value ->> 'text', '')) @@ m.query
+        ORDER BY m.rank DESC, s.start_time DESC, s.id, seg.ord
+        "#
+    );
+    let rows = sqlx::query_as::<_, SearchMatchRow>(&sql)
+        .bind(user_id)
+        .bind(query)
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+
+    let mut results: Vec<SessionSearchResult> = Vec::new();
+    for row in rows {
+        let hit = SearchMatch {
+            speaker: row.speaker,
+            start_ms: row.start_ms,
+            snippet: row.snippet,
+        };
+        match results.last_mut() {
+            Some(last) if last.session_id == row.session_id => last.matches.push(hit),
+            _ => results.push(SessionSearchResult {
+                session_id: row.session_id,
+                topic_id: row.topic_id,
+                topic_title: row.topic_title,
+                start_time: row.start_time,
+                rank: row.rank,
+                matches: vec![hit],
+            }),
+        }
+    }
+
+    Ok(results)
+}
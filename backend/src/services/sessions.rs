@@ -1,22 +1,60 @@
+use crate::models::organization::OrganizationMember;
 use crate::models::session::{FinalizeSession, NewSession, Session};
+use crate::models::topic::Topic;
+use crate::services::drills::SessionMode;
 use chrono::{DateTime, Utc};
 use sqlx::PgPool;
 use uuid::Uuid;
 
+#[derive(Debug, thiserror::Error)]
+pub enum CreateSessionError {
+    // Also returned for topics owned by another user or organization.
+    #[error("topic {0} not found")]
+    TopicNotFound(Uuid),
+    #[error("topic {0} is archived")]
+    TopicArchived(Uuid),
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
 pub async fn create_session(
     pool: &PgPool,
     user_id: Uuid,
     topic_id: Uuid,
-) -> anyhow::Result<Session> {
-    Session::create(
+    language: &str,
+    mode: &SessionMode,
+) -> Result<Session, CreateSessionError> {
+    let topic = Topic::get(pool, topic_id)
+        .await?
+        .ok_or(CreateSessionError::TopicNotFound(topic_id))?;
+    if topic.archived_at.is_some() {
+        return Err(CreateSessionError::TopicArchived(topic_id));
+    }
+    if topic.owner_id.is_some_and(|owner| owner != user_id) {
+        return Err(CreateSessionError::TopicNotFound(topic_id));
+    }
+    if let Some(org_id) = topic.org_id {
+        let member_of = OrganizationMember::membership(pool, user_id)
+            .await?
+            .map(|m| m.org_id);
+        if member_of != Some(org_id) {
+            return Err(CreateSessionError::TopicNotFound(topic_id));
+        }
+    }
+
+    let session = Session::create(
         pool,
         NewSession {
             user_id,
             topic_id,
             status: "active".into(),
+            language: language.to_string(),
+            mode: mode.name().to_string(),
+            mode_params: mode.params(),
         },
     )
-    .await
+    .await?;
+    Ok(session)
 }
 
 pub async fn finalize_session(
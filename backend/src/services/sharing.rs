@@ -0,0 +1,257 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::auth::ClientInfo;
+use crate::models::audio_recording::AudioRecording;
+use crate::models::session_share::SessionShare;
+use crate::models::transcript::TranscriptSegment;
+use crate::services::audit::{self, Target};
+use crate::services::history::session_detail_for_user;
+use crate::services::redaction;
+use crate::services::storage::StorageService;
+
+pub const DEFAULT_SHARE_HOURS: i64 = 7 * 24;
+pub const MAX_SHARE_HOURS: i64 = 30 * 24;
+// Presigned audio links are short-lived even when the share lasts longer.
+pub const SHARED_AUDIO_URL_SECONDS: i64 = 60 * 60;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ShareError {
+    #[error("session not found")]
+    NotFound,
+    #[error("{0}")]
+    Invalid(String),
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShareScope {
+    #[default]
+    Transcript,
+    TranscriptAudio,
+}
+
+impl ShareScope {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ShareScope::Transcript => "transcript",
+            ShareScope::TranscriptAudio => "transcript_audio",
+        }
+    }
+
+    pub fn parse(raw: &str) -> Option<ShareScope> {
+        match raw {
+            "transcript" => Some(ShareScope::Transcript),
+            "transcript_audio" => Some(ShareScope::TranscriptAudio),
+            _ => None,
+        }
+    }
+
+    pub fn includes_audio(self) -> bool {
+        matches!(self, ShareScope::TranscriptAudio)
+    }
+}
+
+// What an anonymous viewer sees; deliberately has no owner or session ids.
+#[derive(Debug, Clone, Serialize)]
+pub struct SharedSession {
+    pub topic_title: String,
+    pub start_time: DateTime<Utc>,
+    pub end_time: Option<DateTime<Utc>>,
+    pub duration_seconds: Option<i32>,
+    pub language: String,
+    pub mode: String,
+    pub transcript: Vec<TranscriptSegment>,
+    pub audio_url: Option<String>,
+    pub scope: ShareScope,
+    pub expires_at: DateTime<Utc>,
+    pub view_count: i32,
+}
+
+// Two v4 UUIDs give 244 random bits, rendered as 64 hex characters.
+pub fn generate_token() -> String {
+    format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
+
+pub fn share_expiry(now: DateTime<Utc>, hours: Option<i64>) -> Result<DateTime<Utc>, ShareError> {
+    let hours = hours.unwrap_or(DEFAULT_SHARE_HOURS);
+    if !(1..=MAX_SHARE_HOURS).contains(&hours) {
+        return Err(ShareError::Invalid(format!(
+            "expires_in_hours must be between 1 and {}",
+            MAX_SHARE_HOURS
+        )));
+    }
+    Ok(now + Duration::hours(hours))
+}
+
+async fn ensure_owner(pool: &PgPool, session_id: Uuid, user_id: Uuid) -> Result<(), ShareError> {
+    let owned: bool = sqlx::query_scalar(
+        r#"
+        SELECT EXISTS (
+            SELECT 1 FROM sessions
+            WHERE id = $1 AND user_id = $2 AND deleted_at IS NULL
+        )
+        "#,
+    )
+    .bind(session_id)
+    .bind(user_id)
+    .fetch_one(pool)
+    .await
+    .map_err(anyhow::Error::from)?;
+    if owned {
+        Ok(())
+    } else {
+        Err(ShareError::NotFound)
+    }
+}
+
+pub async fn create_share(
+    pool: &PgPool,
+    session_id: Uuid,
+    user_id: Uuid,
+    scope: ShareScope,
+    expires_in_hours: Option<i64>,
+) -> Result<SessionShare, ShareError> {
+    let expires_at = share_expiry(Utc::now(), expires_in_hours)?;
+    ensure_owner(pool, session_id, user_id).await?;
+    let share = SessionShare::create(
+        pool,
+        session_id,
+        &generate_token(),
+        scope.as_str(),
+        expires_at,
+    )
+    .await?;
+    SessionShare::sync_privacy(pool, Some(session_id)).await?;
+    Ok(share)
+}
+
+pub async fn list_shares(
+    pool: &PgPool,
+    session_id: Uuid,
+    user_id: Uuid,
+) -> Result<Vec<SessionShare>, ShareError> {
+    ensure_owner(pool, session_id, user_id).await?;
+    Ok(SessionShare::list_for_session(pool, session_id).await?)
+}
+
+pub async fn revoke_share(
+    pool: &PgPool,
+    session_id: Uuid,
+    user_id: Uuid,
+    share_id: Uuid,
+) -> Result<(), ShareError> {
+    ensure_owner(pool, session_id, user_id).await?;
+    if !SessionShare::revoke(pool, session_id, share_id).await? {
+        return Err(ShareError::NotFound);
+    }
+    SessionShare::sync_privacy(pool, Some(session_id)).await?;
+    Ok(())
+}
+
+pub struct SharedAudio {
+    pub bytes: Vec<u8>,
+    pub mime_type: Option<String>,
+}
+
+// Serves decrypted audio for a share that includes it; used when the stored object is
+// encrypted and a presigned link would hand out ciphertext.
+pub async fn shared_audio(
+    pool: &PgPool,
+    storage: &StorageService,
+    client: &ClientInfo,
+    token: &str,
+) -> Result<SharedAudio, ShareError> {
+    let Some(share) = SessionShare::resolve(pool, token).await? else {
+        return Err(ShareError::NotFound);
+    };
+    let includes_audio = ShareScope::parse(&share.scope).is_some_and(ShareScope::includes_audio);
+    let recording = match AudioRecording::get_by_session(pool, share.session_id).await? {
+        Some(recording) if includes_audio => recording,
+        _ => return Err(ShareError::NotFound),
+    };
+    let bytes = storage
+        .get_bytes_from_url(&recording.storage_url, share.owner_id)
+        .await?;
+    audit::record(
+        pool,
+        client,
+        None,
+        audit::AUDIO_ACCESSED,
+        Target::Session(share.session_id),
+        json!({ "via": "share" }),
+    )
+    .await;
+    Ok(SharedAudio {
+        bytes,
+        mime_type: recording.mime_type,
+    })
+}
+
+pub async fn view_shared(
+    pool: &PgPool,
+    storage: &StorageService,
+    client: &ClientInfo,
+    token: &str,
+) -> Result<SharedSession, ShareError> {
+    let Some(share) = SessionShare::record_view(pool, token).await? else {
+        return Err(ShareError::NotFound);
+    };
+    let scope = ShareScope::parse(&share.scope)
+        .ok_or_else(|| anyhow::anyhow!("unknown share scope {}", share.scope))?;
+    let detail =
+        session_detail_for_user(pool, storage.encryption(), share.session_id, share.owner_id)
+            .await?;
+    let transcript = redaction::redacted_transcript(
+        pool,
+        storage.encryption(),
+        share.session_id,
+        &detail.transcript,
+    )
+    .await?;
+
+    let mut audio_url = None;
+    if let (true, Some(url)) = (scope.includes_audio(), detail.audio_url.as_deref()) {
+        if storage.encrypts() {
+            // A presigned link would hand out ciphertext; shared_audio decrypts and audits.
+            audio_url = Some(format!("/api/shared/{}/audio", token));
+        } else {
+            let ttl =
+                (share.expires_at - Utc::now()).min(Duration::seconds(SHARED_AUDIO_URL_SECONDS));
+            let key = storage.key_from_url(url)?;
+            audio_url = Some(
+                storage
+                    .presigned_get_url(&key, ttl.to_std().unwrap_or_default())
+                    .await?,
+            );
+            audit::record(
+                pool,
+                client,
+                None,
+                audit::AUDIO_ACCESSED,
+                Target::Session(share.session_id),
+                json!({ "via": "share" }),
+            )
+            .await;
+        }
+    }
+
+    Ok(SharedSession {
+        topic_title: detail.topic_title,
+        start_time: detail.start_time,
+        end_time: detail.end_time,
+        duration_seconds: detail.duration_seconds,
+        language: detail.language,
+        mode: detail.mode,
+        transcript,
+        audio_url,
+        scope,
+        expires_at: share.expires_at,
+        view_count: share.view_count,
+    })
+}
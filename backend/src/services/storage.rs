@@ -1,15 +1,21 @@
 use anyhow::{bail, Context};
 use aws_config::{BehaviorVersion, Region};
 use aws_sdk_s3::config::{Builder as S3ConfigBuilder, Credentials};
+use aws_sdk_s3::presigning::PresigningConfig;
 use aws_sdk_s3::primitives::ByteStream;
 use aws_sdk_s3::Client;
 use std::env;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::encryption::{self, Encryption};
 
 #[derive(Clone)]
 pub struct StorageService {
     client: Client,
     bucket: String,
     endpoint: String,
+    encryption: Encryption,
 }
 
 impl StorageService {
@@ -43,16 +49,36 @@ impl StorageService {
             client,
             bucket,
             endpoint,
+            encryption: Encryption::disabled(),
         };
         Ok(service)
     }
 
+    pub fn with_encryption(mut self, encryption: Encryption) -> Self {
+        self.encryption = encryption;
+        self
+    }
+
+    pub fn encryption(&self) -> &Encryption {
+        &self.encryption
+    }
+
+    // With encryption configured, objects uploaded for an owner are sealed with that
+    // owner's data key and stored as opaque bytes.
     pub async fn upload_bytes(
         &self,
         key: &str,
         bytes: Vec<u8>,
         content_type: Option<&str>,
+        owner: Option<Uuid>,
     ) -> anyhow::Result<String> {
+        let (bytes, content_type) = match (self.encryption.store(), owner) {
+            (Some(store), Some(owner)) => (
+                store.encrypt(owner, &bytes).await?,
+                Some("application/octet-stream"),
+            ),
+            _ => (bytes, content_type),
+        };
         let mut req = self
             .client
             .put_object()
@@ -75,7 +101,7 @@ impl StorageService {
         Ok(url)
     }
 
-    fn key_from_url(&self, url: &str) -> anyhow::Result<String> {
+    pub fn key_from_url(&self, url: &str) -> anyhow::Result<String> {
         if let Some(pos) = url.find(&self.bucket) {
             let after_bucket = &url[pos + self.bucket.len()..];
             let key = after_bucket.trim_start_matches('/');
@@ -87,17 +113,68 @@ impl StorageService {
         bail!("url does not contain bucket name: {}", url)
     }
 
-    pub async fn get_bytes_from_url(&self, url: &str) -> anyhow::Result<Vec<u8>> {
+    pub async fn get_bytes_from_url(
+        &self,
+        url: &str,
+        expected_owner: Uuid,
+    ) -> anyhow::Result<Vec<u8>> {
         let key = self.key_from_url(url)?;
+        self.get_bytes(&key, expected_owner).await
+    }
+
+    // Decrypts sealed objects, so callers always get the plaintext. An object sealed for
+    // anyone but `expected_owner` is refused, so a URL naming another user's object cannot
+    // be used to read it.
+    pub async fn get_bytes(&self, key: &str, expected_owner: Uuid) -> anyhow::Result<Vec<u8>> {
         let obj = self
             .client
             .get_object()
             .bucket(&self.bucket)
-            .key(&key)
+            .key(key)
             .send()
             .await?;
 
-        let data = obj.body.collect().await?;
-        Ok(data.into_bytes().to_vec())
+        let data = obj.body.collect().await?.into_bytes().to_vec();
+        let Some(owner) = encryption::encrypted_owner(&data) else {
+            return Ok(data);
+        };
+        if owner != expected_owner {
+            bail!("{} is sealed for another user", key);
+        }
+        let store = self.encryption.store().ok_or_else(|| {
+            anyhow::anyhow!("{} is encrypted but no master key is configured", key)
+        })?;
+        store.decrypt(&data).await
+    }
+
+    // Encrypted objects are useless through presigned links and must be proxied instead.
+    pub fn encrypts(&self) -> bool {
+        self.encryption.enabled()
+    }
+
+    // Time-limited GET link for a private object.
+    pub async fn presigned_get_url(
+        &self,
+        key: &str,
+        expires_in: Duration,
+    ) -> anyhow::Result<String> {
+        let req = self
+            .client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .presigned(PresigningConfig::expires_in(expires_in)?)
+            .await?;
+        Ok(req.uri().to_string())
+    }
+
+    pub async fn delete_object(&self, key: &str) -> anyhow::Result<()> {
+        self.client
+            .delete_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .send()
+            .await?;
+        Ok(())
     }
 }
@@ -0,0 +1,456 @@
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::models::topic::{NewTopic, Topic, TopicUpdate};
+use crate::services::topics::validate_new_topic;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CatalogFormat {
+    Json,
+    Yaml,
+    Csv,
+}
+
+impl CatalogFormat {
+    pub fn from_path(path: &Path) -> Option<CatalogFormat> {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(|ext| ext.parse().ok())
+    }
+}
+
+impl FromStr for CatalogFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "json" => Ok(CatalogFormat::Json),
+            "yaml" | "yml" => Ok(CatalogFormat::Yaml),
+            "csv" => Ok(CatalogFormat::Csv),
+            other => anyhow::bail!(
+                "unknown catalog format {:?} (expected json, yaml or csv)",
+                other
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeedMode {
+    // Only create topics whose title is not in the database yet.
+    Insert,
+    // Also overwrite existing topics (and unarchive them) to match the file.
+    Upsert,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CatalogError {
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl fmt::Display for CatalogError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {}: {}", line, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+// Tags are a single `;`-separated column in CSV.
+#[derive(Debug, Serialize, Deserialize)]
+struct CsvTopic {
+    title: String,
+    #[serde(default)]
+    difficulty: Option<String>,
+    #[serde(default)]
+    prompt_hint: Option<String>,
+    #[serde(default)]
+    category: Option<String>,
+    #[serde(default)]
+    tags: Option<String>,
+    #[serde(default)]
+    target_skill: Option<String>,
+    #[serde(default)]
+    estimated_duration_seconds: Option<i32>,
+}
+
+impl From<CsvTopic> for NewTopic {
+    fn from(row: CsvTopic) -> Self {
+        NewTopic {
+            title: row.title,
+            difficulty: row.difficulty,
+            prompt_hint: row.prompt_hint,
+            category: row.category,
+            tags: row
+                .tags
+                .unwrap_or_default()
+                .split(';')
+                .map(str::trim)
+                .filter(|t| !t.is_empty())
+                .map(str::to_string)
+                .collect(),
+            target_skill: row.target_skill,
+            estimated_duration_seconds: row.estimated_duration_seconds,
+        }
+    }
+}
+
+impl From<&NewTopic> for CsvTopic {
+    fn from(topic: &NewTopic) -> Self {
+        CsvTopic {
+            title: topic.title.clone(),
+            difficulty: topic.difficulty.clone(),
+            prompt_hint: topic.prompt_hint.clone(),
+            category: topic.category.clone(),
+            tags: Some(topic.tags.join(";")),
+            target_skill: topic.target_skill.clone(),
+            estimated_duration_seconds: topic.estimated_duration_seconds,
+        }
+    }
+}
+
+// Line on which each top-level array entry starts, so validation errors can point into the file.
+fn entry_lines(data: &str, format: CatalogFormat) -> Vec<usize> {
+    match format {
+        CatalogFormat::Json => {
+            let mut lines = Vec::new();
+            let (mut line, mut depth) = (1, 0usize);
+            let (mut in_string, mut escaped) = (false, false);
+            for ch in data.chars() {
+                if ch == '\n' {
+                    line += 1;
+                }
+                if in_string {
+                    match ch {
+                        _ if escaped => escaped = false,
+                        '\\' => escaped = true,
+                        '"' => in_string = false,
+                        _ => {}
+                    }
+                    continue;
+                }
+                match ch {
+                    '"' => in_string = true,
+                    '{' | '[' => {
+                        if depth == 1 && ch == '{' {
+                            lines.push(line);
+                        }
+                        depth += 1;
+                    }
+                    '}' | ']' => depth = depth.saturating_sub(1),
+                    _ => {}
+                }
+            }
+            lines
+        }
+        CatalogFormat::Yaml => data
+            .lines()
+            .enumerate()
+            .filter(|(_, l)| *l == "-" || l.starts_with("- "))
+            .map(|(idx, _)| idx + 1)
+            .collect(),
+        // Record positions come from the CSV reader instead.
+        CatalogFormat::Csv => Vec::new(),
+    }
+}
+
+pub fn parse_catalog(
+    data: &str,
+    format: CatalogFormat,
+) -> Result<Vec<NewTopic>, Vec<CatalogError>> {
+    let (raw, lines): (Vec<NewTopic>, Vec<usize>) = match format {
+        CatalogFormat::Json => {
+            let topics = serde_json::from_str(data).map_err(|err| {
+                vec![CatalogError {
+                    line: Some(err.line()),
+                    message: err.to_string(),
+                }]
+            })?;
+            (topics, entry_lines(data, format))
+        }
+        CatalogFormat::Yaml => {
+            let topics = serde_yaml::from_str(data).map_err(|err| {
+                vec![CatalogError {
+                    line: err.location().map(|loc| loc.line()),
+                    message: err.to_string(),
+                }]
+            })?;
+            (topics, entry_lines(data, format))
+        }
+        CatalogFormat::Csv => {
+            let mut reader = csv::ReaderBuilder::new()
+                .trim(csv::Trim::All)
+                .from_reader(data.as_bytes());
+            let headers = reader
+                .headers()
+                .map_err(|err| {
+                    vec![CatalogError {
+                        line: Some(1),
+                        message: err.to_string(),
+                    }]
+                })?
+                .clone();
+            let mut topics = Vec::new();
+            let mut lines = Vec::new();
+            let mut errors = Vec::new();
+            for record in reader.records() {
+                let parsed = record.and_then(|record| {
+                    let line = record.position().map(|p| p.line() as usize);
+                    record
+                        .deserialize::<CsvTopic>(Some(&headers))
+                        .map(|row| (line, row))
+                });
+                match parsed {
+                    Ok((line, row)) => {
+                        lines.push(line.unwrap_or_default());
+                        topics.push(NewTopic::from(row));
+                    }
+                    Err(err) => errors.push(CatalogError {
+                        line: err.position().map(|p| p.line() as usize),
+                        message: err.to_string(),
+                    }),
+                }
+            }
+            if !errors.is_empty() {
+                return Err(errors);
+            }
+            (topics, lines)
+        }
+    };
+
+    let mut topics = Vec::with_capacity(raw.len());
+    let mut errors = Vec::new();
+    for (idx, topic) in raw.iter().enumerate() {
+        let line = lines.get(idx).copied();
+        match validate_new_topic(topic) {
+            Ok(topic) if topics.iter().any(|t: &NewTopic| t.title == topic.title) => {
+                errors.push(CatalogError {
+                    line,
+                    message: format!("duplicate title {:?}", topic.title),
+                })
+            }
+            Ok(topic) => topics.push(topic),
+            Err(err) => errors.push(CatalogError {
+                line,
+                message: format!("entry {}: {}", idx + 1, err),
+            }),
+        }
+    }
+    if errors.is_empty() {
+        Ok(topics)
+    } else {
+        Err(errors)
+    }
+}
+
+pub fn topic_record(topic: &Topic) -> NewTopic {
+    NewTopic {
+        title: topic.title.clone(),
+        difficulty: topic.difficulty.clone(),
+        prompt_hint: topic.prompt_hint.clone(),
+        category: topic.category.clone(),
+        tags: topic.tags.clone(),
+        target_skill: topic.target_skill.clone(),
+        estimated_duration_seconds: topic.estimated_duration_seconds,
+    }
+}
+
+// Writes the active catalog in a form `parse_catalog` reads back unchanged.
+pub fn render_catalog(topics: &[Topic], format: CatalogFormat) -> anyhow::Result<String> {
+    let mut records: Vec<NewTopic> = topics
+        .iter()
+        .filter(|t| t.archived_at.is_none())
+        .map(topic_record)
+        .collect();
+    records.sort_by(|a, b| a.title.cmp(&b.title));
+
+    Ok(match format {
+        CatalogFormat::Json => serde_json::to_string_pretty(&records)? + "\n",
+        CatalogFormat::Yaml => serde_yaml::to_string(&records)?,
+        CatalogFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(Vec::new());
+            for record in &records {
+                writer.serialize(CsvTopic::from(record))?;
+            }
+            String::from_utf8(writer.into_inner()?)?
+        }
+    })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldChange {
+    pub field: &'static str,
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CatalogChange {
+    Create(NewTopic),
+    Update {
+        id: Uuid,
+        topic: NewTopic,
+        fields: Vec<FieldChange>,
+    },
+    Archive {
+        id: Uuid,
+        title: String,
+    },
+}
+
+impl fmt::Display for CatalogChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CatalogChange::Create(topic) => write!(f, "+ create {:?}", topic.title),
+            CatalogChange::Update { topic, fields, .. } => {
+                write!(f, "~ update {:?}", topic.title)?;
+                for change in fields {
+                    write!(
+                        f,
+                        "\n    {}: {} -> {}",
+                        change.field, change.from, change.to
+                    )?;
+                }
+                Ok(())
+            }
+            CatalogChange::Archive { title, .. } => write!(f, "- archive {:?}", title),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct CatalogPlan {
+    pub changes: Vec<CatalogChange>,
+    pub unchanged: usize,
+    // Existing topics that differ from the file but were left alone in insert mode.
+    pub skipped: usize,
+}
+
+fn show_opt<T: fmt::Display>(value: &Option<T>) -> String {
+    match value {
+        Some(v) => v.to_string(),
+        None => "(none)".into(),
+    }
+}
+
+fn diff_fields(existing: &Topic, incoming: &NewTopic) -> Vec<FieldChange> {
+    let current = topic_record(existing);
+    let mut fields = Vec::new();
+    let mut push = |field: &'static str, from: String, to: String| {
+        if from != to {
+            fields.push(FieldChange { field, from, to });
+        }
+    };
+    push(
+        "difficulty",
+        show_opt(&current.difficulty),
+        show_opt(&incoming.difficulty),
+    );
+    push(
+        "prompt_hint",
+        show_opt(&current.prompt_hint),
+        show_opt(&incoming.prompt_hint),
+    );
+    push(
+        "category",
+        show_opt(&current.category),
+        show_opt(&incoming.category),
+    );
+    push("tags", current.tags.join(", "), incoming.tags.join(", "));
+    push(
+        "target_skill",
+        show_opt(&current.target_skill),
+        show_opt(&incoming.target_skill),
+    );
+    push(
+        "estimated_duration_seconds",
+        show_opt(&current.estimated_duration_seconds),
+        show_opt(&incoming.estimated_duration_seconds),
+    );
+    if existing.archived_at.is_some() {
+        push("archived", "true".into(), "false".into());
+    }
+    fields
+}
+
+// `incoming` must already be validated by `parse_catalog`. Pruning archives rather than deletes
+// because past sessions still reference the topic.
+pub fn plan_catalog(
+    existing: &[Topic],
+    incoming: &[NewTopic],
+    mode: SeedMode,
+    prune: bool,
+) -> CatalogPlan {
+    let mut plan = CatalogPlan::default();
+    for topic in incoming {
+        match existing.iter().find(|t| t.title == topic.title) {
+            None => plan.changes.push(CatalogChange::Create(topic.clone())),
+            Some(current) => {
+                let fields = diff_fields(current, topic);
+                if fields.is_empty() {
+                    plan.unchanged += 1;
+                } else if mode == SeedMode::Insert {
+                    plan.skipped += 1;
+                } else {
+                    plan.changes.push(CatalogChange::Update {
+                        id: current.id,
+                        topic: topic.clone(),
+                        fields,
+                    });
+                }
+            }
+        }
+    }
+    if prune {
+        for current in existing {
+            if current.archived_at.is_none() && !incoming.iter().any(|t| t.title == current.title) {
+                plan.changes.push(CatalogChange::Archive {
+                    id: current.id,
+                    title: current.title.clone(),
+                });
+            }
+        }
+    }
+    plan
+}
+
+// All-or-nothing, so a failure partway through never leaves a half-applied seed.
+pub async fn apply_catalog(pool: &PgPool, plan: &CatalogPlan) -> anyhow::Result<()> {
+    let mut tx = pool.begin().await?;
+    for change in &plan.changes {
+        match change {
+            CatalogChange::Create(topic) => {
+                Topic::create(&mut *tx, topic).await?;
+            }
+            CatalogChange::Update { id, topic, .. } => {
+                let update = TopicUpdate {
+                    title: None,
+                    difficulty: Some(topic.difficulty.clone()),
+                    prompt_hint: Some(topic.prompt_hint.clone()),
+                    category: Some(topic.category.clone()),
+                    tags: Some(topic.tags.clone()),
+                    target_skill: Some(topic.target_skill.clone()),
+                    estimated_duration_seconds: Some(topic.estimated_duration_seconds),
+                    archived: Some(false),
+                };
+                Topic::update(&mut *tx, *id, None, None, &update).await?;
+            }
+            CatalogChange::Archive { id, .. } => {
+                let update = TopicUpdate {
+                    archived: Some(true),
+                    ..TopicUpdate::default()
+                };
+                Topic::update(&mut *tx, *id, None, None, &update).await?;
+            }
+        }
+    }
+    tx.commit().await?;
+    Ok(())
+}
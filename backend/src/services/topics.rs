@@ -0,0 +1,322 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::models::topic::{NewTopic, Topic, TopicUpdate};
+use crate::models::topic_translation::{NewTopicTranslation, TopicTranslation};
+use crate::services::db::is_unique_violation;
+use crate::services::locale::{normalize_locale, DEFAULT_LOCALE};
+
+pub const DIFFICULTIES: &[&str] = &["easy", "medium", "hard"];
+pub const MAX_TITLE_LEN: usize = 200;
+pub const MAX_PROMPT_HINT_LEN: usize = 1000;
+pub const MAX_LABEL_LEN: usize = 50;
+pub const MAX_TAGS: usize = 10;
+pub const MIN_ESTIMATED_DURATION_SECONDS: i32 = 30;
+pub const MAX_ESTIMATED_DURATION_SECONDS: i32 = 3600;
+pub const MAX_PRIVATE_TOPICS_PER_USER: i64 = 50;
+
+#[derive(Debug, thiserror::Error)]
+pub enum TopicError {
+    #[error("topic not found")]
+    NotFound,
+    #[error("a topic with this title already exists")]
+    DuplicateTitle,
+    #[error("{0}")]
+    Invalid(String),
+    #[error("at most {0} private topics are allowed")]
+    LimitReached(i64),
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+fn map_write_error(err: anyhow::Error) -> TopicError {
+    if is_unique_violation(&err) {
+        TopicError::DuplicateTitle
+    } else {
+        TopicError::Other(err)
+    }
+}
+
+pub fn normalize_title(title: &str) -> Result<String, TopicError> {
+    let title = title.trim();
+    if title.is_empty() {
+        return Err(TopicError::Invalid("title must not be empty".into()));
+    }
+    if title.chars().count() > MAX_TITLE_LEN {
+        return Err(TopicError::Invalid(format!(
+            "title must be at most {} characters",
+            MAX_TITLE_LEN
+        )));
+    }
+    Ok(title.to_string())
+}
+
+pub fn normalize_difficulty(difficulty: Option<&str>) -> Result<Option<String>, TopicError> {
+    match difficulty.map(str::trim) {
+        None | Some("") => Ok(None),
+        Some(value) if DIFFICULTIES.contains(&value) => Ok(Some(value.to_string())),
+        Some(value) => Err(TopicError::Invalid(format!(
+            "difficulty must be one of {}, got {:?}",
+            DIFFICULTIES.join(", "),
+            value
+        ))),
+    }
+}
+
+pub fn normalize_prompt_hint(prompt_hint: Option<&str>) -> Result<Option<String>, TopicError> {
+    match prompt_hint.map(str::trim) {
+        None | Some("") => Ok(None),
+        Some(value) if value.chars().count() > MAX_PROMPT_HINT_LEN => {
+            Err(TopicError::Invalid(format!(
+                "prompt_hint must be at most {} characters",
+                MAX_PROMPT_HINT_LEN
+            )))
+        }
+        Some(value) => Ok(Some(value.to_string())),
+    }
+}
+
+// Categories, skills and tags are matched exactly when filtering, so they are stored as
+// lowercase labels with single spaces.
+pub fn normalize_label(field: &str, value: &str) -> Result<String, TopicError> {
+    let label = value
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase();
+    if label.is_empty() {
+        return Err(TopicError::Invalid(format!("{} must not be empty", field)));
+    }
+    if label.chars().count() > MAX_LABEL_LEN {
+        return Err(TopicError::Invalid(format!(
+            "{} must be at most {} characters",
+            field, MAX_LABEL_LEN
+        )));
+    }
+    Ok(label)
+}
+
+fn normalize_optional_label(
+    field: &str,
+    value: Option<&str>,
+) -> Result<Option<String>, TopicError> {
+    match value {
+        None => Ok(None),
+        Some(v) if v.trim().is_empty() => Ok(None),
+        Some(v) => normalize_label(field, v).map(Some),
+    }
+}
+
+pub fn normalize_tags(tags: &[String]) -> Result<Vec<String>, TopicError> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = normalize_label("tag", tag)?;
+        if !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+    if normalized.len() > MAX_TAGS {
+        return Err(TopicError::Invalid(format!(
+            "at most {} tags are allowed",
+            MAX_TAGS
+        )));
+    }
+    Ok(normalized)
+}
+
+pub fn validate_estimated_duration(seconds: Option<i32>) -> Result<Option<i32>, TopicError> {
+    match seconds {
+        Some(s)
+            if !(MIN_ESTIMATED_DURATION_SECONDS..=MAX_ESTIMATED_DURATION_SECONDS).contains(&s) =>
+        {
+            Err(TopicError::Invalid(format!(
+                "estimated_duration_seconds must be between {} and {}",
+                MIN_ESTIMATED_DURATION_SECONDS, MAX_ESTIMATED_DURATION_SECONDS
+            )))
+        }
+        other => Ok(other),
+    }
+}
+
+pub fn validate_new_topic(topic: &NewTopic) -> Result<NewTopic, TopicError> {
+    Ok(NewTopic {
+        title: normalize_title(&topic.title)?,
+        difficulty: normalize_difficulty(topic.difficulty.as_deref())?,
+        prompt_hint: normalize_prompt_hint(topic.prompt_hint.as_deref())?,
+        category: normalize_optional_label("category", topic.category.as_deref())?,
+        tags: normalize_tags(&topic.tags)?,
+        target_skill: normalize_optional_label("target_skill", topic.target_skill.as_deref())?,
+        estimated_duration_seconds: validate_estimated_duration(topic.estimated_duration_seconds)?,
+    })
+}
+
+pub fn validate_update(update: &TopicUpdate) -> Result<TopicUpdate, TopicError> {
+    Ok(TopicUpdate {
+        title: update.title.as_deref().map(normalize_title).transpose()?,
+        difficulty: update
+            .difficulty
+            .as_ref()
+            .map(|d| normalize_difficulty(d.as_deref()))
+            .transpose()?,
+        prompt_hint: update
+            .prompt_hint
+            .as_ref()
+            .map(|h| normalize_prompt_hint(h.as_deref()))
+            .transpose()?,
+        category: update
+            .category
+            .as_ref()
+            .map(|c| normalize_optional_label("category", c.as_deref()))
+            .transpose()?,
+        tags: update.tags.as_deref().map(normalize_tags).transpose()?,
+        target_skill: update
+            .target_skill
+            .as_ref()
+            .map(|s| normalize_optional_label("target_skill", s.as_deref()))
+            .transpose()?,
+        estimated_duration_seconds: update
+            .estimated_duration_seconds
+            .map(validate_estimated_duration)
+            .transpose()?,
+        archived: update.archived,
+    })
+}
+
+pub async fn create_topic(pool: &PgPool, topic: &NewTopic) -> Result<Topic, TopicError> {
+    let topic = validate_new_topic(topic)?;
+    Topic::create(pool, &topic).await.map_err(map_write_error)
+}
+
+pub async fn update_topic(
+    pool: &PgPool,
+    topic_id: Uuid,
+    update: &TopicUpdate,
+) -> Result<Topic, TopicError> {
+    let update = validate_update(update)?;
+    Topic::update(pool, topic_id, None, None, &update)
+        .await
+        .map_err(map_write_error)?
+        .ok_or(TopicError::NotFound)
+}
+
+pub async fn archive_topic(pool: &PgPool, topic_id: Uuid) -> Result<Topic, TopicError> {
+    let update = TopicUpdate {
+        archived: Some(true),
+        ..TopicUpdate::default()
+    };
+    Topic::update(pool, topic_id, None, None, &update)
+        .await?
+        .ok_or(TopicError::NotFound)
+}
+
+// The limit counts active private topics, so archiving one frees a slot. The owner lock keeps
+// concurrent creates from both passing the count.
+pub async fn create_private_topic(
+    pool: &PgPool,
+    owner_id: Uuid,
+    topic: &NewTopic,
+) -> Result<Topic, TopicError> {
+    let topic = validate_new_topic(topic)?;
+    let mut tx = pool.begin().await.map_err(anyhow::Error::from)?;
+    Topic::lock_owner(&mut *tx, owner_id).await?;
+    if Topic::count_owned(&mut *tx, owner_id).await? >= MAX_PRIVATE_TOPICS_PER_USER {
+        return Err(TopicError::LimitReached(MAX_PRIVATE_TOPICS_PER_USER));
+    }
+    let created = Topic::create_for_owner(&mut *tx, Some(owner_id), &topic)
+        .await
+        .map_err(map_write_error)?;
+    tx.commit().await.map_err(anyhow::Error::from)?;
+    Ok(created)
+}
+
+// Unlike private topics there is no cap; titles only need to be unique within the organization.
+pub async fn create_org_topic(
+    pool: &PgPool,
+    org_id: Uuid,
+    topic: &NewTopic,
+) -> Result<Topic, TopicError> {
+    let topic = validate_new_topic(topic)?;
+    Topic::create_for_org(pool, org_id, &topic)
+        .await
+        .map_err(map_write_error)
+}
+
+pub async fn update_private_topic(
+    pool: &PgPool,
+    owner_id: Uuid,
+    topic_id: Uuid,
+    update: &TopicUpdate,
+) -> Result<Topic, TopicError> {
+    let update = validate_update(update)?;
+    Topic::update(pool, topic_id, Some(owner_id), None, &update)
+        .await
+        .map_err(map_write_error)?
+        .ok_or(TopicError::NotFound)
+}
+
+// Topics with past sessions are archived instead so session history keeps its topic.
+pub async fn delete_private_topic(
+    pool: &PgPool,
+    owner_id: Uuid,
+    topic_id: Uuid,
+) -> Result<(), TopicError> {
+    if Topic::delete_owned(pool, topic_id, owner_id).await? {
+        return Ok(());
+    }
+    let update = TopicUpdate {
+        archived: Some(true),
+        ..TopicUpdate::default()
+    };
+    Topic::update(pool, topic_id, Some(owner_id), None, &update)
+        .await?
+        .map(|_| ())
+        .ok_or(TopicError::NotFound)
+}
+
+// The base topic columns hold the default-locale text, so only other supported locales can
+// carry translations.
+pub fn validate_translation_locale(locale: &str) -> Result<&'static str, TopicError> {
+    match normalize_locale(locale) {
+        Some(DEFAULT_LOCALE) => Err(TopicError::Invalid(format!(
+            "{} text is edited on the topic itself",
+            DEFAULT_LOCALE
+        ))),
+        Some(locale) => Ok(locale),
+        None => Err(TopicError::Invalid(format!(
+            "unsupported locale {:?}",
+            locale
+        ))),
+    }
+}
+
+pub async fn upsert_translation(
+    pool: &PgPool,
+    topic_id: Uuid,
+    locale: &str,
+    payload: &NewTopicTranslation,
+) -> Result<TopicTranslation, TopicError> {
+    let locale = validate_translation_locale(locale)?;
+    let payload = NewTopicTranslation {
+        title: normalize_title(&payload.title)?,
+        prompt_hint: normalize_prompt_hint(payload.prompt_hint.as_deref())?,
+    };
+    // Private and organization topics are not translated.
+    match Topic::get(pool, topic_id).await? {
+        Some(topic) if topic.owner_id.is_none() && topic.org_id.is_none() => {}
+        _ => return Err(TopicError::NotFound),
+    }
+    Ok(TopicTranslation::upsert(pool, topic_id, locale, &payload).await?)
+}
+
+pub async fn delete_translation(
+    pool: &PgPool,
+    topic_id: Uuid,
+    locale: &str,
+) -> Result<(), TopicError> {
+    let locale = validate_translation_locale(locale)?;
+    if TopicTranslation::delete(pool, topic_id, locale).await? {
+        Ok(())
+    } else {
+        Err(TopicError::NotFound)
+    }
+}
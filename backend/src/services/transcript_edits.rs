@@ -0,0 +1,382 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
+use sqlx::{FromRow, PgExecutor, PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::encryption::Encryption;
+use crate::models::transcript::{TranscriptRevision, TranscriptSegment};
+use crate::services::redaction;
+
+#[derive(Debug, thiserror::Error)]
+pub enum TranscriptEditError {
+    #[error("transcript not found")]
+    NotFound,
+    #[error("revision {0} not found")]
+    RevisionNotFound(i32),
+    #[error("revision conflict: expected {expected}, current {current}")]
+    Conflict { expected: i32, current: i32 },
+    #[error("invalid edit: {0}")]
+    Invalid(String),
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SegmentEdit {
+    pub index: usize,
+    pub text: Option<String>,
+    pub speaker: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TranscriptState {
+    pub revision: i32,
+    pub segments: Vec<TranscriptSegment>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RevisionSummary {
+    pub revision: i32,
+    pub author_id: Option<Uuid>,
+    pub restored_from: Option<i32>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RevisionHistory {
+    pub current_revision: i32,
+    pub revisions: Vec<RevisionSummary>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct SegmentChange {
+    pub index: usize,
+    pub before: Option<TranscriptSegment>,
+    pub after: Option<TranscriptSegment>,
+}
+
+#[derive(FromRow)]
+struct StoredTranscriptRow {
+    id: Uuid,
+    segments: serde_json::Value,
+    original_segments: Option<serde_json::Value>,
+    revision: i32,
+    created_at: DateTime<Utc>,
+}
+
+struct OwnedTranscriptRow {
+    id: Uuid,
+    segments: Json<Vec<TranscriptSegment>>,
+    original_segments: Option<Json<Vec<TranscriptSegment>>>,
+    revision: i32,
+    created_at: DateTime<Utc>,
+}
+
+async fn open_segments(
+    encryption: &Encryption,
+    value: serde_json::Value,
+) -> anyhow::Result<Json<Vec<TranscriptSegment>>> {
+    Ok(Json(serde_json::from_value(
+        encryption.open_json(value).await?,
+    )?))
+}
+
+impl StoredTranscriptRow {
+    async fn open(
+        self,
+        encryption: &Encryption,
+    ) -> Result<OwnedTranscriptRow, TranscriptEditError> {
+        let original_segments = match self.original_segments {
+            Some(value) => Some(open_segments(encryption, value).await?),
+            None => None,
+        };
+        Ok(OwnedTranscriptRow {
+            id: self.id,
+            segments: open_segments(encryption, self.segments).await?,
+            original_segments,
+            revision: self.revision,
+            created_at: self.created_at,
+        })
+    }
+}
+
+pub fn apply_edits(
+    segments: &[TranscriptSegment],
+    edits: &[SegmentEdit],
+) -> Result<Vec<TranscriptSegment>, TranscriptEditError> {
+    if edits.is_empty() {
+        return Err(TranscriptEditError::Invalid("no edits supplied".into()));
+    }
+    let mut updated = segments.to_vec();
+    for edit in edits {
+        let segment = updated.get_mut(edit.index).ok_or_else(|| {
+            TranscriptEditError::Invalid(format!("segment index {} out of range", edit.index))
+        })?;
+        if let Some(text) = edit.text.as_ref() {
+            if text.trim().is_empty() {
+                return Err(TranscriptEditError::Invalid(format!(
+                    "segment {} text must not be empty",
+                    edit.index
+                )));
+            }
+            if &segment.text != text {
+                // Word timings no longer line up with corrected text.
+                segment.words = None;
+            }
+            segment.text = text.clone();
+        }
+        if let Some(speaker) = edit.speaker.as_ref() {
+            if speaker.trim().is_empty() {
+                return Err(TranscriptEditError::Invalid(format!(
+                    "segment {} speaker must not be empty",
+                    edit.index
+                )));
+            }
+            segment.speaker = speaker.trim().to_string();
+        }
+    }
+    Ok(updated)
+}
+
+pub fn diff_segments(
+    before: &[TranscriptSegment],
+    after: &[TranscriptSegment],
+) -> Vec<SegmentChange> {
+    (0..before.len().max(after.len()))
+        .filter_map(|index| {
+            let old = before.get(index);
+            let new = after.get(index);
+            (old != new).then(|| SegmentChange {
+                index,
+                before: old.cloned(),
+                after: new.cloned(),
+            })
+        })
+        .collect()
+}
+
+async fn load_owned(
+    tx: &mut Transaction<'_, Postgres>,
+    encryption: &Encryption,
+    session_id: Uuid,
+    user_id: Uuid,
+) -> Result<OwnedTranscriptRow, TranscriptEditError> {
+    sqlx::query_as::<_, StoredTranscriptRow>(
+        r#"
+        SELECT tr.id, tr.segments, tr.original_segments, tr.revision, tr.created_at
+        FROM transcripts tr
+        JOIN sessions s ON s.id = tr.session_id
+        WHERE tr.session_id = $1 AND s.user_id = $2 AND s.deleted_at IS NULL
+        FOR UPDATE OF tr
+        "#,
+    )
+    .bind(session_id)
+    .bind(user_id)
+    .fetch_optional(&mut **tx)
+    .await?
+    .ok_or(TranscriptEditError::NotFound)?
+    .open(encryption)
+    .await
+}
+
+async fn load_owned_readonly(
+    pool: &PgPool,
+    encryption: &Encryption,
+    session_id: Uuid,
+    user_id: Uuid,
+) -> Result<OwnedTranscriptRow, TranscriptEditError> {
+    sqlx::query_as::<_, StoredTranscriptRow>(
+        r#"
+        SELECT tr.id, tr.segments, tr.original_segments, tr.revision, tr.created_at
+        FROM transcripts tr
+        JOIN sessions s ON s.id = tr.session_id
+        WHERE tr.session_id = $1 AND s.user_id = $2 AND s.deleted_at IS NULL
+        "#,
+    )
+    .bind(session_id)
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or(TranscriptEditError::NotFound)?
+    .open(encryption)
+    .await
+}
+
+async fn write_revision(
+    tx: &mut Transaction<'_, Postgres>,
+    encryption: &Encryption,
+    current: &OwnedTranscriptRow,
+    segments: &[TranscriptSegment],
+    author_id: Uuid,
+    restored_from: Option<i32>,
+) -> Result<i32, TranscriptEditError> {
+    let next_revision = current.revision + 1;
+    // Only owners edit, so the author's key is the owner's key.
+    let segments_json = encryption
+        .seal_json(
+            author_id,
+            serde_json::to_value(segments).map_err(anyhow::Error::from)?,
+        )
+        .await?;
+    let redacted_json = encryption
+        .seal_json(
+            author_id,
+            serde_json::to_value(redaction::redactor().redact_segments(segments))
+                .map_err(anyhow::Error::from)?,
+        )
+        .await?;
+
+    sqlx::query(
+        r#"
+        UPDATE transcripts
+        SET segments = $2,
+            revision = $3,
+            redacted_segments = $4,
+            redacted_at = now()
+        WHERE id = $1
+        "#,
+    )
+    .bind(current.id)
+    .bind(&segments_json)
+    .bind(next_revision)
+    .bind(&redacted_json)
+    .execute(&mut **tx)
+    .await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO transcript_revisions (transcript_id, revision, segments, author_id, restored_from)
+        VALUES ($1, $2, $3, $4, $5)
+        "#,
+    )
+    .bind(current.id)
+    .bind(next_revision)
+    .bind(&segments_json)
+    .bind(author_id)
+    .bind(restored_from)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(next_revision)
+}
+
+fn check_revision(current: i32, expected: i32) -> Result<(), TranscriptEditError> {
+    if current != expected {
+        return Err(TranscriptEditError::Conflict { expected, current });
+    }
+    Ok(())
+}
+
+async fn segments_at(
+    executor: impl PgExecutor<'_>,
+    encryption: &Encryption,
+    row: &OwnedTranscriptRow,
+    revision: i32,
+) -> Result<Vec<TranscriptSegment>, TranscriptEditError> {
+    if revision == row.revision {
+        return Ok(row.segments.0.clone());
+    }
+    if revision == 0 {
+        return Ok(row
+            .original_segments
+            .as_ref()
+            .map(|Json(segments)| segments.clone())
+            .unwrap_or_else(|| row.segments.0.clone()));
+    }
+    let stored = TranscriptRevision::get(executor, encryption, row.id, revision)
+        .await?
+        .ok_or(TranscriptEditError::RevisionNotFound(revision))?;
+    serde_json::from_value::<Vec<TranscriptSegment>>(stored.segments)
+        .map_err(|err| TranscriptEditError::Other(err.into()))
+}
+
+pub async fn edit_transcript(
+    pool: &PgPool,
+    encryption: &Encryption,
+    session_id: Uuid,
+    user_id: Uuid,
+    expected_revision: i32,
+    edits: &[SegmentEdit],
+) -> Result<TranscriptState, TranscriptEditError> {
+    let mut tx = pool.begin().await?;
+    let current = load_owned(&mut tx, encryption, session_id, user_id).await?;
+    check_revision(current.revision, expected_revision)?;
+
+    let segments = apply_edits(&current.segments.0, edits)?;
+    let revision = write_revision(&mut tx, encryption, &current, &segments, user_id, None).await?;
+    tx.commit().await?;
+
+    Ok(TranscriptState { revision, segments })
+}
+
+pub async fn restore_revision(
+    pool: &PgPool,
+    encryption: &Encryption,
+    session_id: Uuid,
+    user_id: Uuid,
+    expected_revision: i32,
+    target_revision: i32,
+) -> Result<TranscriptState, TranscriptEditError> {
+    let mut tx = pool.begin().await?;
+    let current = load_owned(&mut tx, encryption, session_id, user_id).await?;
+    check_revision(current.revision, expected_revision)?;
+
+    let segments = segments_at(&mut *tx, encryption, &current, target_revision).await?;
+    let revision = write_revision(
+        &mut tx,
+        encryption,
+        &current,
+        &segments,
+        user_id,
+        Some(target_revision),
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(TranscriptState { revision, segments })
+}
+
+pub async fn revision_history(
+    pool: &PgPool,
+    encryption: &Encryption,
+    session_id: Uuid,
+    user_id: Uuid,
+) -> Result<RevisionHistory, TranscriptEditError> {
+    let current = load_owned_readonly(pool, encryption, session_id, user_id).await?;
+    let stored = TranscriptRevision::list_for_transcript(pool, encryption, current.id).await?;
+
+    // Revision 0 is the immutable machine transcript and has no author.
+    let mut revisions = vec![RevisionSummary {
+        revision: 0,
+        author_id: None,
+        restored_from: None,
+        created_at: current.created_at,
+    }];
+    revisions.extend(stored.into_iter().map(|rev| RevisionSummary {
+        revision: rev.revision,
+        author_id: Some(rev.author_id),
+        restored_from: rev.restored_from,
+        created_at: rev.created_at,
+    }));
+
+    Ok(RevisionHistory {
+        current_revision: current.revision,
+        revisions,
+    })
+}
+
+pub async fn transcript_at_revision(
+    pool: &PgPool,
+    encryption: &Encryption,
+    session_id: Uuid,
+    user_id: Uuid,
+    revision: Option<i32>,
+) -> Result<TranscriptState, TranscriptEditError> {
+    let current = load_owned_readonly(pool, encryption, session_id, user_id).await?;
+    let revision = revision.unwrap_or(current.revision);
+    let segments = segments_at(pool, encryption, &current, revision).await?;
+    Ok(TranscriptState { revision, segments })
+}
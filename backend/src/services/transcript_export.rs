@@ -0,0 +1,326 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::models::transcript::TranscriptSegment;
+
+pub const CAPTION_LINE_WIDTH: usize = 42;
+pub const CAPTION_MAX_LINES: usize = 2;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Srt,
+    Vtt,
+    Md,
+    Txt,
+    #[default]
+    Json,
+}
+
+impl ExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Srt => "srt",
+            ExportFormat::Vtt => "vtt",
+            ExportFormat::Md => "md",
+            ExportFormat::Txt => "txt",
+            ExportFormat::Json => "json",
+        }
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            ExportFormat::Srt => "application/x-subrip; charset=utf-8",
+            ExportFormat::Vtt => "text/vtt; charset=utf-8",
+            ExportFormat::Md => "text/markdown; charset=utf-8",
+            ExportFormat::Txt => "text/plain; charset=utf-8",
+            ExportFormat::Json => "application/json",
+        }
+    }
+}
+
+pub struct TranscriptDocument<'a> {
+    pub topic_title: &'a str,
+    pub start_time: DateTime<Utc>,
+    pub segments: &'a [TranscriptSegment],
+}
+
+#[derive(Serialize)]
+struct JsonExport<'a> {
+    topic_title: &'a str,
+    start_time: DateTime<Utc>,
+    segments: &'a [TranscriptSegment],
+}
+
+pub fn render(format: ExportFormat, doc: &TranscriptDocument<'_>) -> anyhow::Result<String> {
+    let rendered = match format {
+        ExportFormat::Srt => render_srt(doc.segments),
+        ExportFormat::Vtt => render_vtt(doc.segments),
+        ExportFormat::Md => render_markdown(doc),
+        ExportFormat::Txt => render_text(doc.segments),
+        ExportFormat::Json => serde_json::to_string_pretty(&JsonExport {
+            topic_title: doc.topic_title,
+            start_time: doc.start_time,
+            segments: doc.segments,
+        })?,
+    };
+    Ok(rendered)
+}
+
+pub fn export_filename(
+    topic_title: &str,
+    start_time: DateTime<Utc>,
+    format: ExportFormat,
+) -> String {
+    dated_filename(
+        &slugify(topic_title, |ch| ch.is_ascii_alphanumeric()),
+        start_time,
+        format,
+    )
+}
+
+// ASCII `filename` for older clients plus an RFC 5987 `filename*` that keeps non-Latin titles.
+pub fn content_disposition(
+    topic_title: &str,
+    start_time: DateTime<Utc>,
+    format: ExportFormat,
+) -> String {
+    let unicode = dated_filename(
+        &slugify(topic_title, char::is_alphanumeric),
+        start_time,
+        format,
+    );
+    format!(
+        "attachment; filename=\"{}\"; filename*=UTF-8''{}",
+        export_filename(topic_title, start_time, format),
+        percent_encode(&unicode)
+    )
+}
+
+fn slugify(title: &str, keep: impl Fn(char) -> bool) -> String {
+    let mut slug = String::new();
+    for ch in title.chars() {
+        if keep(ch) {
+            slug.extend(ch.to_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_end_matches('-').to_string()
+}
+
+fn dated_filename(slug: &str, start_time: DateTime<Utc>, format: ExportFormat) -> String {
+    let slug = if slug.is_empty() { "session" } else { slug };
+    format!(
+        "{}-{}.{}",
+        slug,
+        start_time.format("%Y-%m-%d"),
+        format.extension()
+    )
+}
+
+// Everything outside RFC 5987 attr-char is percent-encoded as UTF-8 bytes.
+fn percent_encode(value: &str) -> String {
+    let mut out = String::new();
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || b"!#$&+-.^_`|~".contains(&byte) {
+            out.push(byte as char);
+        } else {
+            out.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    out
+}
+
+pub fn speaker_label(speaker: &str) -> String {
+    match speaker {
+        "user" => "You".into(),
+        "ai" | "assistant" | "coach" => "Coach".into(),
+        other => {
+            let mut chars = other.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => "Speaker".into(),
+            }
+        }
+    }
+}
+
+fn split_ms(ms: i64) -> (i64, i64, i64, i64) {
+    let ms = ms.max(0);
+    (
+        ms / 3_600_000,
+        (ms / 60_000) % 60,
+        (ms / 1000) % 60,
+        ms % 1000,
+    )
+}
+
+pub fn format_srt_timestamp(ms: i64) -> String {
+    let (h, m, s, millis) = split_ms(ms);
+    format!("{:02}:{:02}:{:02},{:03}", h, m, s, millis)
+}
+
+pub fn format_vtt_timestamp(ms: i64) -> String {
+    let (h, m, s, millis) = split_ms(ms);
+    format!("{:02}:{:02}:{:02}.{:03}", h, m, s, millis)
+}
+
+fn format_clock(ms: i64) -> String {
+    let (h, m, s, _) = split_ms(ms);
+    if h > 0 {
+        format!("{:02}:{:02}:{:02}", h, m, s)
+    } else {
+        format!("{:02}:{:02}", m, s)
+    }
+}
+
+pub fn wrap_caption_lines(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    for word in text.split_whitespace() {
+        let needed = if current.is_empty() {
+            word.chars().count()
+        } else {
+            current.chars().count() + 1 + word.chars().count()
+        };
+        if needed > width && !current.is_empty() {
+            lines.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
+struct Cue {
+    start_ms: i64,
+    end_ms: i64,
+    lines: Vec<String>,
+}
+
+// Splits each segment into cues of at most `CAPTION_MAX_LINES` wrapped lines, sharing the
+// segment's time span across its cues in proportion to their character counts. Formats without
+// voice tags (SRT) carry the speaker label inline, so it is wrapped along with the text.
+fn caption_cues(segments: &[TranscriptSegment], inline_speaker: bool) -> Vec<(String, Cue)> {
+    let mut cues = Vec::new();
+    for segment in segments {
+        let text = if inline_speaker {
+            format!(
+                "{}: {}",
+                speaker_label(&segment.speaker),
+                segment.text.trim()
+            )
+        } else {
+            segment.text.clone()
+        };
+        let lines = wrap_caption_lines(&text, CAPTION_LINE_WIDTH);
+        if lines.is_empty() {
+            continue;
+        }
+        let chunks: Vec<Vec<String>> = lines
+            .chunks(CAPTION_MAX_LINES)
+            .map(|chunk| chunk.to_vec())
+            .collect();
+        let total_chars: usize = chunks
+            .iter()
+            .map(|chunk| chunk.iter().map(|l| l.chars().count()).sum::<usize>())
+            .sum();
+        let span = (segment.end_ms - segment.start_ms).max(0);
+        let mut consumed = 0usize;
+        let mut start_ms = segment.start_ms;
+        let count = chunks.len();
+        for (idx, chunk) in chunks.into_iter().enumerate() {
+            consumed += chunk.iter().map(|l| l.chars().count()).sum::<usize>();
+            let end_ms = if idx + 1 == count {
+                segment.end_ms.max(segment.start_ms)
+            } else {
+                segment.start_ms + span * consumed as i64 / total_chars.max(1) as i64
+            };
+            cues.push((
+                segment.speaker.clone(),
+                Cue {
+                    start_ms,
+                    end_ms,
+                    lines: chunk,
+                },
+            ));
+            start_ms = end_ms;
+        }
+    }
+    cues
+}
+
+pub fn render_srt(segments: &[TranscriptSegment]) -> String {
+    let mut out = String::new();
+    for (idx, (_, cue)) in caption_cues(segments, true).into_iter().enumerate() {
+        out.push_str(&format!(
+            "{}\n{} --> {}\n",
+            idx + 1,
+            format_srt_timestamp(cue.start_ms),
+            format_srt_timestamp(cue.end_ms)
+        ));
+        out.push_str(&cue.lines.join("\n"));
+        out.push_str("\n\n");
+    }
+    out
+}
+
+pub fn render_vtt(segments: &[TranscriptSegment]) -> String {
+    let mut out = String::from("WEBVTT\n\n");
+    for (speaker, cue) in caption_cues(segments, false) {
+        let lines: Vec<String> = cue.lines.iter().map(|line| escape_vtt(line)).collect();
+        out.push_str(&format!(
+            "{} --> {}\n<v {}>{}\n\n",
+            format_vtt_timestamp(cue.start_ms),
+            format_vtt_timestamp(cue.end_ms),
+            escape_vtt(&speaker_label(&speaker)),
+            lines.join("\n")
+        ));
+    }
+    out
+}
+
+// Cue text is markup: `&` and `<` must be escaped, and escaping `>` keeps a literal "-->"
+// from reading as a timing line. Wrapping already collapses newlines, so no blank lines
+// can end a cue early.
+fn escape_vtt(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+pub fn render_markdown(doc: &TranscriptDocument<'_>) -> String {
+    let mut out = format!(
+        "# {}\n\n_{}_\n\n",
+        doc.topic_title,
+        doc.start_time.format("%Y-%m-%d %H:%M UTC")
+    );
+    for segment in doc.segments {
+        out.push_str(&format!(
+            "**{}** `{}`: {}\n\n",
+            speaker_label(&segment.speaker),
+            format_clock(segment.start_ms),
+            segment.text.trim()
+        ));
+    }
+    out
+}
+
+pub fn render_text(segments: &[TranscriptSegment]) -> String {
+    let mut out = String::new();
+    for segment in segments {
+        out.push_str(&format!(
+            "[{}] {}: {}\n",
+            format_clock(segment.start_ms),
+            speaker_label(&segment.speaker),
+            segment.text.trim()
+        ));
+    }
+    out
+}
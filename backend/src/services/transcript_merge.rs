@@ -0,0 +1,246 @@
+use std::collections::HashSet;
+
+use crate::models::transcript::TranscriptSegment;
+
+pub const USER_SPEAKER: &str = "user";
+pub const COACH_SPEAKER: &str = "ai";
+
+#[derive(Debug, Clone, Copy)]
+pub struct MergeOptions {
+    // Segments longer than this are split at sentence boundaries.
+    pub max_segment_ms: i64,
+    // Overlaps are resolved by trimming the earlier segment unless that would leave it shorter
+    // than this, in which case the later segment is pushed back instead.
+    pub min_segment_ms: i64,
+}
+
+impl Default for MergeOptions {
+    fn default() -> Self {
+        Self {
+            max_segment_ms: 15_000,
+            min_segment_ms: 300,
+        }
+    }
+}
+
+// Moves audio-derived segments onto the session timeline when the recording started after the
+// realtime session did.
+pub fn shift_segments(segments: &mut [TranscriptSegment], offset_ms: i64) {
+    for segment in segments {
+        segment.start_ms = (segment.start_ms + offset_ms).max(0);
+        segment.end_ms = (segment.end_ms + offset_ms).max(segment.start_ms);
+        for word in segment.words.iter_mut().flatten() {
+            word.start_ms = (word.start_ms + offset_ms).max(0);
+            word.end_ms = (word.end_ms + offset_ms).max(word.start_ms);
+        }
+    }
+}
+
+pub fn merge_transcripts(
+    primary: &[TranscriptSegment],
+    coach_turns: &[TranscriptSegment],
+    options: &MergeOptions,
+) -> Vec<TranscriptSegment> {
+    let mut merged: Vec<TranscriptSegment> = Vec::with_capacity(primary.len() + coach_turns.len());
+    let primary: Vec<TranscriptSegment> = primary
+        .iter()
+        .map(|segment| TranscriptSegment {
+            speaker: normalize_speaker(&segment.speaker),
+            ..segment.clone()
+        })
+        .collect();
+    let coach = coach_turns
+        .iter()
+        .map(|turn| TranscriptSegment {
+            speaker: COACH_SPEAKER.into(),
+            ..turn.clone()
+        })
+        // Clients may send coach turns both inline and separately, and a retried finalize
+        // sends them again alongside the transcript they were already merged into.
+        .filter(|turn| !already_merged(&primary, turn));
+
+    let mut seen = HashSet::new();
+    for segment in primary.iter().cloned().chain(coach) {
+        if segment.text.trim().is_empty() {
+            continue;
+        }
+        if !seen.insert((
+            segment.speaker.clone(),
+            segment.start_ms,
+            collapse_whitespace(&segment.text),
+        )) {
+            continue;
+        }
+        let mut segment = segment;
+        segment.text = segment.text.trim().to_string();
+        segment.end_ms = segment.end_ms.max(segment.start_ms);
+        merged.extend(split_long_segment(segment, options.max_segment_ms));
+    }
+
+    merged.sort_by(|a, b| {
+        a.start_ms
+            .cmp(&b.start_ms)
+            .then(a.end_ms.cmp(&b.end_ms))
+            .then(speaker_rank(&a.speaker).cmp(&speaker_rank(&b.speaker)))
+    });
+
+    resolve_overlaps(&mut merged, options.min_segment_ms);
+    merged
+}
+
+fn normalize_speaker(speaker: &str) -> String {
+    match speaker {
+        "assistant" => COACH_SPEAKER.into(),
+        other => other.to_string(),
+    }
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+// A turn was merged before if segments of the same speaker, starting at the turn's original
+// start and within its span, spell out its text. Earlier merges may have split the turn into
+// sentence pieces and trimmed their ends.
+fn already_merged(primary: &[TranscriptSegment], turn: &TranscriptSegment) -> bool {
+    let text = collapse_whitespace(&turn.text);
+    let mut pieces: Vec<&TranscriptSegment> = primary
+        .iter()
+        .filter(|s| {
+            s.speaker == turn.speaker
+                && s.start_ms >= turn.start_ms
+                && s.start_ms <= turn.end_ms.max(turn.start_ms)
+        })
+        .collect();
+    pieces.sort_by_key(|s| s.start_ms);
+    let mut joined = String::new();
+    for piece in pieces {
+        if joined.is_empty() && piece.start_ms != turn.start_ms {
+            continue;
+        }
+        if !joined.is_empty() {
+            joined.push(' ');
+        }
+        joined.push_str(&collapse_whitespace(&piece.text));
+        if joined == text {
+            return true;
+        }
+        if !text.starts_with(&joined) {
+            joined.clear();
+        }
+    }
+    false
+}
+
+// At identical timestamps the coach prompt precedes the user's answer.
+fn speaker_rank(speaker: &str) -> u8 {
+    if speaker == COACH_SPEAKER {
+        0
+    } else {
+        1
+    }
+}
+
+fn resolve_overlaps(segments: &mut [TranscriptSegment], min_segment_ms: i64) {
+    for idx in 1..segments.len() {
+        let (before, after) = segments.split_at_mut(idx);
+        let prev = &mut before[idx - 1];
+        let current = &mut after[0];
+        if current.start_ms >= prev.end_ms {
+            continue;
+        }
+        if current.start_ms - prev.start_ms >= min_segment_ms {
+            prev.end_ms = current.start_ms;
+        } else {
+            let duration = current.end_ms - current.start_ms;
+            current.start_ms = prev.end_ms;
+            current.end_ms = current.start_ms + duration;
+        }
+    }
+}
+
+pub fn split_sentences(text: &str) -> Vec<String> {
+    let mut sentences = Vec::new();
+    let mut current = String::new();
+    let mut chars = text.chars().peekable();
+    while let Some(ch) = chars.next() {
+        current.push(ch);
+        let terminal = matches!(ch, '.' | '!' | '?' | '。' | '！' | '？');
+        let at_boundary = match chars.peek() {
+            None => true,
+            Some(next) => next.is_whitespace() || !ch.is_ascii(),
+        };
+        if terminal && at_boundary {
+            let sentence = current.trim().to_string();
+            if !sentence.is_empty() {
+                sentences.push(sentence);
+            }
+            current.clear();
+        }
+    }
+    let rest = current.trim();
+    if !rest.is_empty() {
+        sentences.push(rest.to_string());
+    }
+    sentences
+}
+
+fn split_long_segment(segment: TranscriptSegment, max_segment_ms: i64) -> Vec<TranscriptSegment> {
+    let span = segment.end_ms - segment.start_ms;
+    if span <= max_segment_ms {
+        return vec![segment];
+    }
+    let sentences = split_sentences(&segment.text);
+    if sentences.len() < 2 {
+        return vec![segment];
+    }
+
+    // Greedily pack sentences into pieces, estimating time from character share.
+    let total_chars: i64 = sentences.iter().map(|s| s.chars().count() as i64).sum();
+    let ms_for = |chars: i64| span * chars / total_chars.max(1);
+    let mut pieces: Vec<(String, i64)> = Vec::new();
+    for sentence in sentences {
+        let chars = sentence.chars().count() as i64;
+        match pieces.last_mut() {
+            Some((text, piece_chars)) if ms_for(*piece_chars + chars) <= max_segment_ms => {
+                text.push(' ');
+                text.push_str(&sentence);
+                *piece_chars += chars;
+            }
+            _ => pieces.push((sentence, chars)),
+        }
+    }
+
+    let count = pieces.len();
+    let mut start_ms = segment.start_ms;
+    let mut consumed = 0;
+    pieces
+        .into_iter()
+        .enumerate()
+        .map(|(idx, (text, chars))| {
+            consumed += chars;
+            let is_last = idx + 1 == count;
+            let end_ms = if is_last {
+                segment.end_ms
+            } else {
+                segment.start_ms + ms_for(consumed)
+            };
+            let words = segment.words.as_ref().map(|words| {
+                words
+                    .iter()
+                    .filter(|w| w.start_ms >= start_ms && (w.start_ms < end_ms || is_last))
+                    .cloned()
+                    .collect()
+            });
+            let piece = TranscriptSegment {
+                speaker: segment.speaker.clone(),
+                text,
+                start_ms,
+                end_ms,
+                words,
+            };
+            start_ms = end_ms;
+            piece
+        })
+        .collect()
+}
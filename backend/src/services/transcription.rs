@@ -1,8 +1,9 @@
 use anyhow::Context;
 use reqwest::multipart;
 use serde::Deserialize;
+use uuid::Uuid;
 
-use crate::models::transcript::TranscriptSegment;
+use crate::models::transcript::{TranscriptSegment, TranscriptWord};
 use crate::services::storage::StorageService;
 
 #[derive(Deserialize)]
@@ -10,21 +11,133 @@ struct WhisperSegment {
     start: f64,
     end: f64,
     text: String,
+    avg_logprob: Option<f64>,
+}
+
+#[derive(Deserialize)]
+struct WhisperWord {
+    word: String,
+    start: f64,
+    end: f64,
+    // Not returned by OpenAI, but by self-hosted Whisper servers.
+    probability: Option<f64>,
 }
 
 #[derive(Deserialize)]
 struct WhisperVerboseResponse {
     text: String,
     segments: Option<Vec<WhisperSegment>>,
+    words: Option<Vec<WhisperWord>>,
+}
+
+fn to_ms(seconds: f64) -> i64 {
+    (seconds * 1000.0) as i64
+}
+
+fn distance_to(seg: &WhisperSegment, start: f64) -> f64 {
+    if start < seg.start {
+        seg.start - start
+    } else if start >= seg.end {
+        start - seg.end
+    } else {
+        0.0
+    }
+}
+
+// Words come back as one flat list and are attached to their nearest segment: the one their
+// start falls in, otherwise the closest one, so words before the first segment or in the gaps
+// between segments are kept. Both lists are time-ordered, so one cursor walks them together.
+// Without per-word probabilities the segment's average log-probability is the confidence.
+fn group_words(words: &[WhisperWord], segments: &[WhisperSegment]) -> Vec<Vec<TranscriptWord>> {
+    let mut grouped = vec![Vec::new(); segments.len()];
+    if segments.is_empty() {
+        return grouped;
+    }
+    let mut idx = 0;
+    let mut previous_start = f64::NEG_INFINITY;
+    for word in words {
+        if word.start < previous_start {
+            idx = 0;
+        }
+        previous_start = word.start;
+        while idx + 1 < segments.len()
+            && distance_to(&segments[idx + 1], word.start) < distance_to(&segments[idx], word.start)
+        {
+            idx += 1;
+        }
+        let segment_confidence = segments[idx]
+            .avg_logprob
+            .map(|logprob| logprob.exp().clamp(0.0, 1.0));
+        grouped[idx].push(TranscriptWord::new(
+            word.word.trim().to_string(),
+            to_ms(word.start),
+            to_ms(word.end),
+            word.probability.or(segment_confidence).map(|c| c as f32),
+        ));
+    }
+    grouped
+}
+
+pub fn parse_verbose_response(
+    raw: &[u8],
+    duration_seconds: Option<i32>,
+) -> anyhow::Result<Vec<TranscriptSegment>> {
+    let body: WhisperVerboseResponse =
+        serde_json::from_slice(raw).context("parse transcription response")?;
+
+    if let Some(segments) = body.segments {
+        let mut grouped = body
+            .words
+            .as_deref()
+            .map(|words| group_words(words, &segments));
+        let mapped = segments
+            .iter()
+            .enumerate()
+            .map(|(idx, seg)| TranscriptSegment {
+                speaker: "user".into(),
+                text: seg.text.clone(),
+                start_ms: to_ms(seg.start),
+                end_ms: to_ms(seg.end),
+                words: grouped
+                    .as_mut()
+                    .map(|groups| std::mem::take(&mut groups[idx])),
+            })
+            .collect();
+        Ok(mapped)
+    } else {
+        let end_ms = duration_seconds.unwrap_or_default().max(0) as i64 * 1000;
+        let words = body.words.map(|words| {
+            words
+                .into_iter()
+                .map(|w| {
+                    TranscriptWord::new(
+                        w.word.trim().to_string(),
+                        to_ms(w.start),
+                        to_ms(w.end),
+                        w.probability.map(|c| c as f32),
+                    )
+                })
+                .collect()
+        });
+        Ok(vec![TranscriptSegment {
+            speaker: "user".into(),
+            text: body.text,
+            start_ms: 0,
+            end_ms,
+            words,
+        }])
+    }
 }
 
 pub async fn transcribe_audio_from_url(
     storage: &StorageService,
     audio_url: &str,
+    owner: Uuid,
     duration_seconds: Option<i32>,
+    language: Option<&str>,
 ) -> anyhow::Result<Vec<TranscriptSegment>> {
     let audio_bytes = storage
-        .get_bytes_from_url(audio_url)
+        .get_bytes_from_url(audio_url, owner)
         .await
         .context("fetch audio from storage")?;
 
@@ -34,10 +147,16 @@ pub async fn transcribe_audio_from_url(
         .file_name("audio.webm")
         .mime_str("audio/webm")?;
 
-    let form = multipart::Form::new()
+    let mut form = multipart::Form::new()
         .part("file", file_part)
         .text("model", "whisper-1")
-        .text("response_format", "verbose_json");
+        .text("response_format", "verbose_json")
+        .text("timestamp_granularities[]", "segment")
+        .text("timestamp_granularities[]", "word");
+    if let Some(language) = language {
+        // ISO-639-1 hint; improves accuracy and latency for non-English practice.
+        form = form.text("language", language.to_string());
+    }
 
     let client = reqwest::Client::new();
     let resp = client
@@ -48,26 +167,6 @@ pub async fn transcribe_audio_from_url(
         .await?
         .error_for_status()?;
 
-    let body: WhisperVerboseResponse = resp.json().await?;
-
-    if let Some(segments) = body.segments {
-        let mapped = segments
-            .into_iter()
-            .map(|seg| TranscriptSegment {
-                speaker: "user".into(),
-                text: seg.text,
-                start_ms: (seg.start * 1000.0) as i64,
-                end_ms: (seg.end * 1000.0) as i64,
-            })
-            .collect();
-        Ok(mapped)
-    } else {
-        let end_ms = duration_seconds.unwrap_or_default().max(0) as i64 * 1000;
-        Ok(vec![TranscriptSegment {
-            speaker: "user".into(),
-            text: body.text,
-            start_ms: 0,
-            end_ms: end_ms,
-        }])
-    }
+    let body = resp.bytes().await?;
+    parse_verbose_response(&body, duration_seconds)
 }
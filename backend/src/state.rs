@@ -1,3 +1,4 @@
+use crate::encryption::Encryption;
 use crate::services::storage::StorageService;
 use sqlx::PgPool;
 use std::sync::Arc;
@@ -6,12 +7,19 @@ use std::sync::Arc;
 pub struct AppState {
     pub db: PgPool,
     pub storage: StorageService,
+    // The storage service's key store, also used to seal transcripts in the database.
+    pub encryption: Encryption,
 }
 
 pub type SharedState = Arc<AppState>;
 
 impl AppState {
     pub fn new(db: PgPool, storage: StorageService) -> SharedState {
-        Arc::new(Self { db, storage })
+        let encryption = storage.encryption().clone();
+        Arc::new(Self {
+            db,
+            storage,
+            encryption,
+        })
     }
 }
@@ -1,14 +1,38 @@
 use tracing::{info, warn};
+use tracing_subscriber::field::RecordFields;
+use tracing_subscriber::fmt::format::{DefaultFields, Writer};
+use tracing_subscriber::fmt::FormatFields;
 use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
 use uuid::Uuid;
 
+use crate::services::redaction::redactor;
+
+// Formats fields (the message included) like the default formatter, then runs the PII
+// detectors over the result so emails, phone numbers and the like never reach the logs.
+#[derive(Debug, Default)]
+pub struct RedactingFields {
+    inner: DefaultFields,
+}
+
+impl<'writer> FormatFields<'writer> for RedactingFields {
+    fn format_fields<R: RecordFields>(
+        &self,
+        mut writer: Writer<'writer>,
+        fields: R,
+    ) -> std::fmt::Result {
+        let mut buf = String::new();
+        self.inner.format_fields(Writer::new(&mut buf), fields)?;
+        writer.write_str(&redactor().redact(&buf).text)
+    }
+}
+
 pub fn init_tracing() {
     let env_filter = EnvFilter::try_from_default_env()
         .unwrap_or_else(|_| EnvFilter::new("info,sqlx=warn,aws_config=warn"));
 
     tracing_subscriber::registry()
         .with(env_filter)
-        .with(fmt::layer())
+        .with(fmt::layer().fmt_fields(RedactingFields::default()))
         .init();
 }
 
@@ -0,0 +1,313 @@
+use axum::body::{self, Body};
+use axum::http::{Method, Request, StatusCode};
+use axum::Router;
+use backend::api;
+use backend::models::audio_recording::{AudioRecording, NewAudioRecording};
+use backend::models::storage_deletion::{AccountDeletion, StorageDeletion};
+use backend::models::topic::{NewTopic, Topic};
+use backend::services::deletion::{delete_account, process_due, purge_tombstoned_sessions, retry_delay};
+use backend::services::storage::StorageService;
+use backend::state::AppState;
+use chrono::{DateTime, Duration, Utc};
+use dotenvy::dotenv;
+use serde_json::{json, Value};
+use sqlx::postgres::PgPoolOptions;
+use sqlx::PgPool;
+use tower::util::ServiceExt;
+use uuid::Uuid;
+
+#[test]
+fn retry_delay_backs_off_and_caps() {
+    assert_eq!(retry_delay(1), Duration::seconds(30));
+    assert_eq!(retry_delay(2), Duration::seconds(60));
+    assert_eq!(retry_delay(4), Duration::seconds(240));
+    assert_eq!(retry_delay(30), Duration::hours(6));
+}
+
+async fn storage() -> StorageService {
+    std::env::set_var("S3_BUCKET", "test-bucket");
+    std::env::set_var("S3_ENDPOINT", "http://localhost:9000");
+    std::env::set_var("S3_ACCESS_KEY", "test");
+    std::env::set_var("S3_SECRET_KEY", "test");
+    StorageService::from_env().await.expect("storage")
+}
+
+async fn test_app(pool: PgPool) -> Router {
+    let state = AppState::new(pool, storage().await);
+    api::router(state)
+}
+
+async fn read_json(res: axum::response::Response) -> Value {
+    let bytes = body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    serde_json::from_slice(&bytes).unwrap()
+}
+
+fn request(method: Method, uri: &str, user: Uuid, body: Option<Value>) -> Request<Body> {
+    let builder = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("content-type", "application/json")
+        .header("x-user-id", user.to_string());
+    match body {
+        Some(body) => builder.body(Body::from(body.to_string())).unwrap(),
+        None => builder.body(Body::empty()).unwrap(),
+    }
+}
+
+async fn test_pool() -> PgPool {
+    dotenv().ok();
+    let url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set for tests");
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect(&url)
+        .await
+        .expect("connect db");
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+    sqlx::query("TRUNCATE client_secrets, transcripts, audio_recordings, sessions, topics RESTART IDENTITY CASCADE")
+        .execute(&pool)
+        .await
+        .unwrap();
+    pool
+}
+
+async fn session_with_audio(app: &Router, pool: &PgPool, user: Uuid, topic_id: Uuid) -> Uuid {
+    let resp = app
+        .clone()
+        .oneshot(request(
+            Method::POST,
+            "/api/sessions",
+            user,
+            Some(json!({ "topic_id": topic_id })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let id: Uuid = read_json(resp).await["id"]
+        .as_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    AudioRecording::insert(
+        pool,
+        NewAudioRecording {
+            session_id: id,
+            storage_url: format!("http://localhost:9000/test-bucket/sessions/{id}/audio.webm"),
+            duration_seconds: Some(5),
+            mime_type: Some("audio/webm".into()),
+            size_bytes: None,
+            quality_status: None,
+        },
+    )
+    .await
+    .unwrap();
+    id
+}
+
+async fn queued_keys(pool: &PgPool, user: Uuid) -> Vec<String> {
+    sqlx::query_scalar("SELECT object_key FROM storage_deletions WHERE user_id = $1 ORDER BY object_key")
+        .bind(user)
+        .fetch_all(pool)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn deleting_an_account_queues_storage_objects() {
+    let pool = test_pool().await;
+    let app = test_app(pool.clone()).await;
+    let user = Uuid::new_v4();
+    let topic = Topic::create(
+        &pool,
+        &NewTopic {
+            title: format!("Farewell Toast {}", Uuid::new_v4()),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+
+    let first = session_with_audio(&app, &pool, user, topic.id).await;
+    let resp = app
+        .clone()
+        .oneshot(request(
+            Method::DELETE,
+            &format!("/api/sessions/{first}"),
+            Uuid::new_v4(),
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    assert!(queued_keys(&pool, user).await.is_empty());
+
+    let resp = app
+        .clone()
+        .oneshot(request(
+            Method::DELETE,
+            &format!("/api/sessions/{first}"),
+            user,
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+    // Tombstoned sessions keep their audio until the restore window passes.
+    assert!(queued_keys(&pool, user).await.is_empty());
+
+    let second = session_with_audio(&app, &pool, user, topic.id).await;
+    let resp = app
+        .clone()
+        .oneshot(request(
+            Method::POST,
+            "/api/topics/private",
+            user,
+            Some(json!({ "title": "My own prompt" })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::CREATED);
+
+    let resp = app
+        .clone()
+        .oneshot(request(Method::DELETE, "/api/me", user, None))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::ACCEPTED);
+    let body = read_json(resp).await;
+    assert_eq!(body["sessions_deleted"], 2);
+    assert_eq!(body["objects_queued"], 2);
+    assert!(body["completed_at"].is_null());
+
+    let (sessions, topics): (i64, i64) = sqlx::query_as(
+        "SELECT (SELECT COUNT(*) FROM sessions WHERE user_id = $1), (SELECT COUNT(*) FROM topics WHERE owner_id = $1)",
+    )
+    .bind(user)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!((sessions, topics), (0, 0));
+    assert_eq!(queued_keys(&pool, user).await.len(), 2);
+    assert!(queued_keys(&pool, user)
+        .await
+        .contains(&format!("sessions/{second}/audio.webm")));
+
+    // Nothing listens on the test endpoint, so every attempt fails and is rescheduled.
+    sqlx::query("UPDATE storage_deletions SET next_attempt_at = now() - interval '1 second' WHERE user_id = $1")
+        .bind(user)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let stats = process_due(&pool, &storage().await, 500).await.unwrap();
+    assert!(stats.failed >= 2);
+    assert_eq!(StorageDeletion::pending_for_user(&pool, user).await.unwrap(), 2);
+    let (attempts, retry_later): (i32, bool) = sqlx::query_as(
+        "SELECT MIN(attempts), BOOL_AND(next_attempt_at > now() AND last_error IS NOT NULL) FROM storage_deletions WHERE user_id = $1",
+    )
+    .bind(user)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(attempts, 1);
+    assert!(retry_later);
+    let record = AccountDeletion::get(&pool, user).await.unwrap().unwrap();
+    assert!(record.completed_at.is_none());
+
+    sqlx::query("UPDATE storage_deletions SET completed_at = now() WHERE user_id = $1")
+        .bind(user)
+        .execute(&pool)
+        .await
+        .unwrap();
+    AccountDeletion::complete_drained(&pool).await.unwrap();
+    let record = AccountDeletion::get(&pool, user).await.unwrap().unwrap();
+    assert!(record.completed_at.is_some());
+}
+
+#[tokio::test]
+async fn unrecognised_audio_urls_block_deletion_instead_of_orphaning() {
+    let pool = test_pool().await;
+    let app = test_app(pool.clone()).await;
+    let user = Uuid::new_v4();
+    let topic = Topic::create(
+        &pool,
+        &NewTopic {
+            title: format!("Lost Luggage {}", Uuid::new_v4()),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    let session = session_with_audio(&app, &pool, user, topic.id).await;
+    sqlx::query("UPDATE audio_recordings SET storage_url = 'https://elsewhere.example/audio.webm' WHERE session_id = $1")
+        .bind(session)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("UPDATE sessions SET deleted_at = now() - interval '100 hours' WHERE id = $1")
+        .bind(session)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    purge_tombstoned_sessions(&pool, &storage().await, Duration::hours(72), 100)
+        .await
+        .unwrap();
+    assert!(delete_account(&pool, &storage().await, user).await.is_err());
+    let remaining: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM sessions WHERE id = $1")
+        .bind(session)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(remaining, 1);
+    assert!(queued_keys(&pool, user).await.is_empty());
+}
+
+#[tokio::test]
+async fn failing_purges_back_off_instead_of_blocking_the_queue() {
+    let pool = test_pool().await;
+    let app = test_app(pool.clone()).await;
+    let user = Uuid::new_v4();
+    let topic = Topic::create(
+        &pool,
+        &NewTopic {
+            title: format!("Missed Connection {}", Uuid::new_v4()),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    let stuck = session_with_audio(&app, &pool, user, topic.id).await;
+    let behind = session_with_audio(&app, &pool, user, topic.id).await;
+    sqlx::query("UPDATE audio_recordings SET storage_url = 'https://elsewhere.example/audio.webm' WHERE session_id = $1")
+        .bind(stuck)
+        .execute(&pool)
+        .await
+        .unwrap();
+    for (session, hours) in [(stuck, 300), (behind, 250)] {
+        sqlx::query("UPDATE sessions SET deleted_at = now() - make_interval(hours => $2) WHERE id = $1")
+            .bind(session)
+            .bind(hours)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+
+    let storage = storage().await;
+    assert_eq!(purge_tombstoned_sessions(&pool, &storage, Duration::hours(72), 1).await.unwrap(), 0);
+    let (attempts, error, retry_at): (i32, Option<String>, Option<DateTime<Utc>>) =
+        sqlx::query_as("SELECT purge_attempts, purge_error, purge_retry_at FROM sessions WHERE id = $1")
+            .bind(stuck)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(attempts, 1);
+    assert!(error.is_some());
+    assert!(retry_at.unwrap() > Utc::now());
+
+    assert_eq!(purge_tombstoned_sessions(&pool, &storage, Duration::hours(72), 1).await.unwrap(), 1);
+    let remaining: Vec<Uuid> = sqlx::query_scalar("SELECT id FROM sessions WHERE id = ANY($1)")
+        .bind(vec![stuck, behind])
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    assert_eq!(remaining, vec![stuck]);
+}
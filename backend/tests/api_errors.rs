@@ -0,0 +1,161 @@
+use axum::body::{self, Body};
+use axum::http::{Method, Request, StatusCode};
+use axum::response::IntoResponse;
+use axum::Router;
+use backend::api;
+use backend::api::error::{ApiError, PROBLEM_CONTENT_TYPE, REQUEST_ID_HEADER};
+use backend::services::storage::StorageService;
+use backend::state::AppState;
+use dotenvy::dotenv;
+use serde_json::{json, Value};
+use sqlx::postgres::PgPoolOptions;
+use sqlx::PgPool;
+use tower::util::ServiceExt;
+use uuid::Uuid;
+
+#[test]
+fn database_errors_map_by_cause() {
+    assert_eq!(ApiError::from(sqlx::Error::RowNotFound).status(), StatusCode::NOT_FOUND);
+    assert_eq!(ApiError::from(sqlx::Error::PoolTimedOut).status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(ApiError::from(sqlx::Error::PoolClosed).status(), StatusCode::SERVICE_UNAVAILABLE);
+    let wrapped = anyhow::Error::from(sqlx::Error::RowNotFound).context("load session");
+    assert_eq!(ApiError::from(wrapped).status(), StatusCode::NOT_FOUND);
+    let internal = ApiError::from(anyhow::anyhow!("password=hunter2 leaked in a message"));
+    assert_eq!(internal.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    assert!(!internal.problem().detail.contains("hunter2"));
+    let problem = ApiError::Validation("expires_in_hours must be positive".into()).problem();
+    assert_eq!(problem.problem_type, "/problems/validation-failed");
+    assert_eq!(problem.title, "Unprocessable Entity");
+    assert_eq!(problem.status, 422);
+    assert_eq!(problem.request_id, None);
+}
+
+async fn test_app(pool: PgPool) -> Router {
+    std::env::set_var("S3_BUCKET", "test-bucket");
+    std::env::set_var("S3_ENDPOINT", "http://localhost:9000");
+    std::env::set_var("S3_ACCESS_KEY", "test");
+    std::env::set_var("S3_SECRET_KEY", "test");
+    let storage = StorageService::from_env().await.expect("storage");
+    let state = AppState::new(pool, storage);
+    api::router(state)
+}
+
+fn request(method: Method, uri: &str, user: Option<Uuid>, request_id: Option<&str>, body: Option<Value>) -> Request<Body> {
+    let mut builder = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("content-type", "application/json");
+    if let Some(user) = user {
+        builder = builder.header("x-user-id", user.to_string());
+    }
+    if let Some(request_id) = request_id {
+        builder = builder.header(REQUEST_ID_HEADER, request_id);
+    }
+    match body {
+        Some(body) => builder.body(Body::from(body.to_string())).unwrap(),
+        None => builder.body(Body::empty()).unwrap(),
+    }
+}
+
+async fn test_pool() -> PgPool {
+    dotenv().ok();
+    let url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set for tests");
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect(&url)
+        .await
+        .expect("connect db");
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+    sqlx::query("TRUNCATE client_secrets, transcripts, audio_recordings, sessions, topics RESTART IDENTITY CASCADE")
+        .execute(&pool)
+        .await
+        .unwrap();
+    pool
+}
+
+// Returns the status, content type, echoed request id and parsed body.
+async fn call(app: &Router, req: Request<Body>) -> (StatusCode, String, String, Value) {
+    let resp = app.clone().oneshot(req).await.unwrap();
+    let status = resp.status();
+    let header = |name: &str| {
+        resp.headers()
+            .get(name)
+            .and_then(|h| h.to_str().ok())
+            .unwrap_or_default()
+            .to_string()
+    };
+    let content_type = header("content-type");
+    let request_id = header(REQUEST_ID_HEADER);
+    let bytes = body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    (status, content_type, request_id, serde_json::from_slice(&bytes).unwrap_or(Value::Null))
+}
+
+#[tokio::test]
+async fn handler_failures_are_problem_documents() {
+    let pool = test_pool().await;
+    let app = test_app(pool.clone()).await;
+    let user = Uuid::new_v4();
+
+    let missing = Uuid::new_v4();
+    let (status, content_type, request_id, problem) = call(
+        &app,
+        request(Method::GET, &format!("/api/sessions/{missing}"), Some(user), Some("trace-abc-123"), None),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(content_type, PROBLEM_CONTENT_TYPE);
+    assert_eq!(request_id, "trace-abc-123");
+    assert_eq!(problem["type"], "/problems/not-found");
+    assert_eq!(problem["title"], "Not Found");
+    assert_eq!(problem["status"], 404);
+    assert_eq!(problem["request_id"], "trace-abc-123");
+
+    // Without a usable caller id one is minted and still echoed.
+    let (status, content_type, request_id, problem) =
+        call(&app, request(Method::GET, "/api/sessions", None, Some("bad id"), None)).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(content_type, PROBLEM_CONTENT_TYPE);
+    assert!(Uuid::parse_str(&request_id).is_ok());
+    assert_eq!(problem["request_id"], request_id.as_str());
+    assert_eq!(problem["detail"], "missing x-user-id header");
+
+    let (status, _, _, problem) = call(
+        &app,
+        request(Method::POST, "/api/sessions", Some(user), None, Some(json!({ "topic_id": missing }))),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(problem["detail"], format!("topic {missing} not found"));
+
+    let (status, _, _, problem) =
+        call(&app, request(Method::GET, "/api/sessions/search?q=%20", Some(user), None, None)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(problem["type"], "/problems/bad-request");
+
+    let (status, _, _, problem) = call(
+        &app,
+        request(
+            Method::POST,
+            "/api/realtime/session",
+            Some(user),
+            None,
+            Some(json!({ "session_id": missing })),
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(problem["status"], 404);
+
+    let response = ApiError::Gone("session time limit reached".into()).into_response();
+    assert_eq!(response.status(), StatusCode::GONE);
+
+    // A database outage is no longer reported as a missing session.
+    pool.close().await;
+    let (status, _, _, problem) = call(
+        &app,
+        request(Method::GET, &format!("/api/sessions/{missing}"), Some(user), None, None),
+    )
+    .await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(problem["detail"], "the database is unavailable");
+}
@@ -0,0 +1,225 @@
+use axum::body::{self, Body};
+use axum::extract::ConnectInfo;
+use axum::http::{HeaderMap, Method, Request, StatusCode};
+use axum::Router;
+use backend::api;
+use backend::auth::{ClientInfo, TrustedProxies};
+use backend::models::topic::{NewTopic, Topic};
+use backend::models::user_role::{UserRole, ADMIN_ROLE};
+use backend::services::audit;
+use backend::services::storage::StorageService;
+use backend::state::AppState;
+use chrono::{Duration, Utc};
+use dotenvy::dotenv;
+use serde_json::{json, Value};
+use sqlx::postgres::PgPoolOptions;
+use sqlx::PgPool;
+use std::net::SocketAddr;
+use tower::util::ServiceExt;
+use uuid::Uuid;
+
+#[test]
+fn client_info_only_trusts_forwarding_from_known_proxies() {
+    let trusted = TrustedProxies::parse("10.0.0.0/8, ::1").unwrap();
+    assert!(trusted.contains("10.20.30.40".parse().unwrap()));
+    assert!(trusted.contains("::ffff:10.0.0.1".parse().unwrap()));
+    assert!(!trusted.contains("11.0.0.1".parse().unwrap()));
+    assert!(TrustedProxies::parse("10.0.0.0/33").is_err());
+
+    let peer: SocketAddr = "10.0.0.9:4000".parse().unwrap();
+    let mut headers = HeaderMap::new();
+    // The left-most entry is whatever the client sent; the proxies appended the rest.
+    headers.insert("x-forwarded-for", "192.0.2.66, 203.0.113.5, 10.0.0.1".parse().unwrap());
+    headers.insert("user-agent", "SpeechApp/2.1".parse().unwrap());
+    let client = ClientInfo::from_parts(&headers, Some(peer), &trusted);
+    assert_eq!(client.ip.as_deref(), Some("203.0.113.5"));
+    assert_eq!(client.user_agent.as_deref(), Some("SpeechApp/2.1"));
+
+    // A direct caller cannot choose its address with the header.
+    let direct: SocketAddr = "198.51.100.20:5000".parse().unwrap();
+    let client = ClientInfo::from_parts(&headers, Some(direct), &trusted);
+    assert_eq!(client.ip.as_deref(), Some("198.51.100.20"));
+    let client = ClientInfo::from_parts(&headers, Some(peer), &TrustedProxies::default());
+    assert_eq!(client.ip.as_deref(), Some("10.0.0.9"));
+
+    let client = ClientInfo::from_parts(&HeaderMap::new(), Some(peer), &trusted);
+    assert_eq!(client.ip.as_deref(), Some("10.0.0.9"));
+    assert_eq!(client.user_agent, None);
+
+    let event = audit::event(&client, None, audit::AUTH_FAILED, audit::Target::None, Value::Null);
+    assert_eq!(event.details, json!({}));
+    assert_eq!(event.target_type, None);
+}
+
+async fn test_app(pool: PgPool) -> Router {
+    std::env::set_var("TRUSTED_PROXIES", "10.0.0.0/8");
+    std::env::set_var("S3_BUCKET", "test-bucket");
+    std::env::set_var("S3_ENDPOINT", "http://localhost:9000");
+    std::env::set_var("S3_ACCESS_KEY", "test");
+    std::env::set_var("S3_SECRET_KEY", "test");
+    let storage = StorageService::from_env().await.expect("storage");
+    let state = AppState::new(pool, storage);
+    api::router(state)
+}
+
+fn request(method: Method, uri: &str, user: Uuid, body: Option<Value>) -> Request<Body> {
+    let builder = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("content-type", "application/json")
+        .header("x-user-id", user.to_string())
+        .header("x-forwarded-for", "198.51.100.7")
+        .header("user-agent", "audit-test")
+        .extension(ConnectInfo("10.0.0.9:4000".parse::<SocketAddr>().unwrap()));
+    match body {
+        Some(body) => builder.body(Body::from(body.to_string())).unwrap(),
+        None => builder.body(Body::empty()).unwrap(),
+    }
+}
+
+async fn test_pool() -> PgPool {
+    dotenv().ok();
+    let url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set for tests");
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect(&url)
+        .await
+        .expect("connect db");
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+    sqlx::query("TRUNCATE client_secrets, transcripts, audio_recordings, sessions, topics RESTART IDENTITY CASCADE")
+        .execute(&pool)
+        .await
+        .unwrap();
+    pool
+}
+
+async fn call(app: &Router, method: Method, uri: &str, user: Uuid, body: Option<Value>) -> (StatusCode, Value) {
+    let resp = app.clone().oneshot(request(method, uri, user, body)).await.unwrap();
+    let status = resp.status();
+    let bytes = body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    (status, serde_json::from_slice(&bytes).unwrap_or(Value::Null))
+}
+
+#[tokio::test]
+async fn security_actions_land_in_an_append_only_audit_log() {
+    let pool = test_pool().await;
+    let app = test_app(pool.clone()).await;
+    let user = Uuid::new_v4();
+    let admin = Uuid::new_v4();
+    let topic = Topic::create(
+        &pool,
+        &NewTopic {
+            title: format!("Audit Topic {}", Uuid::new_v4()),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+
+    let (status, session) = call(
+        &app,
+        Method::POST,
+        "/api/sessions",
+        user,
+        Some(json!({ "topic_id": topic.id })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let session_id = session["id"].as_str().unwrap().to_string();
+    let (status, _) = call(
+        &app,
+        Method::POST,
+        &format!("/api/sessions/{session_id}/shares"),
+        user,
+        Some(json!({})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let (status, _) = call(
+        &app,
+        Method::DELETE,
+        &format!("/api/sessions/{session_id}"),
+        user,
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+
+    // Only admins may read the log, and the refusal is itself audited.
+    let (status, _) = call(&app, Method::GET, "/api/audit/events", user, None).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    UserRole::grant(&pool, admin, ADMIN_ROLE).await.unwrap();
+
+    let (status, page) = call(
+        &app,
+        Method::GET,
+        &format!("/api/audit/events?target_type=session&target_id={session_id}"),
+        admin,
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(page["total"], 2);
+    let events = page["events"].as_array().unwrap();
+    assert_eq!(events[0]["action"], audit::SESSION_DELETED);
+    assert_eq!(events[1]["action"], audit::SESSION_CREATED);
+    assert_eq!(events[0]["actor_id"], user.to_string());
+    assert_eq!(events[0]["ip"], "198.51.100.7");
+    assert_eq!(events[0]["user_agent"], "audit-test");
+
+    let (_, page) = call(
+        &app,
+        Method::GET,
+        &format!("/api/audit/events?actor_id={user}&action=share.create"),
+        admin,
+        None,
+    )
+    .await;
+    assert_eq!(page["total"], 1);
+    assert_eq!(page["events"][0]["details"]["session_id"], session_id);
+    let (_, page) = call(
+        &app,
+        Method::GET,
+        &format!("/api/audit/events?actor_id={user}&action=auth.failure"),
+        admin,
+        None,
+    )
+    .await;
+    assert_eq!(page["total"], 1);
+    assert_eq!(page["events"][0]["details"]["path"], "/api/audit/events");
+    assert_eq!(page["events"][0]["details"]["status"], 403);
+
+    // Rows cannot be edited or deleted outside the retention purge.
+    let target: Uuid = session_id.parse().unwrap();
+    assert!(sqlx::query("UPDATE audit_events SET actor_id = NULL WHERE target_id = $1")
+        .bind(target)
+        .execute(&pool)
+        .await
+        .is_err());
+    assert!(sqlx::query("DELETE FROM audit_events WHERE target_id = $1")
+        .bind(target)
+        .execute(&pool)
+        .await
+        .is_err());
+
+    let stale = Uuid::new_v4();
+    sqlx::query(
+        "INSERT INTO audit_events (actor_id, action, occurred_at) VALUES ($1, 'session.create', now() - interval '400 days')",
+    )
+    .bind(stale)
+    .execute(&pool)
+    .await
+    .unwrap();
+    let purged = audit::purge_expired(&pool, Duration::days(365), Utc::now())
+        .await
+        .unwrap();
+    assert!(purged >= 1);
+    let remaining: i64 =
+        sqlx::query_scalar("SELECT count(*) FROM audit_events WHERE actor_id = $1 OR target_id = $2")
+            .bind(stale)
+            .bind(target)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(remaining, 2);
+}
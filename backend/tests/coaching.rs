@@ -0,0 +1,290 @@
+use axum::body::{self, Body};
+use axum::http::{Method, Request, StatusCode};
+use axum::Router;
+use backend::api;
+use backend::encryption::Encryption;
+use backend::models::topic::{NewTopic, Topic};
+use backend::models::transcript::{upsert_transcript, TranscriptSegment};
+use backend::services::coaching::{validate_comment, CommentInput};
+use backend::services::storage::StorageService;
+use backend::state::AppState;
+use dotenvy::dotenv;
+use serde_json::{json, Value};
+use sqlx::postgres::PgPoolOptions;
+use sqlx::PgPool;
+use tower::util::ServiceExt;
+use uuid::Uuid;
+
+fn comment(body: &str) -> CommentInput {
+    CommentInput {
+        body: body.into(),
+        parent_id: None,
+        segment_index: None,
+        start_ms: None,
+        end_ms: None,
+    }
+}
+
+#[test]
+fn comment_anchors_are_validated() {
+    assert!(validate_comment(&comment("Nice pacing"), 0).is_ok());
+    assert!(validate_comment(&comment("   "), 0).is_err());
+    let anchored = CommentInput {
+        segment_index: Some(1),
+        start_ms: Some(500),
+        end_ms: Some(900),
+        ..comment("Slow down here")
+    };
+    assert!(validate_comment(&anchored, 2).is_ok());
+    assert!(validate_comment(&anchored, 1).is_err());
+    let half_range = CommentInput {
+        start_ms: Some(10),
+        ..comment("Range")
+    };
+    assert!(validate_comment(&half_range, 0).is_err());
+    let backwards = CommentInput {
+        start_ms: Some(10),
+        end_ms: Some(5),
+        ..comment("Range")
+    };
+    assert!(validate_comment(&backwards, 0).is_err());
+    let anchored_reply = CommentInput {
+        parent_id: Some(Uuid::new_v4()),
+        segment_index: Some(0),
+        ..comment("Thanks")
+    };
+    assert!(validate_comment(&anchored_reply, 1).is_err());
+}
+
+async fn test_app(pool: PgPool) -> Router {
+    std::env::set_var("S3_BUCKET", "test-bucket");
+    std::env::set_var("S3_ENDPOINT", "http://localhost:9000");
+    std::env::set_var("S3_ACCESS_KEY", "test");
+    std::env::set_var("S3_SECRET_KEY", "test");
+    let storage = StorageService::from_env().await.expect("storage");
+    let state = AppState::new(pool, storage);
+    api::router(state)
+}
+
+fn request(method: Method, uri: &str, user: Uuid, body: Option<Value>) -> Request<Body> {
+    let builder = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("content-type", "application/json")
+        .header("x-user-id", user.to_string());
+    match body {
+        Some(body) => builder.body(Body::from(body.to_string())).unwrap(),
+        None => builder.body(Body::empty()).unwrap(),
+    }
+}
+
+async fn test_pool() -> PgPool {
+    dotenv().ok();
+    let url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set for tests");
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect(&url)
+        .await
+        .expect("connect db");
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+    sqlx::query("TRUNCATE client_secrets, transcripts, audio_recordings, sessions, topics RESTART IDENTITY CASCADE")
+        .execute(&pool)
+        .await
+        .unwrap();
+    pool
+}
+
+async fn call(app: &Router, method: Method, uri: &str, user: Uuid, body: Option<Value>) -> (StatusCode, Value) {
+    let resp = app.clone().oneshot(request(method, uri, user, body)).await.unwrap();
+    let status = resp.status();
+    let bytes = body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    (status, serde_json::from_slice(&bytes).unwrap_or(Value::Null))
+}
+
+#[tokio::test]
+async fn coaches_review_sessions_students_share() {
+    let pool = test_pool().await;
+    let app = test_app(pool.clone()).await;
+    let coach = Uuid::new_v4();
+    let student = Uuid::new_v4();
+    let topic = Topic::create(
+        &pool,
+        &NewTopic {
+            title: format!("Debate Opening {}", Uuid::new_v4()),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    let (status, session) = call(
+        &app,
+        Method::POST,
+        "/api/sessions",
+        student,
+        Some(json!({ "topic_id": topic.id })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let session_id = session["id"].as_str().unwrap().to_string();
+    upsert_transcript(
+        &pool,
+        &Encryption::disabled(),
+        session_id.parse().unwrap(),
+        true,
+        &[
+            TranscriptSegment {
+                speaker: "user".into(),
+                text: "Honourable judges.".into(),
+                start_ms: 0,
+                end_ms: 1000,
+                words: None,
+            },
+            TranscriptSegment {
+                speaker: "user".into(),
+                text: "Our case rests on three points.".into(),
+                start_ms: 1000,
+                end_ms: 3000,
+                words: None,
+            },
+        ],
+    )
+    .await
+    .unwrap();
+    let grant_uri = format!("/api/sessions/{session_id}/coaches/{coach}");
+    let comments_uri = format!("/api/sessions/{session_id}/comments");
+
+    // No relationship yet: nothing to grant, nothing to read.
+    let (status, _) = call(&app, Method::PUT, &grant_uri, student, None).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+    let (status, link) = call(
+        &app,
+        Method::POST,
+        "/api/coaching/invitations",
+        coach,
+        Some(json!({ "student_id": student })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(link["status"], "pending");
+    let link_id = link["id"].as_str().unwrap().to_string();
+    let (status, _) = call(
+        &app,
+        Method::POST,
+        &format!("/api/coaching/invitations/{link_id}/accept"),
+        coach,
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, link) = call(
+        &app,
+        Method::POST,
+        &format!("/api/coaching/invitations/{link_id}/accept"),
+        student,
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(link["status"], "accepted");
+
+    let (status, sessions) = call(&app, Method::GET, "/api/coaching/sessions", coach, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(sessions, json!([]));
+    let (status, _) = call(&app, Method::GET, &comments_uri, coach, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, _) = call(&app, Method::PUT, &grant_uri, student, None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (_, sessions) = call(&app, Method::GET, "/api/coaching/sessions", coach, None).await;
+    assert_eq!(sessions[0]["id"].as_str(), Some(session_id.as_str()));
+    let (status, detail) = call(
+        &app,
+        Method::GET,
+        &format!("/api/coaching/sessions/{session_id}"),
+        coach,
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(detail["transcript"].as_array().unwrap().len(), 2);
+
+    let (status, _) = call(
+        &app,
+        Method::POST,
+        &comments_uri,
+        coach,
+        Some(json!({ "body": "Pause here", "segment_index": 5 })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    let (status, review) = call(
+        &app,
+        Method::POST,
+        &comments_uri,
+        coach,
+        Some(json!({ "body": "Pause before the points", "segment_index": 1, "start_ms": 1000, "end_ms": 1400 })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    let (_, notifications) = call(&app, Method::GET, "/api/me/notifications?unread=true", student, None).await;
+    let kinds: Vec<&str> = notifications
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|n| n["kind"].as_str().unwrap())
+        .collect();
+    assert_eq!(kinds, vec!["review_comment", "coach_invitation"]);
+    let notification_id = notifications[0]["id"].as_str().unwrap();
+    let (status, _) = call(
+        &app,
+        Method::POST,
+        &format!("/api/me/notifications/{notification_id}/read"),
+        student,
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+
+    let (status, _) = call(
+        &app,
+        Method::POST,
+        &comments_uri,
+        student,
+        Some(json!({ "body": "Starting my own thread" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    let (status, _) = call(
+        &app,
+        Method::POST,
+        &comments_uri,
+        student,
+        Some(json!({ "body": "Will do, thanks!", "parent_id": review["id"] })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let (_, coach_notifications) = call(&app, Method::GET, "/api/me/notifications", coach, None).await;
+    assert_eq!(coach_notifications[0]["kind"], "review_reply");
+
+    let (_, thread) = call(&app, Method::GET, &comments_uri, student, None).await;
+    let thread = thread.as_array().unwrap();
+    assert_eq!(thread.len(), 2);
+    assert_eq!(thread[1]["parent_id"], review["id"]);
+
+    // Ending the relationship removes the coach's access.
+    let (status, _) = call(
+        &app,
+        Method::DELETE,
+        &format!("/api/coaching/links/{link_id}"),
+        student,
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (status, _) = call(&app, Method::GET, &comments_uri, coach, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (_, sessions) = call(&app, Method::GET, "/api/coaching/sessions", coach, None).await;
+    assert_eq!(sessions, json!([]));
+}
@@ -0,0 +1,278 @@
+use axum::body::{self, Body};
+use axum::http::{Method, Request, StatusCode};
+use axum::Router;
+use backend::api;
+use backend::models::curriculum::{CurriculumUnit, UnitTopicCount};
+use backend::models::topic::{NewTopic, Topic};
+use backend::models::user_role::{UserRole, ADMIN_ROLE};
+use backend::services::curricula::{compute_progress, next_topic, UnitStatus};
+use backend::services::storage::StorageService;
+use backend::state::AppState;
+use dotenvy::dotenv;
+use serde_json::{json, Value};
+use sqlx::postgres::PgPoolOptions;
+use sqlx::PgPool;
+use tower::util::ServiceExt;
+use uuid::Uuid;
+
+async fn test_app(pool: PgPool) -> Router {
+    std::env::set_var("S3_BUCKET", "test-bucket");
+    std::env::set_var("S3_ENDPOINT", "http://localhost:9000");
+    std::env::set_var("S3_ACCESS_KEY", "test");
+    std::env::set_var("S3_SECRET_KEY", "test");
+    let storage = StorageService::from_env().await.expect("storage");
+    let state = AppState::new(pool, storage);
+    api::router(state)
+}
+
+async fn read_json(res: axum::response::Response) -> Value {
+    let bytes = body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    serde_json::from_slice(&bytes).unwrap()
+}
+
+fn request(method: Method, uri: String, user: Uuid, body: Option<Value>) -> Request<Body> {
+    let builder = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("content-type", "application/json")
+        .header("x-user-id", user.to_string());
+    match body {
+        Some(body) => builder.body(Body::from(body.to_string())).unwrap(),
+        None => builder.body(Body::empty()).unwrap(),
+    }
+}
+
+async fn test_pool() -> PgPool {
+    dotenv().ok();
+    let url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set for tests");
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect(&url)
+        .await
+        .expect("connect db");
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+    sqlx::query("TRUNCATE client_secrets, transcripts, audio_recordings, sessions, topics RESTART IDENTITY CASCADE")
+        .execute(&pool)
+        .await
+        .unwrap();
+    pool
+}
+
+async fn insert_topic(pool: &PgPool, title: &str) -> Uuid {
+    Topic::create(
+        pool,
+        &NewTopic {
+            title: title.into(),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap()
+    .id
+}
+
+async fn insert_session(pool: &PgPool, user: Uuid, topic: Uuid, status: &str, duration: i32) -> Uuid {
+    sqlx::query_scalar(
+        r#"
+        INSERT INTO sessions (user_id, topic_id, status, duration_seconds)
+        VALUES ($1, $2, $3, $4)
+        RETURNING id
+        "#,
+    )
+    .bind(user)
+    .bind(topic)
+    .bind(status)
+    .bind(duration)
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+fn unit(position: i32, required_sessions: i32, topic_ids: Vec<Uuid>) -> CurriculumUnit {
+    CurriculumUnit {
+        id: Uuid::new_v4(),
+        curriculum_id: Uuid::nil(),
+        position,
+        title: format!("Unit {position}"),
+        required_sessions,
+        min_duration_seconds: None,
+        topic_ids,
+    }
+}
+
+fn count(unit: &CurriculumUnit, topic_id: Uuid, sessions: i64) -> UnitTopicCount {
+    UnitTopicCount {
+        unit_id: unit.id,
+        topic_id,
+        sessions,
+    }
+}
+
+#[test]
+fn units_unlock_in_order() {
+    let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+    let units = vec![unit(1, 2, vec![b, c]), unit(0, 1, vec![a])];
+
+    let progress = compute_progress(&units, &[]);
+    assert_eq!(progress[0].position, 0);
+    assert_eq!(progress[0].status, UnitStatus::Available);
+    assert_eq!(progress[1].status, UnitStatus::Locked);
+    assert_eq!(
+        next_topic(&units, &progress, &[], &[]).map(|n| n.topic_id),
+        Some(a)
+    );
+
+    // Sessions in a later unit do not count until the earlier one is complete.
+    let counts = vec![count(&units[0], b, 5)];
+    let progress = compute_progress(&units, &counts);
+    assert_eq!(progress[1].status, UnitStatus::Locked);
+
+    let counts = vec![count(&units[1], a, 1), count(&units[0], b, 1)];
+    let progress = compute_progress(&units, &counts);
+    assert_eq!(progress[0].status, UnitStatus::Completed);
+    assert_eq!(progress[1].status, UnitStatus::Available);
+    assert_eq!(progress[1].completed_sessions, 1);
+    // The least-practiced topic of the current unit comes next.
+    assert_eq!(
+        next_topic(&units, &progress, &counts, &[]).map(|n| n.topic_id),
+        Some(c)
+    );
+    // Archived topics are skipped even when they are the least practiced.
+    assert_eq!(
+        next_topic(&units, &progress, &counts, &[c]).map(|n| n.topic_id),
+        Some(b)
+    );
+
+    let counts = vec![
+        count(&units[1], a, 1),
+        count(&units[0], b, 1),
+        count(&units[0], c, 1),
+    ];
+    let progress = compute_progress(&units, &counts);
+    assert!(progress.iter().all(|p| p.status == UnitStatus::Completed));
+    assert_eq!(next_topic(&units, &progress, &counts, &[]), None);
+}
+
+#[tokio::test]
+async fn learner_enrolls_and_progresses_through_units() {
+    let pool = test_pool().await;
+    let app = test_app(pool.clone()).await;
+    let admin = Uuid::new_v4();
+    let learner = Uuid::new_v4();
+    UserRole::grant(&pool, admin, ADMIN_ROLE).await.unwrap();
+
+    let greetings = insert_topic(&pool, "Greet a Neighbor").await;
+    let directions = insert_topic(&pool, "Ask for Directions").await;
+    let title = format!("Everyday Basics {}", Uuid::new_v4());
+
+    let curriculum = json!({
+        "title": title,
+        "units": [
+            { "title": "Warm up", "topic_ids": [greetings] },
+            { "title": "Getting around", "required_sessions": 2, "min_duration_seconds": 60, "topic_ids": [directions] }
+        ]
+    });
+    let resp = app
+        .clone()
+        .oneshot(request(Method::POST, "/api/curricula".into(), learner, Some(curriculum.clone())))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    let resp = app
+        .clone()
+        .oneshot(request(Method::POST, "/api/curricula".into(), admin, Some(curriculum)))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let created = read_json(resp).await;
+    let id = created["id"].as_str().unwrap().to_string();
+    assert_eq!(created["units"].as_array().unwrap().len(), 2);
+
+    let resp = app
+        .clone()
+        .oneshot(request(Method::GET, format!("/api/curricula/{id}/next"), learner, None))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::CONFLICT);
+
+    let resp = app
+        .clone()
+        .oneshot(request(Method::POST, format!("/api/curricula/{id}/enroll"), learner, None))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let resp = app
+        .clone()
+        .oneshot(request(Method::POST, format!("/api/curricula/{id}/enroll"), learner, None))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let resp = app
+        .clone()
+        .oneshot(request(Method::GET, format!("/api/curricula/{id}/next"), learner, None))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(read_json(resp).await["topic"]["id"], greetings.to_string());
+
+    insert_session(&pool, learner, greetings, "ended", 30).await;
+    // Failed and too-short sessions do not count toward the second unit.
+    insert_session(&pool, learner, directions, "failed", 120).await;
+    insert_session(&pool, learner, directions, "ended", 20).await;
+    insert_session(&pool, learner, directions, "ended", 90).await;
+
+    let resp = app
+        .clone()
+        .oneshot(request(Method::GET, format!("/api/curricula/{id}/progress"), learner, None))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let progress = read_json(resp).await;
+    assert_eq!(progress["units"][0]["status"], "completed");
+    assert_eq!(progress["units"][1]["status"], "available");
+    assert_eq!(progress["units"][1]["completed_sessions"], 1);
+    assert_eq!(progress["next"]["topic_id"], directions.to_string());
+    assert!(progress["completed_at"].is_null());
+
+    // Reading progress reports completion without recording it; finalizing does.
+    let last = insert_session(&pool, learner, directions, "active", 75).await;
+    let resp = app
+        .clone()
+        .oneshot(request(Method::GET, format!("/api/curricula/{id}/progress"), learner, None))
+        .await
+        .unwrap();
+    let progress = read_json(resp).await;
+    assert_eq!(progress["completed"], false);
+    assert!(progress["completed_at"].is_null());
+
+    let finalize = json!({
+        "transcript": [{ "speaker": "user", "text": "Which way to the station?", "start_ms": 0, "end_ms": 2000 }],
+        "status": "ended"
+    });
+    let resp = app
+        .clone()
+        .oneshot(request(Method::POST, format!("/api/sessions/{last}/finalize"), learner, Some(finalize)))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let resp = app
+        .clone()
+        .oneshot(request(Method::GET, format!("/api/curricula/{id}/progress"), learner, None))
+        .await
+        .unwrap();
+    let progress = read_json(resp).await;
+    assert_eq!(progress["completed"], true);
+    assert!(progress["completed_at"].is_string());
+
+    let resp = app
+        .clone()
+        .oneshot(request(Method::GET, format!("/api/curricula/{id}/next"), learner, None))
+        .await
+        .unwrap();
+    let next = read_json(resp).await;
+    assert_eq!(next["completed"], true);
+    assert!(next["topic"].is_null());
+}
@@ -0,0 +1,285 @@
+use std::io::Read;
+
+use axum::body::{self, Body};
+use axum::http::{Method, Request, StatusCode};
+use axum::Router;
+use backend::api;
+use backend::encryption::Encryption;
+use backend::models::data_export::DataExport;
+use backend::models::storage_deletion::StorageDeletion;
+use backend::models::topic::{NewTopic, Topic};
+use backend::models::transcript::{upsert_transcript, TranscriptSegment};
+use backend::services::data_export::{
+    audio_extension, build_archive, collect_bundle, export_key, purge_expired_archives, request_export, AudioFile,
+};
+use backend::services::storage::StorageService;
+use backend::state::AppState;
+use chrono::{Duration, Utc};
+use dotenvy::dotenv;
+use serde_json::{json, Value};
+use sqlx::postgres::PgPoolOptions;
+use sqlx::PgPool;
+use tower::util::ServiceExt;
+use uuid::Uuid;
+
+#[test]
+fn audio_extensions_follow_mime_type_then_url() {
+    assert_eq!(audio_extension(Some("audio/webm;codecs=opus"), "x"), "webm");
+    assert_eq!(audio_extension(None, "s3://bucket/a/b.ogg"), "ogg");
+    assert_eq!(audio_extension(Some("application/octet-stream"), "blob"), "bin");
+}
+
+async fn test_app(pool: PgPool) -> Router {
+    std::env::set_var("S3_BUCKET", "test-bucket");
+    std::env::set_var("S3_ENDPOINT", "http://localhost:9000");
+    std::env::set_var("S3_ACCESS_KEY", "test");
+    std::env::set_var("S3_SECRET_KEY", "test");
+    let storage = StorageService::from_env().await.expect("storage");
+    let state = AppState::new(pool, storage);
+    api::router(state)
+}
+
+async fn read_json(res: axum::response::Response) -> Value {
+    let bytes = body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    serde_json::from_slice(&bytes).unwrap()
+}
+
+fn request(method: Method, uri: &str, user: Uuid, body: Option<Value>) -> Request<Body> {
+    let builder = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("content-type", "application/json")
+        .header("x-user-id", user.to_string());
+    match body {
+        Some(body) => builder.body(Body::from(body.to_string())).unwrap(),
+        None => builder.body(Body::empty()).unwrap(),
+    }
+}
+
+async fn test_pool() -> PgPool {
+    dotenv().ok();
+    let url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set for tests");
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect(&url)
+        .await
+        .expect("connect db");
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+    sqlx::query("TRUNCATE client_secrets, transcripts, audio_recordings, sessions, topics RESTART IDENTITY CASCADE")
+        .execute(&pool)
+        .await
+        .unwrap();
+    pool
+}
+
+#[tokio::test]
+async fn export_bundles_sessions_and_is_owner_scoped() {
+    let pool = test_pool().await;
+    let app = test_app(pool.clone()).await;
+    let user = Uuid::new_v4();
+    let topic = Topic::create(
+        &pool,
+        &NewTopic {
+            title: format!("Town Hall {}", Uuid::new_v4()),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+
+    let resp = app
+        .clone()
+        .oneshot(request(
+            Method::POST,
+            "/api/sessions",
+            user,
+            Some(json!({ "topic_id": topic.id })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let session_id: Uuid = read_json(resp).await["id"]
+        .as_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    upsert_transcript(
+        &pool,
+        &Encryption::disabled(),
+        session_id,
+        true,
+        &[TranscriptSegment {
+            speaker: "user".into(),
+            text: "Thanks for coming tonight.".into(),
+            start_ms: 0,
+            end_ms: 1800,
+            words: None,
+        }],
+    )
+    .await
+    .unwrap();
+
+    let bundle = collect_bundle(&pool, &Encryption::disabled(), user).await.unwrap();
+    assert_eq!(bundle.sessions.len(), 1);
+    let missing = vec![Uuid::new_v4()];
+    let archive = build_archive(
+        &bundle,
+        &[AudioFile {
+            session_id,
+            extension: "webm",
+            bytes: b"fake audio".to_vec(),
+        }],
+        &missing,
+    )
+    .unwrap();
+
+    let mut zip = zip::ZipArchive::new(std::io::Cursor::new(archive)).unwrap();
+    let mut text = String::new();
+    zip.by_name(&format!("sessions/{}/transcript.txt", session_id))
+        .unwrap()
+        .read_to_string(&mut text)
+        .unwrap();
+    assert!(text.contains("Thanks for coming tonight."));
+    assert!(zip
+        .by_name(&format!("sessions/{}/audio.webm", session_id))
+        .is_ok());
+    let mut manifest = String::new();
+    zip.by_name("manifest.json")
+        .unwrap()
+        .read_to_string(&mut manifest)
+        .unwrap();
+    let manifest: Value = serde_json::from_str(&manifest).unwrap();
+    assert_eq!(manifest["session_count"], 1);
+    assert_eq!(manifest["missing_audio"], json!(missing));
+    let mut sessions = String::new();
+    zip.by_name("sessions.json")
+        .unwrap()
+        .read_to_string(&mut sessions)
+        .unwrap();
+    let sessions: Value = serde_json::from_str(&sessions).unwrap();
+    assert_eq!(sessions[0]["topic_title"], json!(topic.title));
+
+    let resp = app
+        .clone()
+        .oneshot(request(Method::POST, "/api/me/export", user, None))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::ACCEPTED);
+    let export = read_json(resp).await;
+    assert!(export.get("storage_key").is_none());
+    let uri = format!("/api/me/export/{}", export["id"].as_str().unwrap());
+
+    let resp = app
+        .clone()
+        .oneshot(request(Method::GET, &uri, user, None))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let status = read_json(resp).await;
+    assert_eq!(status["expired"], false);
+
+    let resp = app
+        .clone()
+        .oneshot(request(Method::GET, &uri, Uuid::new_v4(), None))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn interrupted_exports_fail_and_expired_archives_are_deleted() {
+    let pool = test_pool().await;
+    let user = Uuid::new_v4();
+
+    // Left running by a process that restarted mid-build.
+    let stale: Uuid = sqlx::query_scalar(
+        "INSERT INTO data_exports (user_id, status, created_at) VALUES ($1, 'running', now() - interval '2 hours') RETURNING id",
+    )
+    .bind(user)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    let (export, created) = request_export(&pool, user).await.unwrap();
+    assert!(created);
+    assert_ne!(export.id, stale);
+    let stale = DataExport::get_for_user(&pool, stale, user).await.unwrap().unwrap();
+    assert_eq!(stale.status, "failed");
+
+    let expired: Uuid = sqlx::query_scalar(
+        "INSERT INTO data_exports (user_id, status, storage_key, expires_at) VALUES ($1, 'ready', $2, now() - interval '1 minute') RETURNING id",
+    )
+    .bind(user)
+    .bind(export_key(user, Uuid::new_v4()))
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert!(purge_expired_archives(&pool, Utc::now(), 100).await.unwrap() >= 1);
+    assert_eq!(StorageDeletion::pending_for_user(&pool, user).await.unwrap(), 1);
+    let expired = DataExport::get_for_user(&pool, expired, user).await.unwrap().unwrap();
+    assert!(expired.storage_key.is_none());
+}
+
+#[tokio::test]
+async fn long_builds_keep_their_lease_and_abandoned_ones_stay_failed() {
+    let pool = test_pool().await;
+    let app = test_app(pool.clone()).await;
+    let user = Uuid::new_v4();
+
+    // Created long ago but still heartbeating: not stale.
+    let building: Uuid = sqlx::query_scalar(
+        "INSERT INTO data_exports (user_id, status, created_at, heartbeat_at) VALUES ($1, 'running', now() - interval '2 hours', now()) RETURNING id",
+    )
+    .bind(user)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    let (export, created) = request_export(&pool, user).await.unwrap();
+    assert!(!created);
+    assert_eq!(export.id, building);
+    assert!(DataExport::heartbeat(&pool, building).await.unwrap());
+
+    sqlx::query("UPDATE data_exports SET heartbeat_at = now() - interval '2 hours' WHERE id = $1")
+        .bind(building)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let (_, created) = request_export(&pool, user).await.unwrap();
+    assert!(created);
+    // The worker that finally finishes cannot revive its failed row.
+    assert!(!DataExport::heartbeat(&pool, building).await.unwrap());
+    let mut tx = pool.begin().await.unwrap();
+    let revived = DataExport::mark_ready(&mut tx, building, &export_key(user, building), 10, Utc::now() + Duration::hours(1))
+        .await
+        .unwrap();
+    tx.commit().await.unwrap();
+    assert!(!revived);
+    let building = DataExport::get_for_user(&pool, building, user).await.unwrap().unwrap();
+    assert_eq!(building.status, "failed");
+    assert!(building.storage_key.is_none());
+
+    // Polling a ready export is not a download.
+    let ready: Uuid = sqlx::query_scalar(
+        "INSERT INTO data_exports (user_id, status, storage_key, expires_at) VALUES ($1, 'ready', $2, now() + interval '1 hour') RETURNING id",
+    )
+    .bind(user)
+    .bind(export_key(user, Uuid::new_v4()))
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    for _ in 0..3 {
+        let resp = app
+            .clone()
+            .oneshot(request(Method::GET, &format!("/api/me/export/{ready}"), user, None))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let status = read_json(resp).await;
+        assert_eq!(status["download_url"], format!("/api/me/export/{ready}/download").as_str());
+    }
+    let downloads: i64 = sqlx::query_scalar("SELECT count(*) FROM audit_events WHERE action = 'export.download' AND target_id = $1")
+        .bind(ready)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(downloads, 0);
+}
@@ -0,0 +1,191 @@
+use axum::body::{self, Body};
+use axum::http::{Method, Request, StatusCode};
+use axum::Router;
+use backend::api;
+use backend::models::topic::{NewTopic, Topic};
+use backend::services::drills::{secret_expiry, SessionMode};
+use backend::services::storage::StorageService;
+use backend::state::AppState;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use dotenvy::dotenv;
+use serde_json::{json, Value};
+use sqlx::postgres::PgPoolOptions;
+use sqlx::PgPool;
+use tower::util::ServiceExt;
+use uuid::Uuid;
+
+#[test]
+fn modes_round_trip_through_storage_columns() {
+    let mode: SessionMode =
+        serde_json::from_value(json!({ "type": "impromptu", "prep_seconds": 30 })).unwrap();
+    assert_eq!(
+        mode,
+        SessionMode::Impromptu {
+            prep_seconds: 30,
+            speak_seconds: 120
+        }
+    );
+    assert_eq!(mode.name(), "impromptu");
+    assert_eq!(mode.params(), json!({ "prep_seconds": 30, "speak_seconds": 120 }));
+    assert_eq!(SessionMode::from_parts(mode.name(), &mode.params()).unwrap(), mode);
+    assert_eq!(SessionMode::from_parts("free", &json!({})).unwrap(), SessionMode::Free);
+    assert!(SessionMode::from_parts("karaoke", &json!({})).is_err());
+}
+
+#[test]
+fn time_limits_and_validation() {
+    assert_eq!(SessionMode::Free.time_limit_seconds(), None);
+    let qa = SessionMode::QaRound {
+        questions: 4,
+        seconds_per_question: 45,
+    };
+    assert_eq!(qa.time_limit_seconds(), Some(180));
+    assert!(qa.validate().is_ok());
+    let too_many = SessionMode::QaRound {
+        questions: 50,
+        seconds_per_question: 45,
+    };
+    assert!(too_many.validate().is_err());
+    assert!(SessionMode::TimedSpeech { speak_seconds: 5 }.validate().is_err());
+}
+
+#[test]
+fn secret_expiry_respects_deadline() {
+    let now: DateTime<Utc> = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap();
+    assert_eq!(secret_expiry(now, None), Some(now + Duration::minutes(10)));
+    let soon = now + Duration::seconds(90);
+    assert_eq!(secret_expiry(now, Some(soon)), Some(soon));
+    let later = now + Duration::hours(1);
+    assert_eq!(secret_expiry(now, Some(later)), Some(now + Duration::minutes(10)));
+    assert_eq!(secret_expiry(now, Some(now)), None);
+}
+
+async fn test_app(pool: PgPool) -> Router {
+    std::env::set_var("S3_BUCKET", "test-bucket");
+    std::env::set_var("S3_ENDPOINT", "http://localhost:9000");
+    std::env::set_var("S3_ACCESS_KEY", "test");
+    std::env::set_var("S3_SECRET_KEY", "test");
+    let storage = StorageService::from_env().await.expect("storage");
+    let state = AppState::new(pool, storage);
+    api::router(state)
+}
+
+async fn read_json(res: axum::response::Response) -> Value {
+    let bytes = body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    serde_json::from_slice(&bytes).unwrap()
+}
+
+fn request(method: Method, uri: &str, user: Uuid, body: Option<Value>) -> Request<Body> {
+    let builder = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("content-type", "application/json")
+        .header("x-user-id", user.to_string());
+    match body {
+        Some(body) => builder.body(Body::from(body.to_string())).unwrap(),
+        None => builder.body(Body::empty()).unwrap(),
+    }
+}
+
+async fn test_pool() -> PgPool {
+    dotenv().ok();
+    let url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set for tests");
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect(&url)
+        .await
+        .expect("connect db");
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+    sqlx::query("TRUNCATE client_secrets, transcripts, audio_recordings, sessions, topics RESTART IDENTITY CASCADE")
+        .execute(&pool)
+        .await
+        .unwrap();
+    pool
+}
+
+#[tokio::test]
+async fn timed_session_secrets_stop_at_the_cut_off() {
+    let pool = test_pool().await;
+    let app = test_app(pool.clone()).await;
+    let user = Uuid::new_v4();
+    let topic = Topic::create(
+        &pool,
+        &NewTopic {
+            title: format!("Elevator Pitch {}", Uuid::new_v4()),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+
+    let resp = app
+        .clone()
+        .oneshot(request(
+            Method::POST,
+            "/api/sessions",
+            user,
+            Some(json!({ "topic_id": topic.id, "mode": { "type": "timed_speech", "speak_seconds": 5 } })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    let resp = app
+        .clone()
+        .oneshot(request(
+            Method::POST,
+            "/api/sessions",
+            user,
+            Some(json!({ "topic_id": topic.id, "mode": { "type": "timed_speech", "speak_seconds": 90 } })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let session = read_json(resp).await;
+    assert_eq!(session["mode"], "timed_speech");
+    assert_eq!(session["mode_params"]["speak_seconds"], 90);
+    let session_id = Uuid::parse_str(session["id"].as_str().unwrap()).unwrap();
+
+    let resp = app
+        .clone()
+        .oneshot(request(
+            Method::POST,
+            "/api/realtime/session",
+            user,
+            Some(json!({ "session_id": session_id })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let secret = read_json(resp).await;
+    assert_eq!(secret["mode"]["type"], "timed_speech");
+    let expires_at: DateTime<Utc> = secret["expires_at"].as_str().unwrap().parse().unwrap();
+    let deadline: DateTime<Utc> = secret["deadline"].as_str().unwrap().parse().unwrap();
+    assert_eq!(expires_at, deadline);
+
+    sqlx::query("UPDATE sessions SET start_time = now() - interval '5 minutes' WHERE id = $1")
+        .bind(session_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let resp = app
+        .clone()
+        .oneshot(request(
+            Method::POST,
+            "/api/realtime/session",
+            user,
+            Some(json!({ "session_id": session_id, "force_refresh": true })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::GONE);
+
+    let resp = app
+        .clone()
+        .oneshot(request(Method::GET, "/api/sessions?mode=timed_speech", user, None))
+        .await
+        .unwrap();
+    let page = read_json(resp).await;
+    assert_eq!(page["total"], 1);
+    assert_eq!(page["sessions"][0]["mode"], "timed_speech");
+}
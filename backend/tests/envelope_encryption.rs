@@ -0,0 +1,270 @@
+use axum::body::{self, Body};
+use axum::http::{Method, Request, StatusCode};
+use axum::Router;
+use backend::api;
+use backend::encryption::{self, rewrap_data_keys, Encryption, Keyring, MasterKey};
+use backend::models::audio_recording::{AudioRecording, NewAudioRecording};
+use backend::models::data_export::DataExport;
+use backend::models::topic::{NewTopic, Topic};
+use backend::models::transcript::{
+    get_redacted_segments, get_transcript_by_session, upsert_transcript, TranscriptSegment,
+};
+use backend::services::storage::StorageService;
+use backend::state::AppState;
+use chrono::{Duration, Utc};
+use dotenvy::dotenv;
+use serde_json::{json, Value};
+use sqlx::postgres::PgPoolOptions;
+use sqlx::PgPool;
+use tower::util::ServiceExt;
+use uuid::Uuid;
+
+// Fixed keys so data keys left behind by earlier runs still unwrap.
+const FIRST_KEY: [u8; 32] = [7; 32];
+const SECOND_KEY: [u8; 32] = [9; 32];
+
+fn first() -> MasterKey {
+    MasterKey::new("test-1", &FIRST_KEY).unwrap()
+}
+
+fn second() -> MasterKey {
+    MasterKey::new("test-2", &SECOND_KEY).unwrap()
+}
+
+#[test]
+fn master_keys_are_parsed_from_config() {
+    let key = MasterKey::parse("prod-2024:AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=").unwrap();
+    assert_eq!(key.id(), "prod-2024");
+    assert!(!format!("{:?}", key).contains("AAAA"));
+    assert!(MasterKey::parse("AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=").is_err());
+    assert!(MasterKey::parse("short:AAAA").is_err());
+    assert!(MasterKey::parse("bad:not base64!").is_err());
+    assert!(MasterKey::parse(":AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=").is_err());
+    assert_eq!(encryption::encrypted_owner(b"RIFF....WAVEfmt plain audio bytes"), None);
+}
+
+async fn test_app(pool: PgPool, encryption: Encryption) -> Router {
+    std::env::set_var("S3_BUCKET", "test-bucket");
+    std::env::set_var("S3_ENDPOINT", "http://localhost:9000");
+    std::env::set_var("S3_ACCESS_KEY", "test");
+    std::env::set_var("S3_SECRET_KEY", "test");
+    let storage = StorageService::from_env().await.expect("storage").with_encryption(encryption);
+    let state = AppState::new(pool, storage);
+    api::router(state)
+}
+
+fn request(method: Method, uri: &str, user: Uuid, body: Option<Value>) -> Request<Body> {
+    let builder = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("content-type", "application/json")
+        .header("x-user-id", user.to_string());
+    match body {
+        Some(body) => builder.body(Body::from(body.to_string())).unwrap(),
+        None => builder.body(Body::empty()).unwrap(),
+    }
+}
+
+async fn test_pool() -> PgPool {
+    dotenv().ok();
+    let url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set for tests");
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect(&url)
+        .await
+        .expect("connect db");
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+    sqlx::query("TRUNCATE client_secrets, transcripts, audio_recordings, sessions, topics RESTART IDENTITY CASCADE")
+        .execute(&pool)
+        .await
+        .unwrap();
+    pool
+}
+
+async fn call(app: &Router, method: Method, uri: &str, user: Uuid, body: Option<Value>) -> (StatusCode, Value) {
+    let resp = app.clone().oneshot(request(method, uri, user, body)).await.unwrap();
+    let status = resp.status();
+    let bytes = body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    (status, serde_json::from_slice(&bytes).unwrap_or(Value::Null))
+}
+
+#[tokio::test]
+async fn audio_and_transcripts_are_sealed_with_per_user_keys() {
+    let pool = test_pool().await;
+    let enc = Encryption::new(pool.clone(), Keyring::new(first(), vec![], true));
+    let store = enc.store().unwrap();
+    let app = test_app(pool.clone(), enc.clone()).await;
+    let user = Uuid::new_v4();
+    let other = Uuid::new_v4();
+
+    let audio = b"OggS fake opus frames".to_vec();
+    let sealed = store.encrypt(user, &audio).await.unwrap();
+    assert_eq!(encryption::encrypted_owner(&sealed), Some(user));
+    assert!(!sealed.windows(4).any(|w| w == b"OggS"));
+    assert_eq!(store.decrypt(&sealed).await.unwrap(), audio);
+    assert_ne!(store.encrypt(user, &audio).await.unwrap(), sealed);
+    // Swapping in another user's id breaks the authenticated header.
+    store.encrypt(other, b"warm up").await.unwrap();
+    let mut forged = sealed.clone();
+    forged[8..24].copy_from_slice(other.as_bytes());
+    assert!(store.decrypt(&forged).await.is_err());
+
+    let topic = Topic::create(
+        &pool,
+        &NewTopic {
+            title: format!("Encryption Topic {}", Uuid::new_v4()),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    let (status, session) = call(
+        &app,
+        Method::POST,
+        "/api/sessions",
+        user,
+        Some(json!({ "topic_id": topic.id })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let session_id: Uuid = session["id"].as_str().unwrap().parse().unwrap();
+    upsert_transcript(
+        &pool,
+        &enc,
+        session_id,
+        true,
+        &[TranscriptSegment {
+            speaker: "user".into(),
+            text: "My secret plan for Lisbon".into(),
+            start_ms: 0,
+            end_ms: 1500,
+            words: None,
+        }],
+    )
+    .await
+    .unwrap();
+
+    let (stored, original): (Value, Value) =
+        sqlx::query_as("SELECT segments, original_segments FROM transcripts WHERE session_id = $1")
+            .bind(session_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert!(stored["encrypted"].is_string());
+    assert!(original["encrypted"].is_string());
+    assert!(!stored.to_string().contains("Lisbon"));
+    let transcript = get_transcript_by_session(&pool, &enc, session_id).await.unwrap().unwrap();
+    assert_eq!(transcript.segments[0]["text"], "My secret plan for Lisbon");
+
+    let (status, detail) = call(&app, Method::GET, &format!("/api/sessions/{session_id}"), user, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(detail["session"]["transcript"][0]["text"], "My secret plan for Lisbon");
+    // Sealed text can't be matched, so search reports itself unavailable instead of empty.
+    let (status, found) = call(&app, Method::GET, "/api/sessions/search?q=lisbon", user, None).await;
+    assert_eq!(status, StatusCode::NOT_IMPLEMENTED);
+    assert_eq!(found["type"], "/problems/not-implemented");
+
+    let (status, edited) = call(
+        &app,
+        Method::PATCH,
+        &format!("/api/sessions/{session_id}/transcript"),
+        user,
+        Some(json!({ "base_revision": 0, "edits": [{ "index": 0, "text": "My plan for Porto" }] })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(edited["segments"][0]["text"], "My plan for Porto");
+    let revision: Value = sqlx::query_scalar(
+        "SELECT r.segments FROM transcript_revisions r JOIN transcripts t ON t.id = r.transcript_id WHERE t.session_id = $1",
+    )
+    .bind(session_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert!(revision["encrypted"].is_string());
+    let (_, diff) = call(
+        &app,
+        Method::GET,
+        &format!("/api/sessions/{session_id}/transcript/diff?from=0"),
+        user,
+        None,
+    )
+    .await;
+    assert_eq!(diff["changes"][0]["before"]["text"], "My secret plan for Lisbon");
+    let redacted: Value = sqlx::query_scalar("SELECT redacted_segments FROM transcripts WHERE session_id = $1")
+        .bind(session_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert!(redacted["encrypted"].is_string());
+    assert!(!redacted.to_string().contains("Porto"));
+    let redacted = get_redacted_segments(&pool, &enc, session_id).await.unwrap().unwrap();
+    assert_eq!(redacted[0].text, "My plan for Porto");
+
+    // Owners get the decrypting audio route, never the bucket URL of the ciphertext.
+    AudioRecording::insert(
+        &pool,
+        NewAudioRecording {
+            session_id,
+            storage_url: format!("http://localhost:9000/test-bucket/sessions/{session_id}/take.webm"),
+            duration_seconds: Some(2),
+            mime_type: Some("audio/webm".into()),
+            size_bytes: None,
+            quality_status: None,
+        },
+    )
+    .await
+    .unwrap();
+    let audio_route = format!("/api/sessions/{session_id}/audio");
+    let (_, detail) = call(&app, Method::GET, &format!("/api/sessions/{session_id}"), user, None).await;
+    assert_eq!(detail["session"]["audio_url"], audio_route.as_str());
+    let (_, list) = call(&app, Method::GET, "/api/sessions", user, None).await;
+    assert_eq!(list["sessions"][0]["audio_url"], audio_route.as_str());
+    let (status, _) = call(&app, Method::GET, &audio_route, other, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    // Finalize only ever transcribes the session's own recording, never a URL the client names.
+    let (status, _) = call(
+        &app,
+        Method::POST,
+        &format!("/api/sessions/{session_id}/finalize"),
+        user,
+        Some(json!({
+            "transcript": [],
+            "status": "completed",
+            "audio_url": format!("http://localhost:9000/test-bucket/sessions/{}/take.webm", Uuid::new_v4()),
+        })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+    // Sealed export archives are downloaded through the decrypting route.
+    let export = DataExport::create(&pool, user).await.unwrap();
+    assert!(DataExport::mark_running(&pool, export.id).await.unwrap());
+    let mut tx = pool.begin().await.unwrap();
+    DataExport::mark_ready(&mut tx, export.id, &format!("exports/{user}/{}.zip", export.id), 10, Utc::now() + Duration::hours(1))
+        .await
+        .unwrap();
+    tx.commit().await.unwrap();
+    let (status, export_status) = call(&app, Method::GET, &format!("/api/me/export/{}", export.id), user, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(export_status["download_url"], format!("/api/me/export/{}/download", export.id).as_str());
+    let (status, _) = call(&app, Method::GET, &format!("/api/me/export/{}/download", export.id), other, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    // Rotating onto a new master key and back leaves every data key usable.
+    let rotated = Keyring::new(second(), vec![first()], true);
+    assert!(rewrap_data_keys(&pool, &rotated).await.unwrap() >= 2);
+    let master: String = sqlx::query_scalar("SELECT master_key_id FROM user_data_keys WHERE user_id = $1")
+        .bind(user)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(master, "test-2");
+    assert!(rewrap_data_keys(&pool, &Keyring::new(first(), vec![], true)).await.is_err());
+    let restored = Keyring::new(first(), vec![second()], true);
+    assert!(rewrap_data_keys(&pool, &restored).await.unwrap() >= 2);
+    assert_eq!(rewrap_data_keys(&pool, &restored).await.unwrap(), 0);
+    assert_eq!(store.decrypt(&sealed).await.unwrap(), audio);
+    let transcript = get_transcript_by_session(&pool, &enc, session_id).await.unwrap().unwrap();
+    assert_eq!(transcript.segments[0]["text"], "My plan for Porto");
+}
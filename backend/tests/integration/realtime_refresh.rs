@@ -28,6 +28,7 @@ async fn insert_topic(pool: &PgPool) -> Uuid {
         title: format!("Realtime Refresh {}", Uuid::new_v4()),
         difficulty: None,
         prompt_hint: None,
+        ..Default::default()
     };
     sqlx::query("INSERT INTO topics (title) VALUES ($1) RETURNING id")
         .bind(&topic.title)
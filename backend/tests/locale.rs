@@ -0,0 +1,25 @@
+use backend::services::locale::{from_accept_language, negotiate, normalize_locale, DEFAULT_LOCALE};
+
+#[test]
+fn normalize_locale_accepts_region_tags() {
+    assert_eq!(normalize_locale("ja-JP"), Some("ja"));
+    assert_eq!(normalize_locale(" ES "), Some("es"));
+    assert_eq!(normalize_locale("en_GB"), Some("en"));
+    assert_eq!(normalize_locale("fr"), None);
+    assert_eq!(normalize_locale(""), None);
+}
+
+#[test]
+fn accept_language_honours_quality_values() {
+    assert_eq!(from_accept_language("fr-FR, es;q=0.4, ja;q=0.8"), Some("ja"));
+    assert_eq!(from_accept_language("es, ja"), Some("es"));
+    assert_eq!(from_accept_language("ja;q=0, en;q=0.1"), Some("en"));
+    assert_eq!(from_accept_language("de, fr"), None);
+}
+
+#[test]
+fn explicit_locale_wins_over_header() {
+    assert_eq!(negotiate(Some("es"), Some("ja")), "es");
+    assert_eq!(negotiate(Some("xx"), Some("ja")), "ja");
+    assert_eq!(negotiate(None, None), DEFAULT_LOCALE);
+}
@@ -0,0 +1,324 @@
+use axum::body::{self, Body};
+use axum::http::{Method, Request, StatusCode};
+use axum::Router;
+use backend::api;
+use backend::encryption::Encryption;
+use backend::models::transcript::{upsert_transcript, TranscriptSegment};
+use backend::services::organizations::{normalize_name, validate_role};
+use backend::services::storage::StorageService;
+use backend::state::AppState;
+use dotenvy::dotenv;
+use serde_json::{json, Value};
+use sqlx::postgres::PgPoolOptions;
+use sqlx::PgPool;
+use tower::util::ServiceExt;
+use uuid::Uuid;
+
+#[test]
+fn names_and_roles_are_validated() {
+    assert_eq!(normalize_name("  Lincoln High ").unwrap(), "Lincoln High");
+    assert!(normalize_name("   ").is_err());
+    assert!(normalize_name(&"x".repeat(101)).is_err());
+    assert_eq!(validate_role("admin").unwrap(), "admin");
+    assert_eq!(validate_role("member").unwrap(), "member");
+    assert!(validate_role("owner").is_err());
+}
+
+async fn test_app(pool: PgPool) -> Router {
+    std::env::set_var("S3_BUCKET", "test-bucket");
+    std::env::set_var("S3_ENDPOINT", "http://localhost:9000");
+    std::env::set_var("S3_ACCESS_KEY", "test");
+    std::env::set_var("S3_SECRET_KEY", "test");
+    let storage = StorageService::from_env().await.expect("storage");
+    let state = AppState::new(pool, storage);
+    api::router(state)
+}
+
+fn request(method: Method, uri: &str, user: Uuid, body: Option<Value>) -> Request<Body> {
+    let builder = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("content-type", "application/json")
+        .header("x-user-id", user.to_string());
+    match body {
+        Some(body) => builder.body(Body::from(body.to_string())).unwrap(),
+        None => builder.body(Body::empty()).unwrap(),
+    }
+}
+
+async fn test_pool() -> PgPool {
+    dotenv().ok();
+    let url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set for tests");
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect(&url)
+        .await
+        .expect("connect db");
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+    sqlx::query("TRUNCATE client_secrets, transcripts, audio_recordings, sessions, topics RESTART IDENTITY CASCADE")
+        .execute(&pool)
+        .await
+        .unwrap();
+    pool
+}
+
+async fn call(app: &Router, method: Method, uri: &str, user: Uuid, body: Option<Value>) -> (StatusCode, Value) {
+    let resp = app.clone().oneshot(request(method, uri, user, body)).await.unwrap();
+    let status = resp.status();
+    let bytes = body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    (status, serde_json::from_slice(&bytes).unwrap_or(Value::Null))
+}
+
+#[tokio::test]
+async fn organizations_isolate_tenants_and_report_cohort_aggregates() {
+    let pool = test_pool().await;
+    let app = test_app(pool.clone()).await;
+    let owner = Uuid::new_v4();
+    let student = Uuid::new_v4();
+    let classmate = Uuid::new_v4();
+    let rival = Uuid::new_v4();
+
+    let (status, org) = call(
+        &app,
+        Method::POST,
+        "/api/orgs",
+        owner,
+        Some(json!({ "name": format!("Lincoln High {}", Uuid::new_v4()) })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let org_id = org["id"].as_str().unwrap().to_string();
+    let (status, _) = call(
+        &app,
+        Method::POST,
+        "/api/orgs",
+        owner,
+        Some(json!({ "name": format!("Second {}", Uuid::new_v4()) })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    let (status, other_org) = call(
+        &app,
+        Method::POST,
+        "/api/orgs",
+        rival,
+        Some(json!({ "name": format!("Rival Academy {}", Uuid::new_v4()) })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    // Admins can only invite; nobody joins until they accept.
+    for user in [student, classmate] {
+        let (status, invitation) = call(
+            &app,
+            Method::POST,
+            &format!("/api/orgs/{org_id}/invitations"),
+            owner,
+            Some(json!({ "user_id": user })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(invitation["status"], "pending");
+        let (status, _) = call(&app, Method::GET, "/api/orgs/me", user, None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (_, pending) = call(&app, Method::GET, "/api/orgs/invitations", user, None).await;
+        assert_eq!(pending[0]["id"], invitation["id"]);
+        let accept_uri = format!("/api/orgs/invitations/{}/accept", invitation["id"].as_str().unwrap());
+        let (status, _) = call(&app, Method::POST, &accept_uri, Uuid::new_v4(), None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, member) = call(&app, Method::POST, &accept_uri, user, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(member["role"], "member");
+    }
+    // Users already in another organization keep it until they leave.
+    let (status, invitation) = call(
+        &app,
+        Method::POST,
+        &format!("/api/orgs/{org_id}/invitations"),
+        owner,
+        Some(json!({ "user_id": rival })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let (status, _) = call(
+        &app,
+        Method::POST,
+        &format!("/api/orgs/invitations/{}/accept", invitation["id"].as_str().unwrap()),
+        rival,
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    let (status, _) = call(
+        &app,
+        Method::POST,
+        &format!("/api/orgs/invitations/{}/decline", invitation["id"].as_str().unwrap()),
+        rival,
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (_, mine) = call(&app, Method::GET, "/api/orgs/me", student, None).await;
+    assert_eq!(mine["organization"]["id"], org["id"]);
+    assert_eq!(mine["role"], "member");
+
+    // Plain members cannot administer; other tenants cannot even see the organization.
+    let members_uri = format!("/api/orgs/{org_id}/members");
+    let (status, _) = call(&app, Method::GET, &members_uri, student, None).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = call(&app, Method::GET, &members_uri, rival, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, members) = call(&app, Method::GET, &members_uri, owner, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(members.as_array().unwrap().len(), 3);
+
+    let (status, cohort) = call(
+        &app,
+        Method::POST,
+        &format!("/api/orgs/{org_id}/cohorts"),
+        owner,
+        Some(json!({ "name": "Period 3" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let cohort_id = cohort["id"].as_str().unwrap().to_string();
+    let (status, _) = call(
+        &app,
+        Method::PUT,
+        &format!("/api/orgs/{org_id}/cohorts/{cohort_id}/members/{student}"),
+        owner,
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (status, _) = call(
+        &app,
+        Method::PUT,
+        &format!("/api/orgs/{org_id}/cohorts/{cohort_id}/members/{rival}"),
+        owner,
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+    // Org catalog topics are visible to members only.
+    let (status, topic) = call(
+        &app,
+        Method::POST,
+        &format!("/api/orgs/{org_id}/topics"),
+        owner,
+        Some(json!({ "title": format!("School Board Debate {}", Uuid::new_v4()) })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let topic_id = topic["id"].as_str().unwrap().to_string();
+    let (_, visible) = call(&app, Method::GET, "/api/topics", student, None).await;
+    assert!(visible.as_array().unwrap().iter().any(|t| t["id"] == topic["id"]));
+    let (_, visible) = call(&app, Method::GET, "/api/topics", rival, None).await;
+    assert!(!visible.as_array().unwrap().iter().any(|t| t["id"] == topic["id"]));
+    let (status, _) = call(
+        &app,
+        Method::POST,
+        "/api/sessions",
+        rival,
+        Some(json!({ "topic_id": topic_id })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let mut session_ids = Vec::new();
+    for (user, duration) in [(student, 120), (student, 60), (classmate, 300)] {
+        let (status, session) = call(
+            &app,
+            Method::POST,
+            "/api/sessions",
+            user,
+            Some(json!({ "topic_id": topic_id })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        let id: Uuid = session["id"].as_str().unwrap().parse().unwrap();
+        sqlx::query("UPDATE sessions SET duration_seconds = $2 WHERE id = $1")
+            .bind(id)
+            .bind(duration)
+            .execute(&pool)
+            .await
+            .unwrap();
+        session_ids.push(id);
+    }
+
+    // Only the cohort's members count, and only aggregates come back.
+    let stats_uri = format!("/api/orgs/{org_id}/cohorts/{cohort_id}/stats");
+    let (status, _) = call(&app, Method::GET, &stats_uri, student, None).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let rival_uri = format!(
+        "/api/orgs/{}/cohorts/{cohort_id}/stats",
+        other_org["id"].as_str().unwrap()
+    );
+    let (status, _) = call(&app, Method::GET, &rival_uri, rival, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, stats) = call(&app, Method::GET, &stats_uri, owner, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(stats["member_count"], 1);
+    assert_eq!(stats["active_members"], 1);
+    assert_eq!(stats["session_count"], 2);
+    assert_eq!(stats["total_duration_seconds"], 180);
+    assert_eq!(stats["average_duration_seconds"], 90.0);
+    assert_eq!(stats["sessions_by_mode"][0]["sessions"], 2);
+    assert!(stats.get("sessions").is_none());
+
+    upsert_transcript(
+        &pool,
+        &Encryption::disabled(),
+        session_ids[0],
+        true,
+        &[TranscriptSegment {
+            speaker: "user".into(),
+            text: "The budget should fund the library".into(),
+            start_ms: 0,
+            end_ms: 2000,
+            words: None,
+        }],
+    )
+    .await
+    .unwrap();
+
+    // Leaving the organization takes the student out of its stats but not out of their own history.
+    let (_, history) = call(&app, Method::GET, "/api/sessions", student, None).await;
+    assert_eq!(history["total"], 2);
+    let (status, _) = call(
+        &app,
+        Method::DELETE,
+        &format!("/api/orgs/{org_id}/members/{student}"),
+        owner,
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (_, history) = call(&app, Method::GET, "/api/sessions", student, None).await;
+    assert_eq!(history["total"], 2);
+    let (status, _) = call(
+        &app,
+        Method::GET,
+        &format!("/api/sessions/{}", session_ids[0]),
+        student,
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, found) = call(&app, Method::GET, "/api/sessions/search?q=library", student, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(found["results"][0]["session_id"], session_ids[0].to_string());
+    let (_, stats) = call(&app, Method::GET, &stats_uri, owner, None).await;
+    assert_eq!(stats["member_count"], 0);
+
+    let (status, _) = call(
+        &app,
+        Method::DELETE,
+        &format!("/api/orgs/{org_id}/members/{owner}"),
+        owner,
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
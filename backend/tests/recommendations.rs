@@ -0,0 +1,171 @@
+use axum::body::{self, Body};
+use axum::http::{Method, Request, StatusCode};
+use axum::Router;
+use backend::api;
+use backend::models::topic::{NewTopic, Topic};
+use backend::services::recommendations::{
+    rank_topics, session_outcome, target_difficulty, Outcome, PastSession,
+};
+use backend::services::storage::StorageService;
+use backend::state::AppState;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use dotenvy::dotenv;
+use serde_json::Value;
+use sqlx::postgres::PgPoolOptions;
+use sqlx::PgPool;
+use tower::util::ServiceExt;
+use uuid::Uuid;
+
+fn now() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap()
+}
+
+fn topic(title: &str, difficulty: &str) -> Topic {
+    Topic {
+        id: Uuid::new_v4(),
+        title: title.into(),
+        difficulty: Some(difficulty.into()),
+        prompt_hint: None,
+        category: None,
+        tags: vec![],
+        target_skill: None,
+        estimated_duration_seconds: Some(120),
+        owner_id: None,
+        org_id: None,
+        archived_at: None,
+        created_at: now(),
+        updated_at: now(),
+    }
+}
+
+fn session(topic: &Topic, status: &str, duration: i32, days_ago: i64) -> PastSession {
+    PastSession {
+        topic_id: topic.id,
+        status: status.into(),
+        duration_seconds: Some(duration),
+        start_time: now() - Duration::days(days_ago),
+    }
+}
+
+#[test]
+fn outcome_uses_status_and_estimated_duration() {
+    let t = topic("Cafe", "easy");
+    assert_eq!(session_outcome(&session(&t, "ended", 60, 0), Some(120)), Some(Outcome::Success));
+    assert_eq!(session_outcome(&session(&t, "ended", 59, 0), Some(120)), Some(Outcome::Struggle));
+    assert_eq!(session_outcome(&session(&t, "failed", 600, 0), None), Some(Outcome::Struggle));
+    assert_eq!(session_outcome(&session(&t, "active", 600, 0), None), None);
+}
+
+#[test]
+fn difficulty_follows_recent_performance() {
+    let easy = topic("Cafe", "easy");
+    let medium = topic("Budget", "medium");
+    assert_eq!(target_difficulty(std::slice::from_ref(&easy), &[]), "easy");
+
+    let strong: Vec<PastSession> = (0..5).map(|d| session(&easy, "ended", 120, d)).collect();
+    assert_eq!(target_difficulty(std::slice::from_ref(&easy), &strong), "medium");
+
+    let weak: Vec<PastSession> = (0..4).map(|d| session(&medium, "failed", 10, d)).collect();
+    assert_eq!(target_difficulty(&[medium], &weak), "easy");
+}
+
+#[test]
+fn ranking_prefers_due_reviews_and_new_topics_deterministically() {
+    let cafe = topic("Cafe", "easy");
+    let market = topic("Market", "easy");
+    let airport = topic("Airport", "easy");
+    let debate = topic("Debate", "hard");
+    let topics = vec![cafe.clone(), market.clone(), airport.clone(), debate.clone()];
+    let sessions = vec![
+        // Newest first: cafe struggled 2 days ago (due after 1 day), market went well yesterday.
+        session(&market, "ended", 120, 1),
+        session(&cafe, "ended", 10, 2),
+        session(&market, "ended", 10, 3),
+    ];
+
+    let ranked = rank_topics(&topics, &sessions, now(), 10);
+    let titles: Vec<&str> = ranked.iter().map(|r| r.topic.title.as_str()).collect();
+    assert_eq!(titles, vec!["Cafe", "Airport", "Market", "Debate"]);
+    assert!(ranked[0].reason.starts_with("Due for review"));
+    assert!(ranked[1].reason.starts_with("New"));
+
+    let again = rank_topics(&topics, &sessions, now(), 2);
+    assert_eq!(again.len(), 2);
+    assert_eq!(again[0].topic.id, ranked[0].topic.id);
+    assert_eq!(again[1].topic.id, ranked[1].topic.id);
+}
+
+#[test]
+fn struggled_topic_waits_for_its_review_interval() {
+    let cafe = topic("Cafe", "easy");
+    let airport = topic("Airport", "easy");
+    let sessions = vec![
+        session(&cafe, "failed", 10, 2),
+        session(&cafe, "failed", 10, 4),
+    ];
+    let ranked = rank_topics(&[cafe, airport], &sessions, now(), 10);
+    assert_eq!(ranked[0].topic.title, "Airport");
+    assert_eq!(ranked[1].reason, "Review scheduled in 3 day(s)");
+}
+
+async fn test_app(pool: PgPool) -> Router {
+    std::env::set_var("S3_BUCKET", "test-bucket");
+    std::env::set_var("S3_ENDPOINT", "http://localhost:9000");
+    std::env::set_var("S3_ACCESS_KEY", "test");
+    std::env::set_var("S3_SECRET_KEY", "test");
+    let storage = StorageService::from_env().await.expect("storage");
+    let state = AppState::new(pool, storage);
+    api::router(state)
+}
+
+async fn test_pool() -> PgPool {
+    dotenv().ok();
+    let url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set for tests");
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect(&url)
+        .await
+        .expect("connect db");
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+    sqlx::query("TRUNCATE client_secrets, transcripts, audio_recordings, sessions, topics RESTART IDENTITY CASCADE")
+        .execute(&pool)
+        .await
+        .unwrap();
+    pool
+}
+
+#[tokio::test]
+async fn recommended_endpoint_returns_reasons() {
+    let pool = test_pool().await;
+    let app = test_app(pool.clone()).await;
+    let user = Uuid::new_v4();
+    let title = format!("Small Talk {}", Uuid::new_v4());
+    Topic::create(
+        &pool,
+        &NewTopic {
+            title: title.clone(),
+            difficulty: Some("easy".into()),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+
+    let req = Request::builder()
+        .method(Method::GET)
+        .uri("/api/topics/recommended?limit=5")
+        .header("x-user-id", user.to_string())
+        .body(Body::empty())
+        .unwrap();
+    let resp = app.oneshot(req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let bytes = body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    let recommendations: Value = serde_json::from_slice(&bytes).unwrap();
+    let entry = recommendations
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|r| r["topic"]["title"] == title.as_str())
+        .expect("topic recommended");
+    assert_eq!(entry["reason"], "New easy topic matched to your level");
+}
@@ -0,0 +1,245 @@
+use axum::body::{self, Body};
+use axum::http::{Method, Request, StatusCode};
+use axum::Router;
+use backend::api;
+use backend::encryption::Encryption;
+use backend::models::topic::{NewTopic, Topic};
+use backend::models::transcript::{get_redacted_segments, upsert_transcript, TranscriptSegment};
+use backend::services::redaction::{self, passes_luhn, Redactor, BUILTIN_DETECTORS};
+use backend::services::storage::StorageService;
+use backend::state::AppState;
+use backend::telemetry::RedactingFields;
+use dotenvy::dotenv;
+use serde_json::{json, Value};
+use sqlx::postgres::PgPoolOptions;
+use sqlx::PgPool;
+use std::collections::BTreeMap;
+use std::io;
+use std::sync::{Arc, Mutex};
+use tower::util::ServiceExt;
+use uuid::Uuid;
+
+const SPOKEN: &str = "Email me at jane.doe@example.com or call +1 415 555 0132.";
+
+#[test]
+fn detectors_replace_pii_with_labels() {
+    let redactor = Redactor::new(BUILTIN_DETECTORS, &BTreeMap::new()).unwrap();
+    let out = redactor.redact(SPOKEN);
+    assert_eq!(out.text, "Email me at [EMAIL] or call [PHONE].");
+    assert_eq!(out.counts["EMAIL"], 1);
+    assert_eq!(out.counts["PHONE"], 1);
+
+    let out = redactor.redact("My card is 4111 1111 1111 1111 and I live at 221 Baker Street, London.");
+    assert_eq!(out.text, "My card is [CREDIT_CARD] and I live at [ADDRESS], London.");
+    assert!(passes_luhn("4111-1111-1111-1111"));
+    assert!(!passes_luhn("4111 1111 1111 1112"));
+    assert!(!passes_luhn("4111"));
+
+    let plain = "I practiced my toast for 20 minutes.";
+    assert_eq!(redactor.redact(plain).text, plain);
+    for number in ["(415) 555-0132", "+14155550132", "+44 (20) 7946 0958", "415.555.0132"] {
+        assert_eq!(redactor.redact(number).text, "[PHONE]", "{number}");
+    }
+    let counts = "uploaded 104857600 bytes in 1234567890us for order 4155550132";
+    assert_eq!(redactor.redact(counts).text, counts);
+
+    let custom = BTreeMap::from([("employee_id".to_string(), r"\bEMP-\d{5}\b".to_string())]);
+    let redactor = Redactor::new(&["email"], &custom).unwrap();
+    let out = redactor.redact("EMP-12345 wrote from jane@example.com, call 415 555 0132");
+    assert_eq!(out.text, "[EMPLOYEE_ID] wrote from [EMAIL], call 415 555 0132");
+    assert!(Redactor::new(&["passport"], &BTreeMap::new()).is_err());
+}
+
+#[derive(Clone, Default)]
+struct Capture(Arc<Mutex<Vec<u8>>>);
+
+impl io::Write for Capture {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn log_fields_are_scrubbed() {
+    let capture = Capture::default();
+    let writer = capture.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_ansi(false)
+        .with_writer(move || writer.clone())
+        .fmt_fields(RedactingFields::default())
+        .finish();
+    tracing::subscriber::with_default(subscriber, || {
+        tracing::error!(reason = SPOKEN, "lookup failed for jane.doe@example.com");
+    });
+    let logged = String::from_utf8(capture.0.lock().unwrap().clone()).unwrap();
+    assert!(logged.contains("lookup failed for [EMAIL]"));
+    assert!(logged.contains("call [PHONE]"));
+    assert!(!logged.contains("example.com"));
+    assert!(!logged.contains("555"));
+}
+
+#[test]
+fn plain_numeric_log_fields_are_kept() {
+    let capture = Capture::default();
+    let writer = capture.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_ansi(false)
+        .with_writer(move || writer.clone())
+        .fmt_fields(RedactingFields::default())
+        .finish();
+    tracing::subscriber::with_default(subscriber, || {
+        tracing::info!(size_bytes = 104857600u64, elapsed_us = 1234567890u64, "upload finished");
+    });
+    let logged = String::from_utf8(capture.0.lock().unwrap().clone()).unwrap();
+    assert!(logged.contains("size_bytes=104857600"));
+    assert!(logged.contains("elapsed_us=1234567890"));
+    assert!(!logged.contains("[PHONE]"));
+}
+
+async fn test_app(pool: PgPool) -> Router {
+    std::env::set_var("S3_BUCKET", "test-bucket");
+    std::env::set_var("S3_ENDPOINT", "http://localhost:9000");
+    std::env::set_var("S3_ACCESS_KEY", "test");
+    std::env::set_var("S3_SECRET_KEY", "test");
+    let storage = StorageService::from_env().await.expect("storage");
+    let state = AppState::new(pool, storage);
+    api::router(state)
+}
+
+fn request(method: Method, uri: &str, user: Option<Uuid>, body: Option<Value>) -> Request<Body> {
+    let mut builder = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("content-type", "application/json");
+    if let Some(user) = user {
+        builder = builder.header("x-user-id", user.to_string());
+    }
+    match body {
+        Some(body) => builder.body(Body::from(body.to_string())).unwrap(),
+        None => builder.body(Body::empty()).unwrap(),
+    }
+}
+
+async fn test_pool() -> PgPool {
+    dotenv().ok();
+    let url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set for tests");
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect(&url)
+        .await
+        .expect("connect db");
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+    sqlx::query("TRUNCATE client_secrets, transcripts, audio_recordings, sessions, topics RESTART IDENTITY CASCADE")
+        .execute(&pool)
+        .await
+        .unwrap();
+    pool
+}
+
+async fn call(app: &Router, method: Method, uri: &str, user: Option<Uuid>, body: Option<Value>) -> (StatusCode, Value) {
+    let resp = app.clone().oneshot(request(method, uri, user, body)).await.unwrap();
+    let status = resp.status();
+    let bytes = body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    (status, serde_json::from_slice(&bytes).unwrap_or(Value::Null))
+}
+
+#[tokio::test]
+async fn shares_see_the_redacted_view_while_the_owner_keeps_the_original() {
+    let pool = test_pool().await;
+    let app = test_app(pool.clone()).await;
+    let user = Uuid::new_v4();
+    let topic = Topic::create(
+        &pool,
+        &NewTopic {
+            title: format!("Redaction Topic {}", Uuid::new_v4()),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    let (status, session) = call(
+        &app,
+        Method::POST,
+        "/api/sessions",
+        Some(user),
+        Some(json!({ "topic_id": topic.id })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let session_id: Uuid = session["id"].as_str().unwrap().parse().unwrap();
+    upsert_transcript(
+        &pool,
+        &Encryption::disabled(),
+        session_id,
+        true,
+        &[TranscriptSegment {
+            speaker: "user".into(),
+            text: SPOKEN.into(),
+            start_ms: 0,
+            end_ms: 4000,
+            words: None,
+        }],
+    )
+    .await
+    .unwrap();
+    assert!(get_redacted_segments(&pool, &Encryption::disabled(), session_id).await.unwrap().is_none());
+    redaction::store_redacted_view(&pool, &Encryption::disabled(), session_id).await.unwrap();
+    let stored = get_redacted_segments(&pool, &Encryption::disabled(), session_id).await.unwrap().unwrap();
+    assert_eq!(stored[0].text, "Email me at [EMAIL] or call [PHONE].");
+
+    let (status, share) = call(
+        &app,
+        Method::POST,
+        &format!("/api/sessions/{session_id}/shares"),
+        Some(user),
+        Some(json!({})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let (status, view) = call(
+        &app,
+        Method::GET,
+        &format!("/api/shared/{}", share["token"].as_str().unwrap()),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(view["transcript"][0]["text"], "Email me at [EMAIL] or call [PHONE].");
+
+    let (status, detail) = call(&app, Method::GET, &format!("/api/sessions/{session_id}"), Some(user), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(detail["session"]["transcript"][0]["text"], SPOKEN);
+
+    // Re-transcribing drops the stale view rather than serving it for the new text.
+    upsert_transcript(
+        &pool,
+        &Encryption::disabled(),
+        session_id,
+        true,
+        &[TranscriptSegment {
+            speaker: "user".into(),
+            text: "Write to sam@example.org instead.".into(),
+            start_ms: 0,
+            end_ms: 2000,
+            words: None,
+        }],
+    )
+    .await
+    .unwrap();
+    assert!(get_redacted_segments(&pool, &Encryption::disabled(), session_id).await.unwrap().is_none());
+    let (_, view) = call(
+        &app,
+        Method::GET,
+        &format!("/api/shared/{}", share["token"].as_str().unwrap()),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(view["transcript"][0]["text"], "Write to [EMAIL] instead.");
+}
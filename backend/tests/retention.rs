@@ -0,0 +1,339 @@
+use axum::body::{self, Body};
+use axum::http::{Method, Request, StatusCode};
+use axum::Router;
+use backend::api;
+use backend::encryption::Encryption;
+use backend::models::audio_recording::{AudioRecording, NewAudioRecording};
+use backend::models::topic::{NewTopic, Topic};
+use backend::models::transcript::{get_transcript_by_session, upsert_transcript, TranscriptSegment};
+use backend::services::retention::{enforce, report, RetentionRule};
+use backend::services::storage::StorageService;
+use backend::state::AppState;
+use chrono::{Duration, Utc};
+use dotenvy::dotenv;
+use serde_json::{json, Value};
+use sqlx::postgres::PgPoolOptions;
+use sqlx::PgPool;
+use tower::util::ServiceExt;
+use uuid::Uuid;
+
+#[test]
+fn overrides_only_shorten_the_deployment_default() {
+    let defaults = RetentionRule {
+        audio_days: Some(90),
+        transcript_days: None,
+        metadata_days: Some(365),
+    };
+    let user = RetentionRule {
+        audio_days: Some(30),
+        transcript_days: Some(60),
+        metadata_days: None,
+    };
+    assert_eq!(
+        defaults.effective(Some(user)),
+        RetentionRule {
+            audio_days: Some(30),
+            transcript_days: Some(60),
+            metadata_days: Some(365),
+        }
+    );
+    assert_eq!(defaults.effective(None), defaults);
+    assert!(user.validate_override(&defaults).is_ok());
+    let longer = RetentionRule {
+        audio_days: Some(120),
+        ..Default::default()
+    };
+    assert!(longer.validate_override(&defaults).is_err());
+    let zero = RetentionRule {
+        transcript_days: Some(0),
+        ..Default::default()
+    };
+    assert!(zero.validate_override(&defaults).is_err());
+}
+
+async fn storage() -> StorageService {
+    std::env::set_var("S3_BUCKET", "test-bucket");
+    std::env::set_var("S3_ENDPOINT", "http://localhost:9000");
+    std::env::set_var("S3_ACCESS_KEY", "test");
+    std::env::set_var("S3_SECRET_KEY", "test");
+    StorageService::from_env().await.expect("storage")
+}
+
+async fn test_app(pool: PgPool) -> Router {
+    let state = AppState::new(pool, storage().await);
+    api::router(state)
+}
+
+async fn read_json(res: axum::response::Response) -> Value {
+    let bytes = body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    serde_json::from_slice(&bytes).unwrap()
+}
+
+fn request(method: Method, uri: &str, user: Uuid, body: Option<Value>) -> Request<Body> {
+    let builder = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("content-type", "application/json")
+        .header("x-user-id", user.to_string());
+    match body {
+        Some(body) => builder.body(Body::from(body.to_string())).unwrap(),
+        None => builder.body(Body::empty()).unwrap(),
+    }
+}
+
+async fn test_pool() -> PgPool {
+    dotenv().ok();
+    let url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set for tests");
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect(&url)
+        .await
+        .expect("connect db");
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+    sqlx::query("TRUNCATE client_secrets, transcripts, audio_recordings, sessions, topics RESTART IDENTITY CASCADE")
+        .execute(&pool)
+        .await
+        .unwrap();
+    pool
+}
+
+async fn session_with_audio(app: &Router, pool: &PgPool, user: Uuid, topic_id: Uuid) -> Uuid {
+    let resp = app
+        .clone()
+        .oneshot(request(
+            Method::POST,
+            "/api/sessions",
+            user,
+            Some(json!({ "topic_id": topic_id })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let id: Uuid = read_json(resp).await["id"]
+        .as_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    AudioRecording::insert(
+        pool,
+        NewAudioRecording {
+            session_id: id,
+            storage_url: format!("http://localhost:9000/test-bucket/sessions/{id}/audio.webm"),
+            duration_seconds: Some(5),
+            mime_type: Some("audio/webm".into()),
+            size_bytes: None,
+            quality_status: None,
+        },
+    )
+    .await
+    .unwrap();
+    id
+}
+
+async fn backdate(pool: &PgPool, session_id: Uuid, days: i64) {
+    sqlx::query("UPDATE sessions SET start_time = $2 WHERE id = $1")
+        .bind(session_id)
+        .bind(Utc::now() - Duration::days(days))
+        .execute(pool)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn retention_reports_then_purges_expired_data() {
+    let pool = test_pool().await;
+    let app = test_app(pool.clone()).await;
+    let user = Uuid::new_v4();
+    let topic = Topic::create(
+        &pool,
+        &NewTopic {
+            title: format!("Quarterly Review {}", Uuid::new_v4()),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+
+    let resp = app
+        .clone()
+        .oneshot(request(
+            Method::PUT,
+            "/api/me/retention",
+            user,
+            Some(json!({ "audio_days": 7, "transcript_days": 30, "metadata_days": 0 })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let resp = app
+        .clone()
+        .oneshot(request(
+            Method::PUT,
+            "/api/me/retention",
+            user,
+            Some(json!({ "audio_days": 7, "transcript_days": 30, "metadata_days": 90 })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = read_json(resp).await;
+    assert_eq!(body["effective"]["audio_days"], 7);
+
+    // 10 days old: audio expired. 40 days: audio and transcript. 100 days: everything.
+    let mut sessions = Vec::new();
+    for days in [10, 40, 100] {
+        let id = session_with_audio(&app, &pool, user, topic.id).await;
+        upsert_transcript(
+            &pool,
+            &Encryption::disabled(),
+            id,
+            true,
+            &[TranscriptSegment {
+                speaker: "user".into(),
+                text: "Revenue grew this quarter.".into(),
+                start_ms: 0,
+                end_ms: 1500,
+                words: None,
+            }],
+        )
+        .await
+        .unwrap();
+        backdate(&pool, id, days).await;
+        sessions.push(id);
+    }
+    let fresh = session_with_audio(&app, &pool, user, topic.id).await;
+
+    let resp = app
+        .clone()
+        .oneshot(request(Method::GET, "/api/retention/report", user, None))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    let defaults = RetentionRule::default();
+    let dry_run = report(&pool, defaults, Utc::now(), 500).await.unwrap();
+    let mine = |items: &[backend::services::retention::RetentionCandidate]| {
+        let mut ids: Vec<Uuid> = items
+            .iter()
+            .filter(|c| c.user_id == user)
+            .map(|c| c.session_id)
+            .collect();
+        ids.sort();
+        ids
+    };
+    let sorted = |mut ids: Vec<Uuid>| {
+        ids.sort();
+        ids
+    };
+    assert_eq!(mine(&dry_run.sessions), vec![sessions[2]]);
+    assert_eq!(mine(&dry_run.audio), sorted(sessions.clone()));
+    assert_eq!(mine(&dry_run.transcripts), sorted(vec![sessions[1], sessions[2]]));
+    assert!(AudioRecording::get_by_session(&pool, sessions[0])
+        .await
+        .unwrap()
+        .is_some());
+
+    enforce(&pool, &storage().await, defaults, Utc::now(), 500)
+        .await
+        .unwrap();
+    let remaining: Vec<Uuid> =
+        sqlx::query_scalar("SELECT id FROM sessions WHERE user_id = $1")
+            .bind(user)
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+    assert_eq!(sorted(remaining), sorted(vec![sessions[0], sessions[1], fresh]));
+    assert!(AudioRecording::get_by_session(&pool, sessions[0])
+        .await
+        .unwrap()
+        .is_none());
+    assert!(AudioRecording::get_by_session(&pool, fresh)
+        .await
+        .unwrap()
+        .is_some());
+    assert!(get_transcript_by_session(&pool, &Encryption::disabled(), sessions[0])
+        .await
+        .unwrap()
+        .is_some());
+    assert!(get_transcript_by_session(&pool, &Encryption::disabled(), sessions[1])
+        .await
+        .unwrap()
+        .is_none());
+    let queued: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM storage_deletions WHERE user_id = $1 AND completed_at IS NULL",
+    )
+    .bind(user)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(queued, 3);
+
+    let resp = app
+        .clone()
+        .oneshot(request(Method::DELETE, "/api/me/retention", user, None))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+}
+
+#[tokio::test]
+async fn failing_sessions_back_off_instead_of_starving_retention() {
+    let pool = test_pool().await;
+    let app = test_app(pool.clone()).await;
+    let user = Uuid::new_v4();
+    let topic = Topic::create(
+        &pool,
+        &NewTopic {
+            title: format!("Archived Standup {}", Uuid::new_v4()),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    let resp = app
+        .clone()
+        .oneshot(request(
+            Method::PUT,
+            "/api/me/retention",
+            user,
+            Some(json!({ "audio_days": 7, "transcript_days": 30, "metadata_days": 90 })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let stuck = session_with_audio(&app, &pool, user, topic.id).await;
+    let behind = session_with_audio(&app, &pool, user, topic.id).await;
+    sqlx::query("UPDATE audio_recordings SET storage_url = 'https://elsewhere.example/audio.webm' WHERE session_id = $1")
+        .bind(stuck)
+        .execute(&pool)
+        .await
+        .unwrap();
+    backdate(&pool, stuck, 5000).await;
+    backdate(&pool, behind, 4000).await;
+
+    let storage = storage().await;
+    let first = enforce(&pool, &storage, RetentionRule::default(), Utc::now(), 1)
+        .await
+        .unwrap();
+    assert_eq!((first.sessions, first.audio), (0, 0));
+    let (session_attempts, recording_attempts): (i32, i32) = sqlx::query_as(
+        "SELECT s.purge_attempts, ar.purge_attempts FROM sessions s JOIN audio_recordings ar ON ar.session_id = s.id WHERE s.id = $1",
+    )
+    .bind(stuck)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!((session_attempts, recording_attempts), (1, 1));
+
+    let second = enforce(&pool, &storage, RetentionRule::default(), Utc::now(), 1)
+        .await
+        .unwrap();
+    assert_eq!(second.sessions, 1);
+    let remaining: Vec<Uuid> = sqlx::query_scalar("SELECT id FROM sessions WHERE id = ANY($1)")
+        .bind(vec![stuck, behind])
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    assert_eq!(remaining, vec![stuck]);
+}
@@ -0,0 +1,202 @@
+use axum::body::{self, Body};
+use axum::http::{Method, Request, StatusCode};
+use axum::Router;
+use backend::api;
+use backend::models::audio_recording::{AudioRecording, NewAudioRecording};
+use backend::models::topic::{NewTopic, Topic};
+use backend::services::deletion::{purge_tombstoned_sessions, within_restore_window};
+use backend::services::storage::StorageService;
+use backend::state::AppState;
+use chrono::{Duration, TimeZone, Utc};
+use dotenvy::dotenv;
+use serde_json::{json, Value};
+use sqlx::postgres::PgPoolOptions;
+use sqlx::PgPool;
+use tower::util::ServiceExt;
+use uuid::Uuid;
+
+#[test]
+fn restore_window_is_exclusive_at_the_boundary() {
+    let deleted = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap();
+    let window = Duration::hours(72);
+    assert!(within_restore_window(deleted, deleted + Duration::hours(71), window));
+    assert!(!within_restore_window(deleted, deleted + window, window));
+}
+
+async fn storage() -> StorageService {
+    std::env::set_var("S3_BUCKET", "test-bucket");
+    std::env::set_var("S3_ENDPOINT", "http://localhost:9000");
+    std::env::set_var("S3_ACCESS_KEY", "test");
+    std::env::set_var("S3_SECRET_KEY", "test");
+    StorageService::from_env().await.expect("storage")
+}
+
+async fn test_app(pool: PgPool) -> Router {
+    let state = AppState::new(pool, storage().await);
+    api::router(state)
+}
+
+async fn read_json(res: axum::response::Response) -> Value {
+    let bytes = body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    serde_json::from_slice(&bytes).unwrap()
+}
+
+fn request(method: Method, uri: &str, user: Uuid, body: Option<Value>) -> Request<Body> {
+    let builder = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("content-type", "application/json")
+        .header("x-user-id", user.to_string());
+    match body {
+        Some(body) => builder.body(Body::from(body.to_string())).unwrap(),
+        None => builder.body(Body::empty()).unwrap(),
+    }
+}
+
+async fn test_pool() -> PgPool {
+    dotenv().ok();
+    let url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set for tests");
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect(&url)
+        .await
+        .expect("connect db");
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+    sqlx::query("TRUNCATE client_secrets, transcripts, audio_recordings, sessions, topics RESTART IDENTITY CASCADE")
+        .execute(&pool)
+        .await
+        .unwrap();
+    pool
+}
+
+async fn session_with_audio(app: &Router, pool: &PgPool, user: Uuid, topic_id: Uuid) -> Uuid {
+    let resp = app
+        .clone()
+        .oneshot(request(
+            Method::POST,
+            "/api/sessions",
+            user,
+            Some(json!({ "topic_id": topic_id })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let id: Uuid = read_json(resp).await["id"]
+        .as_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    AudioRecording::insert(
+        pool,
+        NewAudioRecording {
+            session_id: id,
+            storage_url: format!("http://localhost:9000/test-bucket/sessions/{id}/audio.webm"),
+            duration_seconds: Some(5),
+            mime_type: Some("audio/webm".into()),
+            size_bytes: None,
+            quality_status: None,
+        },
+    )
+    .await
+    .unwrap();
+    id
+}
+
+async fn queued_keys(pool: &PgPool, user: Uuid) -> Vec<String> {
+    sqlx::query_scalar("SELECT object_key FROM storage_deletions WHERE user_id = $1 ORDER BY object_key")
+        .bind(user)
+        .fetch_all(pool)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn deleted_sessions_can_be_restored_until_purged() {
+    let pool = test_pool().await;
+    let app = test_app(pool.clone()).await;
+    let user = Uuid::new_v4();
+    let topic = Topic::create(
+        &pool,
+        &NewTopic {
+            title: format!("Retirement Speech {}", Uuid::new_v4()),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    let session = session_with_audio(&app, &pool, user, topic.id).await;
+    let session_uri = format!("/api/sessions/{session}");
+    let restore_uri = format!("/api/sessions/{session}/restore");
+
+    let resp = app
+        .clone()
+        .oneshot(request(Method::POST, &restore_uri, user, None))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::CONFLICT);
+
+    let resp = app
+        .clone()
+        .oneshot(request(Method::DELETE, &session_uri, user, None))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+    let resp = app
+        .clone()
+        .oneshot(request(Method::GET, "/api/sessions", user, None))
+        .await
+        .unwrap();
+    assert_eq!(read_json(resp).await["sessions"], json!([]));
+
+    let resp = app
+        .clone()
+        .oneshot(request(Method::POST, &restore_uri, Uuid::new_v4(), None))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    let resp = app
+        .clone()
+        .oneshot(request(Method::POST, &restore_uri, user, None))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+    let resp = app
+        .clone()
+        .oneshot(request(Method::GET, &session_uri, user, None))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let resp = app
+        .clone()
+        .oneshot(request(Method::DELETE, &session_uri, user, None))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+    sqlx::query("UPDATE sessions SET deleted_at = now() - interval '73 hours' WHERE id = $1")
+        .bind(session)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let resp = app
+        .clone()
+        .oneshot(request(Method::POST, &restore_uri, user, None))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::GONE);
+
+    let purged = purge_tombstoned_sessions(&pool, &storage().await, Duration::hours(72), 100)
+        .await
+        .unwrap();
+    assert!(purged >= 1);
+    let remaining: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM sessions WHERE id = $1")
+        .bind(session)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(remaining, 0);
+    assert_eq!(
+        queued_keys(&pool, user).await,
+        vec![format!("sessions/{session}/audio.webm")]
+    );
+}
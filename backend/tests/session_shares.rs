@@ -0,0 +1,257 @@
+use axum::body::{self, Body};
+use axum::http::{Method, Request, StatusCode};
+use axum::Router;
+use backend::api;
+use backend::encryption::Encryption;
+use backend::models::audio_recording::{AudioRecording, NewAudioRecording};
+use backend::models::session_share::SessionShare;
+use backend::models::topic::{NewTopic, Topic};
+use backend::models::transcript::{upsert_transcript, TranscriptSegment};
+use backend::services::sharing::{generate_token, share_expiry, MAX_SHARE_HOURS};
+use backend::services::storage::StorageService;
+use backend::state::AppState;
+use chrono::{Duration, Utc};
+use dotenvy::dotenv;
+use serde_json::{json, Value};
+use sqlx::postgres::PgPoolOptions;
+use sqlx::PgPool;
+use tower::util::ServiceExt;
+use uuid::Uuid;
+
+#[test]
+fn tokens_are_unique_and_expiry_is_bounded() {
+    let token = generate_token();
+    assert_eq!(token.len(), 64);
+    assert_ne!(token, generate_token());
+    let now = Utc::now();
+    assert_eq!(share_expiry(now, None).unwrap(), now + Duration::days(7));
+    assert_eq!(share_expiry(now, Some(2)).unwrap(), now + Duration::hours(2));
+    assert!(share_expiry(now, Some(0)).is_err());
+    assert!(share_expiry(now, Some(MAX_SHARE_HOURS + 1)).is_err());
+}
+
+async fn storage() -> StorageService {
+    std::env::set_var("S3_BUCKET", "test-bucket");
+    std::env::set_var("S3_ENDPOINT", "http://localhost:9000");
+    std::env::set_var("S3_ACCESS_KEY", "test");
+    std::env::set_var("S3_SECRET_KEY", "test");
+    StorageService::from_env().await.expect("storage")
+}
+
+async fn test_app(pool: PgPool) -> Router {
+    let state = AppState::new(pool, storage().await);
+    api::router(state)
+}
+
+async fn read_json(res: axum::response::Response) -> Value {
+    let bytes = body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    serde_json::from_slice(&bytes).unwrap()
+}
+
+fn request(method: Method, uri: &str, user: Uuid, body: Option<Value>) -> Request<Body> {
+    let builder = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("content-type", "application/json")
+        .header("x-user-id", user.to_string());
+    match body {
+        Some(body) => builder.body(Body::from(body.to_string())).unwrap(),
+        None => builder.body(Body::empty()).unwrap(),
+    }
+}
+
+async fn test_pool() -> PgPool {
+    dotenv().ok();
+    let url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set for tests");
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect(&url)
+        .await
+        .expect("connect db");
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+    sqlx::query("TRUNCATE client_secrets, transcripts, audio_recordings, sessions, topics RESTART IDENTITY CASCADE")
+        .execute(&pool)
+        .await
+        .unwrap();
+    pool
+}
+
+async fn session_with_audio(app: &Router, pool: &PgPool, user: Uuid, topic_id: Uuid) -> Uuid {
+    let resp = app
+        .clone()
+        .oneshot(request(
+            Method::POST,
+            "/api/sessions",
+            user,
+            Some(json!({ "topic_id": topic_id })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let id: Uuid = read_json(resp).await["id"]
+        .as_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    AudioRecording::insert(
+        pool,
+        NewAudioRecording {
+            session_id: id,
+            storage_url: format!("http://localhost:9000/test-bucket/sessions/{id}/audio.webm"),
+            duration_seconds: Some(5),
+            mime_type: Some("audio/webm".into()),
+            size_bytes: None,
+            quality_status: None,
+        },
+    )
+    .await
+    .unwrap();
+    id
+}
+
+fn anonymous(uri: &str) -> Request<Body> {
+    Request::builder()
+        .method(Method::GET)
+        .uri(uri)
+        .body(Body::empty())
+        .unwrap()
+}
+
+async fn privacy(pool: &PgPool, session_id: Uuid) -> String {
+    sqlx::query_scalar("SELECT privacy FROM sessions WHERE id = $1")
+        .bind(session_id)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn share_links_expose_a_read_only_view_until_revoked() {
+    let pool = test_pool().await;
+    let app = test_app(pool.clone()).await;
+    let user = Uuid::new_v4();
+    let topic = Topic::create(
+        &pool,
+        &NewTopic {
+            title: format!("Wedding Toast {}", Uuid::new_v4()),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    let session = session_with_audio(&app, &pool, user, topic.id).await;
+    upsert_transcript(
+        &pool,
+        &Encryption::disabled(),
+        session,
+        true,
+        &[TranscriptSegment {
+            speaker: "user".into(),
+            text: "To the happy couple.".into(),
+            start_ms: 0,
+            end_ms: 1200,
+            words: None,
+        }],
+    )
+    .await
+    .unwrap();
+    let shares_uri = format!("/api/sessions/{session}/shares");
+
+    let resp = app
+        .clone()
+        .oneshot(request(Method::POST, &shares_uri, Uuid::new_v4(), Some(json!({}))))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    let resp = app
+        .clone()
+        .oneshot(request(
+            Method::POST,
+            &shares_uri,
+            user,
+            Some(json!({ "expires_in_hours": 0 })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let resp = app
+        .clone()
+        .oneshot(request(Method::POST, &shares_uri, user, Some(json!({}))))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let transcript_share = read_json(resp).await;
+    assert_eq!(transcript_share["scope"], "transcript");
+    assert_eq!(privacy(&pool, session).await, "shared");
+
+    let shared_uri = format!("/api/shared/{}", transcript_share["token"].as_str().unwrap());
+    let resp = app.clone().oneshot(anonymous(&shared_uri)).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let bytes = body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    let raw = String::from_utf8(bytes.to_vec()).unwrap();
+    assert!(!raw.contains(&user.to_string()));
+    let view: Value = serde_json::from_str(&raw).unwrap();
+    assert_eq!(view["transcript"][0]["text"], "To the happy couple.");
+    assert!(view["audio_url"].is_null());
+    assert_eq!(view["view_count"], 1);
+    let resp = app.clone().oneshot(anonymous(&shared_uri)).await.unwrap();
+    assert_eq!(read_json(resp).await["view_count"], 2);
+
+    let resp = app
+        .clone()
+        .oneshot(request(
+            Method::POST,
+            &shares_uri,
+            user,
+            Some(json!({ "scope": "transcript_audio", "expires_in_hours": 1 })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let audio_share = read_json(resp).await;
+    let resp = app
+        .clone()
+        .oneshot(anonymous(&format!(
+            "/api/shared/{}",
+            audio_share["token"].as_str().unwrap()
+        )))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let audio_url = read_json(resp).await["audio_url"].as_str().unwrap().to_string();
+    assert!(audio_url.contains(&format!("sessions/{session}/audio.webm")));
+    assert!(audio_url.contains("X-Amz-Signature"));
+
+    let resp = app
+        .clone()
+        .oneshot(request(
+            Method::DELETE,
+            &format!("{shares_uri}/{}", transcript_share["id"].as_str().unwrap()),
+            user,
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+    let resp = app.clone().oneshot(anonymous(&shared_uri)).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    assert_eq!(privacy(&pool, session).await, "shared");
+
+    // Once the remaining link lapses, the sweep flips the session back to private.
+    sqlx::query("UPDATE session_shares SET expires_at = now() - interval '1 minute' WHERE session_id = $1")
+        .bind(session)
+        .execute(&pool)
+        .await
+        .unwrap();
+    SessionShare::sync_privacy(&pool, None).await.unwrap();
+    assert_eq!(privacy(&pool, session).await, "private");
+
+    let resp = app
+        .clone()
+        .oneshot(request(Method::GET, &shares_uri, user, None))
+        .await
+        .unwrap();
+    let shares = read_json(resp).await;
+    assert_eq!(shares.as_array().unwrap().len(), 2);
+}
@@ -27,6 +27,7 @@ async fn insert_topic(pool: &PgPool) -> Uuid {
         title: format!("Test Topic {}", Uuid::new_v4()),
         difficulty: Some("easy".into()),
         prompt_hint: Some("hint".into()),
+        ..Default::default()
     };
     sqlx::query(
         r#"
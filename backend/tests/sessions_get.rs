@@ -2,6 +2,7 @@ use axum::body::{self, Body};
 use axum::http::{Method, Request, StatusCode};
 use axum::Router;
 use backend::api;
+use backend::encryption::Encryption;
 use backend::models::audio_recording::NewAudioRecording;
 use backend::models::topic::NewTopic;
 use backend::models::transcript::{upsert_transcript, TranscriptSegment};
@@ -29,6 +30,7 @@ async fn insert_topic(pool: &PgPool) -> Uuid {
         title: format!("History Topic {}", Uuid::new_v4()),
         difficulty: None,
         prompt_hint: None,
+        ..Default::default()
     };
     sqlx::query("INSERT INTO topics (title) VALUES ($1) RETURNING id")
         .bind(&topic.title)
@@ -103,8 +105,9 @@ async fn list_and_detail_sessions_include_audio_and_transcript() {
         text: "hello history".into(),
         start_ms: 0,
         end_ms: 1000,
+        words: None,
     }];
-    upsert_transcript(&pool, session_id, true, &segments)
+    upsert_transcript(&pool, &Encryption::disabled(), session_id, true, &segments)
         .await
         .unwrap();
 
@@ -129,7 +132,7 @@ async fn list_and_detail_sessions_include_audio_and_transcript() {
     // detail
     let detail_req = Request::builder()
         .method(Method::GET)
-        .uri(&format!("/api/sessions/{session_id}"))
+        .uri(format!("/api/sessions/{session_id}"))
         .header("x-user-id", user.to_string())
         .body(Body::empty())
         .unwrap();
@@ -148,7 +151,7 @@ async fn list_and_detail_sessions_include_audio_and_transcript() {
 }
 
 #[tokio::test]
-async fn delete_session_removes_record() {
+async fn delete_session_tombstones_record() {
     let pool = test_pool().await;
     let topic_id = insert_topic(&pool).await;
     let user = Uuid::new_v4();
@@ -158,18 +161,137 @@ async fn delete_session_removes_record() {
 
     let delete_req = Request::builder()
         .method(Method::DELETE)
-        .uri(&format!("/api/sessions/{session_id}"))
+        .uri(format!("/api/sessions/{session_id}"))
         .header("x-user-id", user.to_string())
         .body(Body::empty())
         .unwrap();
     let delete_resp = app.clone().oneshot(delete_req).await.unwrap();
     assert_eq!(delete_resp.status(), StatusCode::NO_CONTENT);
 
-    let reget = sqlx::query("SELECT count(*) FROM sessions WHERE id = $1")
+    let reget = sqlx::query("SELECT count(*) FROM sessions WHERE id = $1 AND deleted_at IS NOT NULL")
         .bind(session_id)
         .fetch_one(&pool)
         .await
         .unwrap();
-    let remaining: i64 = reget.get(0);
-    assert_eq!(remaining, 0);
+    let tombstoned: i64 = reget.get(0);
+    assert_eq!(tombstoned, 1);
+
+    let detail_req = Request::builder()
+        .method(Method::GET)
+        .uri(format!("/api/sessions/{session_id}"))
+        .header("x-user-id", user.to_string())
+        .body(Body::empty())
+        .unwrap();
+    let detail_resp = app.clone().oneshot(detail_req).await.unwrap();
+    assert_eq!(detail_resp.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn list_sessions_paginates_and_filters() {
+    let pool = test_pool().await;
+    let topic_id = insert_topic(&pool).await;
+    let other_topic_id = insert_topic(&pool).await;
+    let user = Uuid::new_v4();
+
+    let base = chrono::Utc::now() - chrono::Duration::hours(1);
+    let mut ids = Vec::new();
+    for minutes in 0..3 {
+        let id = insert_session(&pool, user, topic_id).await;
+        sqlx::query("UPDATE sessions SET start_time = $2 WHERE id = $1")
+            .bind(id)
+            .bind(base + chrono::Duration::minutes(minutes))
+            .execute(&pool)
+            .await
+            .unwrap();
+        ids.push(id);
+    }
+    insert_session(&pool, user, other_topic_id).await;
+
+    let app = test_app(pool.clone()).await;
+
+    let first_req = Request::builder()
+        .method(Method::GET)
+        .uri(format!("/api/sessions?limit=2&topic_id={topic_id}"))
+        .header("x-user-id", user.to_string())
+        .body(Body::empty())
+        .unwrap();
+    let first_resp = app.clone().oneshot(first_req).await.unwrap();
+    assert_eq!(first_resp.status(), StatusCode::OK);
+    let first = read_json(first_resp).await;
+    assert_eq!(first["total"], 3);
+    let first_page = first["sessions"].as_array().unwrap();
+    assert_eq!(first_page.len(), 2);
+    assert_eq!(first_page[0]["id"].as_str(), Some(ids[2].to_string().as_str()));
+    assert_eq!(first_page[1]["id"].as_str(), Some(ids[1].to_string().as_str()));
+    let cursor = first["next_cursor"].as_str().unwrap().to_string();
+
+    let second_req = Request::builder()
+        .method(Method::GET)
+        .uri(format!(
+            "/api/sessions?limit=2&topic_id={topic_id}&cursor={cursor}"
+        ))
+        .header("x-user-id", user.to_string())
+        .body(Body::empty())
+        .unwrap();
+    let second_resp = app.clone().oneshot(second_req).await.unwrap();
+    assert_eq!(second_resp.status(), StatusCode::OK);
+    let second = read_json(second_resp).await;
+    let second_page = second["sessions"].as_array().unwrap();
+    assert_eq!(second_page.len(), 1);
+    assert_eq!(second_page[0]["id"].as_str(), Some(ids[0].to_string().as_str()));
+    assert!(second["next_cursor"].is_null());
+
+    let bad_req = Request::builder()
+        .method(Method::GET)
+        .uri("/api/sessions?cursor=garbage")
+        .header("x-user-id", user.to_string())
+        .body(Body::empty())
+        .unwrap();
+    let bad_resp = app.clone().oneshot(bad_req).await.unwrap();
+    assert_eq!(bad_resp.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn transcript_export_sets_attachment_headers() {
+    let pool = test_pool().await;
+    let topic_id = insert_topic(&pool).await;
+    let user = Uuid::new_v4();
+    let session_id = insert_session(&pool, user, topic_id).await;
+    let segments = vec![TranscriptSegment {
+        speaker: "user".into(),
+        text: "caption me".into(),
+        start_ms: 0,
+        end_ms: 1200,
+        words: None,
+    }];
+    upsert_transcript(&pool, &Encryption::disabled(), session_id, true, &segments)
+        .await
+        .unwrap();
+
+    let app = test_app(pool.clone()).await;
+
+    let req = Request::builder()
+        .method(Method::GET)
+        .uri(format!("/api/sessions/{session_id}/transcript?format=vtt"))
+        .header("x-user-id", user.to_string())
+        .body(Body::empty())
+        .unwrap();
+    let resp = app.clone().oneshot(req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let disposition = resp.headers()["content-disposition"].to_str().unwrap().to_string();
+    assert!(disposition.starts_with("attachment; filename=\"history-topic-"));
+    assert!(disposition.contains(".vtt\"; filename*=UTF-8''history-topic-"));
+    assert!(disposition.ends_with(".vtt"));
+    let bytes = body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    let text = String::from_utf8(bytes.to_vec()).unwrap();
+    assert!(text.contains("<v You>caption me"));
+
+    let other_user_req = Request::builder()
+        .method(Method::GET)
+        .uri(format!("/api/sessions/{session_id}/transcript?format=srt"))
+        .header("x-user-id", Uuid::new_v4().to_string())
+        .body(Body::empty())
+        .unwrap();
+    let other_user_resp = app.clone().oneshot(other_user_req).await.unwrap();
+    assert_eq!(other_user_resp.status(), StatusCode::NOT_FOUND);
 }
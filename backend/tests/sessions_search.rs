@@ -0,0 +1,159 @@
+use axum::body::{self, Body};
+use axum::http::{Method, Request, StatusCode};
+use axum::Router;
+use backend::api;
+use backend::encryption::Encryption;
+use backend::models::transcript::{upsert_transcript, TranscriptSegment};
+use backend::services::storage::StorageService;
+use backend::state::AppState;
+use dotenvy::dotenv;
+use serde_json::Value;
+use sqlx::postgres::PgPoolOptions;
+use sqlx::{PgPool, Row};
+use tower::util::ServiceExt;
+use uuid::Uuid;
+
+async fn test_app(pool: PgPool) -> Router {
+    std::env::set_var("S3_BUCKET", "test-bucket");
+    std::env::set_var("S3_ENDPOINT", "http://localhost:9000");
+    std::env::set_var("S3_ACCESS_KEY", "test");
+    std::env::set_var("S3_SECRET_KEY", "test");
+    let storage = StorageService::from_env().await.expect("storage");
+    let state = AppState::new(pool, storage);
+    api::router(state)
+}
+
+async fn insert_topic(pool: &PgPool) -> Uuid {
+    sqlx::query("INSERT INTO topics (title) VALUES ($1) RETURNING id")
+        .bind(format!("Search Topic {}", Uuid::new_v4()))
+        .fetch_one(pool)
+        .await
+        .unwrap()
+        .get::<Uuid, _>(0)
+}
+
+async fn insert_session(pool: &PgPool, user: Uuid, topic_id: Uuid) -> Uuid {
+    sqlx::query(
+        r#"
+        INSERT INTO sessions (user_id, topic_id, status)
+        VALUES ($1, $2, 'ended')
+        RETURNING id
+        "#,
+    )
+    .bind(user)
+    .bind(topic_id)
+    .fetch_one(pool)
+    .await
+    .unwrap()
+    .get::<Uuid, _>(0)
+}
+
+fn segment(speaker: &str, text: &str, start_ms: i64) -> TranscriptSegment {
+    TranscriptSegment {
+        speaker: speaker.into(),
+        text: text.into(),
+        start_ms,
+        end_ms: start_ms + 1000,
+        words: None,
+    }
+}
+
+async fn read_json(res: axum::response::Response) -> Value {
+    let bytes = body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    serde_json::from_slice(&bytes).unwrap()
+}
+
+async fn test_pool() -> PgPool {
+    dotenv().ok();
+    let url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set for tests");
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect(&url)
+        .await
+        .expect("connect db");
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+    sqlx::query("TRUNCATE client_secrets, transcripts, audio_recordings, sessions, topics RESTART IDENTITY CASCADE")
+        .execute(&pool)
+        .await
+        .unwrap();
+    pool
+}
+
+#[tokio::test]
+async fn search_returns_matching_segments_for_owner_only() {
+    let pool = test_pool().await;
+    let topic_id = insert_topic(&pool).await;
+    let user = Uuid::new_v4();
+    let other_user = Uuid::new_v4();
+
+    let kyoto_session = insert_session(&pool, user, topic_id).await;
+    upsert_transcript(
+        &pool,
+        &Encryption::disabled(),
+        kyoto_session,
+        true,
+        &[
+            segment("ai", "Where did you travel last year?", 0),
+            segment("user", "I visited the temples in Kyoto in autumn", 2000),
+            segment("user", "The food was great", 5000),
+        ],
+    )
+    .await
+    .unwrap();
+
+    let other_session = insert_session(&pool, user, topic_id).await;
+    upsert_transcript(
+        &pool,
+        &Encryption::disabled(),
+        other_session,
+        true,
+        &[segment("user", "I talked about my job", 0)],
+    )
+    .await
+    .unwrap();
+
+    let foreign_session = insert_session(&pool, other_user, topic_id).await;
+    upsert_transcript(
+        &pool,
+        &Encryption::disabled(),
+        foreign_session,
+        true,
+        &[segment("user", "Kyoto is beautiful", 0)],
+    )
+    .await
+    .unwrap();
+
+    let app = test_app(pool.clone()).await;
+
+    let req = Request::builder()
+        .method(Method::GET)
+        .uri("/api/sessions/search?q=kyoto")
+        .header("x-user-id", user.to_string())
+        .body(Body::empty())
+        .unwrap();
+    let resp = app.clone().oneshot(req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = read_json(resp).await;
+    let results = body["results"].as_array().unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(
+        results[0]["session_id"].as_str(),
+        Some(kyoto_session.to_string().as_str())
+    );
+    let matches = results[0]["matches"].as_array().unwrap();
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0]["start_ms"], 2000);
+    assert!(matches[0]["snippet"]
+        .as_str()
+        .unwrap()
+        .contains("<mark>Kyoto</mark>"));
+
+    let empty_req = Request::builder()
+        .method(Method::GET)
+        .uri("/api/sessions/search?q=%20")
+        .header("x-user-id", user.to_string())
+        .body(Body::empty())
+        .unwrap();
+    let empty_resp = app.clone().oneshot(empty_req).await.unwrap();
+    assert_eq!(empty_resp.status(), StatusCode::BAD_REQUEST);
+}
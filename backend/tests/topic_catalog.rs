@@ -0,0 +1,253 @@
+use backend::models::topic::{NewTopic, Topic};
+use backend::services::topic_catalog::{
+    apply_catalog, parse_catalog, plan_catalog, render_catalog, CatalogChange, CatalogFormat,
+    CatalogPlan, SeedMode,
+};
+use chrono::Utc;
+use dotenvy::dotenv;
+use sqlx::postgres::PgPoolOptions;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+fn existing(title: &str, difficulty: &str) -> Topic {
+    Topic {
+        id: Uuid::new_v4(),
+        title: title.into(),
+        difficulty: Some(difficulty.into()),
+        prompt_hint: None,
+        category: None,
+        tags: vec![],
+        target_skill: None,
+        estimated_duration_seconds: None,
+        owner_id: None,
+        org_id: None,
+        archived_at: None,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    }
+}
+
+#[test]
+fn parses_all_formats_to_the_same_topics() {
+    let json = r#"[
+  { "title": "Order Coffee", "difficulty": "easy", "tags": ["Food", "travel"] }
+]"#;
+    let yaml = "- title: Order Coffee\n  difficulty: easy\n  tags: [Food, travel]\n";
+    let csv = "title,difficulty,prompt_hint,tags\nOrder Coffee,easy,,Food; travel\n";
+
+    let expected = vec![NewTopic {
+        title: "Order Coffee".into(),
+        difficulty: Some("easy".into()),
+        tags: vec!["food".into(), "travel".into()],
+        ..Default::default()
+    }];
+    assert_eq!(parse_catalog(json, CatalogFormat::Json).unwrap(), expected);
+    assert_eq!(parse_catalog(yaml, CatalogFormat::Yaml).unwrap(), expected);
+    assert_eq!(parse_catalog(csv, CatalogFormat::Csv).unwrap(), expected);
+}
+
+#[test]
+fn validation_errors_point_at_lines() {
+    let json = r#"[
+  { "title": "Fine" },
+  {
+    "title": "Bad",
+    "difficulty": "impossible"
+  },
+  { "title": "Fine" }
+]"#;
+    let errors = parse_catalog(json, CatalogFormat::Json).unwrap_err();
+    assert_eq!(errors.len(), 2);
+    assert_eq!(errors[0].line, Some(3));
+    assert!(errors[0].message.contains("difficulty"));
+    assert_eq!(errors[1].line, Some(7));
+    assert!(errors[1].message.contains("duplicate"));
+
+    let yaml = "- title: Fine\n- title: Slow\n  estimated_duration_seconds: 5\n";
+    let errors = parse_catalog(yaml, CatalogFormat::Yaml).unwrap_err();
+    assert_eq!(errors[0].line, Some(2));
+
+    let csv = "title,estimated_duration_seconds\nFine,60\nBroken,soon\n";
+    let errors = parse_catalog(csv, CatalogFormat::Csv).unwrap_err();
+    assert_eq!(errors[0].line, Some(3));
+
+    let errors = parse_catalog("[{\"title\": ", CatalogFormat::Json).unwrap_err();
+    assert_eq!(errors[0].line, Some(1));
+}
+
+#[test]
+fn export_round_trips() {
+    let mut topic = existing("Order Coffee", "easy");
+    topic.tags = vec!["food".into(), "travel".into()];
+    topic.prompt_hint = Some("Ask for oat milk, please".into());
+    topic.estimated_duration_seconds = Some(90);
+    let mut archived = existing("Old Topic", "hard");
+    archived.archived_at = Some(Utc::now());
+    let topics = vec![topic, archived];
+
+    for format in [CatalogFormat::Json, CatalogFormat::Yaml, CatalogFormat::Csv] {
+        let rendered = render_catalog(&topics, format).unwrap();
+        let parsed = parse_catalog(&rendered, format).unwrap();
+        assert_eq!(parsed.len(), 1, "{:?}", format);
+        assert_eq!(parsed[0].tags, vec!["food", "travel"]);
+        assert_eq!(parsed[0].estimated_duration_seconds, Some(90));
+        assert_eq!(
+            parsed[0].prompt_hint.as_deref(),
+            Some("Ask for oat milk, please")
+        );
+    }
+}
+
+#[test]
+fn plan_respects_mode_and_prune() {
+    let coffee = existing("Order Coffee", "easy");
+    let stale = existing("Stale Topic", "easy");
+    let incoming = vec![
+        NewTopic {
+            title: "Order Coffee".into(),
+            difficulty: Some("medium".into()),
+            ..Default::default()
+        },
+        NewTopic {
+            title: "New Topic".into(),
+            ..Default::default()
+        },
+    ];
+    let current = vec![coffee.clone(), stale.clone()];
+
+    let plan = plan_catalog(&current, &incoming, SeedMode::Insert, false);
+    assert_eq!(plan.changes.len(), 1);
+    assert_eq!(plan.skipped, 1);
+
+    let plan = plan_catalog(&current, &incoming, SeedMode::Upsert, true);
+    assert_eq!(plan.changes.len(), 3);
+    match &plan.changes[0] {
+        CatalogChange::Update { id, fields, .. } => {
+            assert_eq!(*id, coffee.id);
+            assert_eq!(fields.len(), 1);
+            assert_eq!(fields[0].field, "difficulty");
+        }
+        other => panic!("expected update, got {:?}", other),
+    }
+    assert_eq!(
+        plan.changes[0].to_string(),
+        "~ update \"Order Coffee\"\n    difficulty: easy -> medium"
+    );
+    assert!(matches!(plan.changes[1], CatalogChange::Create(_)));
+    assert_eq!(plan.changes[2].to_string(), "- archive \"Stale Topic\"");
+}
+
+async fn test_pool() -> PgPool {
+    dotenv().ok();
+    let url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set for tests");
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect(&url)
+        .await
+        .expect("connect db");
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+    pool
+}
+
+#[tokio::test]
+async fn apply_upserts_and_archives() {
+    let pool = test_pool().await;
+    let suffix = Uuid::new_v4();
+    let kept = Topic::create(
+        &pool,
+        &NewTopic {
+            title: format!("Kept {suffix}"),
+            difficulty: Some("easy".into()),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    let pruned = Topic::create(
+        &pool,
+        &NewTopic {
+            title: format!("Pruned {suffix}"),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+
+    let incoming = vec![
+        NewTopic {
+            title: kept.title.clone(),
+            difficulty: Some("hard".into()),
+            prompt_hint: Some("Updated hint".into()),
+            ..Default::default()
+        },
+        NewTopic {
+            title: format!("Added {suffix}"),
+            ..Default::default()
+        },
+    ];
+    // Only consider this test's topics so parallel tests are not pruned.
+    let current: Vec<Topic> = Topic::list_all(&pool)
+        .await
+        .unwrap()
+        .into_iter()
+        .filter(|t| t.title.ends_with(&suffix.to_string()))
+        .collect();
+    let plan = plan_catalog(&current, &incoming, SeedMode::Upsert, true);
+    apply_catalog(&pool, &plan).await.unwrap();
+
+    let kept = Topic::get(&pool, kept.id).await.unwrap().unwrap();
+    assert_eq!(kept.difficulty.as_deref(), Some("hard"));
+    assert_eq!(kept.prompt_hint.as_deref(), Some("Updated hint"));
+    let pruned = Topic::get(&pool, pruned.id).await.unwrap().unwrap();
+    assert!(pruned.archived_at.is_some());
+
+    let current: Vec<Topic> = Topic::list_all(&pool)
+        .await
+        .unwrap()
+        .into_iter()
+        .filter(|t| t.title.ends_with(&suffix.to_string()))
+        .collect();
+    let again = plan_catalog(&current, &incoming, SeedMode::Upsert, true);
+    assert!(again.changes.is_empty());
+    assert_eq!(again.unchanged, 2);
+}
+
+#[tokio::test]
+async fn failed_apply_leaves_no_partial_changes() {
+    let pool = test_pool().await;
+    let suffix = Uuid::new_v4();
+    let taken = Topic::create(
+        &pool,
+        &NewTopic {
+            title: format!("Taken {suffix}"),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+
+    // The second create collides with an existing global title after the first succeeded.
+    let plan = CatalogPlan {
+        changes: vec![
+            CatalogChange::Create(NewTopic {
+                title: format!("Fresh {suffix}"),
+                ..Default::default()
+            }),
+            CatalogChange::Create(NewTopic {
+                title: taken.title.clone(),
+                ..Default::default()
+            }),
+        ],
+        ..Default::default()
+    };
+    assert!(apply_catalog(&pool, &plan).await.is_err());
+
+    let titles: Vec<String> = Topic::list_all(&pool)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|t| t.title)
+        .filter(|t| t.ends_with(&suffix.to_string()))
+        .collect();
+    assert_eq!(titles, vec![taken.title]);
+}
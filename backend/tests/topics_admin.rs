@@ -0,0 +1,156 @@
+use axum::body::{self, Body};
+use axum::http::{Method, Request, StatusCode};
+use axum::Router;
+use backend::api;
+use backend::models::user_role::{UserRole, ADMIN_ROLE};
+use backend::services::storage::StorageService;
+use backend::state::AppState;
+use dotenvy::dotenv;
+use serde_json::{json, Value};
+use sqlx::postgres::PgPoolOptions;
+use sqlx::PgPool;
+use tower::util::ServiceExt;
+use uuid::Uuid;
+
+async fn test_app(pool: PgPool) -> Router {
+    std::env::set_var("S3_BUCKET", "test-bucket");
+    std::env::set_var("S3_ENDPOINT", "http://localhost:9000");
+    std::env::set_var("S3_ACCESS_KEY", "test");
+    std::env::set_var("S3_SECRET_KEY", "test");
+    let storage = StorageService::from_env().await.expect("storage");
+    let state = AppState::new(pool, storage);
+    api::router(state)
+}
+
+async fn read_json(res: axum::response::Response) -> Value {
+    let bytes = body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    serde_json::from_slice(&bytes).unwrap()
+}
+
+fn request(method: Method, uri: String, user: Uuid, body: Option<Value>) -> Request<Body> {
+    let builder = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("content-type", "application/json")
+        .header("x-user-id", user.to_string());
+    match body {
+        Some(body) => builder.body(Body::from(body.to_string())).unwrap(),
+        None => builder.body(Body::empty()).unwrap(),
+    }
+}
+
+async fn test_pool() -> PgPool {
+    dotenv().ok();
+    let url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set for tests");
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect(&url)
+        .await
+        .expect("connect db");
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+    sqlx::query("TRUNCATE client_secrets, transcripts, audio_recordings, sessions, topics RESTART IDENTITY CASCADE")
+        .execute(&pool)
+        .await
+        .unwrap();
+    pool
+}
+
+#[tokio::test]
+async fn admin_manages_topics_and_archives_them() {
+    let pool = test_pool().await;
+    let app = test_app(pool.clone()).await;
+    let admin = Uuid::new_v4();
+    let learner = Uuid::new_v4();
+    UserRole::grant(&pool, admin, ADMIN_ROLE).await.unwrap();
+
+    let new_topic = json!({
+        "title": "  Describe Your Hometown ",
+        "difficulty": "easy",
+        "prompt_hint": "Where is it and what is it known for?"
+    });
+
+    let resp = app
+        .clone()
+        .oneshot(request(Method::POST, "/api/topics".into(), learner, Some(new_topic.clone())))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    let resp = app
+        .clone()
+        .oneshot(request(Method::POST, "/api/topics".into(), admin, Some(new_topic.clone())))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let created = read_json(resp).await;
+    assert_eq!(created["title"], "Describe Your Hometown");
+    let topic_id = created["id"].as_str().unwrap().to_string();
+
+    let resp = app
+        .clone()
+        .oneshot(request(Method::POST, "/api/topics".into(), admin, Some(new_topic)))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::CONFLICT);
+
+    let resp = app
+        .clone()
+        .oneshot(request(
+            Method::POST,
+            "/api/topics".into(),
+            admin,
+            Some(json!({ "title": "Another", "difficulty": "extreme" })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let resp = app
+        .clone()
+        .oneshot(request(
+            Method::PATCH,
+            format!("/api/topics/{topic_id}"),
+            admin,
+            Some(json!({ "difficulty": "medium", "prompt_hint": null })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let updated = read_json(resp).await;
+    assert_eq!(updated["title"], "Describe Your Hometown");
+    assert_eq!(updated["difficulty"], "medium");
+    assert!(updated["prompt_hint"].is_null());
+    assert_ne!(updated["updated_at"], created["updated_at"]);
+
+    let resp = app
+        .clone()
+        .oneshot(request(Method::DELETE, format!("/api/topics/{topic_id}"), admin, None))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+    let resp = app
+        .clone()
+        .oneshot(request(Method::GET, "/api/topics".into(), learner, None))
+        .await
+        .unwrap();
+    let listed = read_json(resp).await;
+    assert!(listed
+        .as_array()
+        .unwrap()
+        .iter()
+        .all(|t| t["id"].as_str() != Some(topic_id.as_str())));
+
+    // archived topics cannot start new sessions but keep their rows
+    let resp = app
+        .clone()
+        .oneshot(request(
+            Method::POST,
+            "/api/sessions".into(),
+            learner,
+            Some(json!({ "topic_id": topic_id })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+}
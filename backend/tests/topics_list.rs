@@ -0,0 +1,113 @@
+use axum::body::{self, Body};
+use axum::http::{Method, Request, StatusCode};
+use axum::Router;
+use backend::api;
+use backend::models::topic::{NewTopic, Topic};
+use backend::services::storage::StorageService;
+use backend::state::AppState;
+use dotenvy::dotenv;
+use serde_json::Value;
+use sqlx::postgres::PgPoolOptions;
+use sqlx::PgPool;
+use tower::util::ServiceExt;
+use uuid::Uuid;
+
+async fn test_app(pool: PgPool) -> Router {
+    std::env::set_var("S3_BUCKET", "test-bucket");
+    std::env::set_var("S3_ENDPOINT", "http://localhost:9000");
+    std::env::set_var("S3_ACCESS_KEY", "test");
+    std::env::set_var("S3_SECRET_KEY", "test");
+    let storage = StorageService::from_env().await.expect("storage");
+    let state = AppState::new(pool, storage);
+    api::router(state)
+}
+
+async fn test_pool() -> PgPool {
+    dotenv().ok();
+    let url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set for tests");
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect(&url)
+        .await
+        .expect("connect db");
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+    sqlx::query("TRUNCATE client_secrets, transcripts, audio_recordings, sessions, topics RESTART IDENTITY CASCADE")
+        .execute(&pool)
+        .await
+        .unwrap();
+    pool
+}
+
+async fn list(app: &Router, query: &str) -> (Vec<Value>, i64) {
+    let req = Request::builder()
+        .method(Method::GET)
+        .uri(format!("/api/topics{query}"))
+        .header("x-user-id", Uuid::new_v4().to_string())
+        .body(Body::empty())
+        .unwrap();
+    let resp = app.clone().oneshot(req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let total = resp.headers()["x-total-count"]
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    let bytes = body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    let topics: Value = serde_json::from_slice(&bytes).unwrap();
+    (topics.as_array().unwrap().clone(), total)
+}
+
+#[tokio::test]
+async fn topics_filter_by_metadata_and_paginate() {
+    let pool = test_pool().await;
+    let topics = [
+        NewTopic {
+            title: "Order at a Cafe".into(),
+            difficulty: Some("easy".into()),
+            prompt_hint: Some("Ask for a 100% oat latte".into()),
+            category: Some("daily life".into()),
+            tags: vec!["food".into(), "travel".into()],
+            target_skill: Some("small talk".into()),
+            estimated_duration_seconds: Some(90),
+        },
+        NewTopic {
+            title: "Defend a Budget".into(),
+            difficulty: Some("hard".into()),
+            prompt_hint: Some("Convince your manager".into()),
+            category: Some("work".into()),
+            tags: vec!["business".into()],
+            target_skill: Some("persuasion".into()),
+            estimated_duration_seconds: Some(300),
+        },
+        NewTopic {
+            title: "Weekend Plans".into(),
+            difficulty: Some("easy".into()),
+            category: Some("daily life".into()),
+            tags: vec!["travel".into()],
+            ..Default::default()
+        },
+    ];
+    for topic in &topics {
+        Topic::create(&pool, topic).await.unwrap();
+    }
+    let app = test_app(pool.clone()).await;
+
+    let (all, total) = list(&app, "").await;
+    assert_eq!((all.len(), total), (3, 3));
+
+    let (easy_travel, total) = list(&app, "?difficulty=easy&tag=Travel").await;
+    assert_eq!(total, 2);
+    assert!(easy_travel.iter().all(|t| t["category"] == "daily life"));
+
+    let (work, _) = list(&app, "?category=work&skill=persuasion").await;
+    assert_eq!(work.len(), 1);
+    assert_eq!(work[0]["title"], "Defend a Budget");
+    assert_eq!(work[0]["estimated_duration_seconds"], 300);
+
+    let (searched, _) = list(&app, "?q=100%25").await;
+    assert_eq!(searched.len(), 1);
+    assert_eq!(searched[0]["title"], "Order at a Cafe");
+
+    let (page, total) = list(&app, "?limit=2&offset=2").await;
+    assert_eq!((page.len(), total), (1, 3));
+}
@@ -0,0 +1,233 @@
+use axum::body::{self, Body};
+use axum::http::{Method, Request, StatusCode};
+use axum::Router;
+use backend::api;
+use backend::models::user_role::{UserRole, ADMIN_ROLE};
+use backend::services::storage::StorageService;
+use backend::state::AppState;
+use dotenvy::dotenv;
+use serde_json::{json, Value};
+use sqlx::postgres::PgPoolOptions;
+use sqlx::PgPool;
+use tower::util::ServiceExt;
+use uuid::Uuid;
+
+async fn test_app(pool: PgPool) -> Router {
+    std::env::set_var("S3_BUCKET", "test-bucket");
+    std::env::set_var("S3_ENDPOINT", "http://localhost:9000");
+    std::env::set_var("S3_ACCESS_KEY", "test");
+    std::env::set_var("S3_SECRET_KEY", "test");
+    let storage = StorageService::from_env().await.expect("storage");
+    let state = AppState::new(pool, storage);
+    api::router(state)
+}
+
+async fn read_json(res: axum::response::Response) -> Value {
+    let bytes = body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    serde_json::from_slice(&bytes).unwrap()
+}
+
+fn request(
+    method: Method,
+    uri: String,
+    user: Uuid,
+    accept_language: Option<&str>,
+    body: Option<Value>,
+) -> Request<Body> {
+    let mut builder = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("content-type", "application/json")
+        .header("x-user-id", user.to_string());
+    if let Some(accept_language) = accept_language {
+        builder = builder.header("accept-language", accept_language);
+    }
+    match body {
+        Some(body) => builder.body(Body::from(body.to_string())).unwrap(),
+        None => builder.body(Body::empty()).unwrap(),
+    }
+}
+
+async fn test_pool() -> PgPool {
+    dotenv().ok();
+    let url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set for tests");
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect(&url)
+        .await
+        .expect("connect db");
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+    sqlx::query("TRUNCATE client_secrets, transcripts, audio_recordings, sessions, topics RESTART IDENTITY CASCADE")
+        .execute(&pool)
+        .await
+        .unwrap();
+    pool
+}
+
+#[tokio::test]
+async fn topics_are_localized_and_sessions_record_language() {
+    let pool = test_pool().await;
+    let app = test_app(pool.clone()).await;
+    let admin = Uuid::new_v4();
+    let learner = Uuid::new_v4();
+    UserRole::grant(&pool, admin, ADMIN_ROLE).await.unwrap();
+
+    let resp = app
+        .clone()
+        .oneshot(request(
+            Method::POST,
+            "/api/topics".into(),
+            admin,
+            None,
+            Some(json!({ "title": "Plan a Weekend Trip", "prompt_hint": "Where would you go?" })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let topic_id = read_json(resp).await["id"].as_str().unwrap().to_string();
+
+    let translation = json!({ "title": "週末の旅行を計画する" });
+    let resp = app
+        .clone()
+        .oneshot(request(
+            Method::PUT,
+            format!("/api/topics/{topic_id}/translations/ja"),
+            learner,
+            None,
+            Some(translation.clone()),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    let resp = app
+        .clone()
+        .oneshot(request(
+            Method::PUT,
+            format!("/api/topics/{topic_id}/translations/en"),
+            admin,
+            None,
+            Some(translation.clone()),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let resp = app
+        .clone()
+        .oneshot(request(
+            Method::PUT,
+            format!("/api/topics/{topic_id}/translations/ja-JP"),
+            admin,
+            None,
+            Some(translation),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(read_json(resp).await["locale"], "ja");
+
+    // Accept-Language picks the translation; the untranslated hint falls back to English.
+    let resp = app
+        .clone()
+        .oneshot(request(
+            Method::GET,
+            "/api/topics".into(),
+            learner,
+            Some("ja;q=0.9, en;q=0.5"),
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers()["content-language"], "ja");
+    let topics = read_json(resp).await;
+    assert_eq!(topics[0]["title"], "週末の旅行を計画する");
+    assert_eq!(topics[0]["prompt_hint"], "Where would you go?");
+
+    // An explicit locale overrides the header, and search matches translated text.
+    let resp = app
+        .clone()
+        .oneshot(request(
+            Method::GET,
+            "/api/topics?locale=en&q=%E6%97%85%E8%A1%8C".into(),
+            learner,
+            Some("ja"),
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.headers()["content-language"], "en");
+    let topics = read_json(resp).await;
+    assert_eq!(topics.as_array().unwrap().len(), 1);
+    assert_eq!(topics[0]["title"], "Plan a Weekend Trip");
+
+    let resp = app
+        .clone()
+        .oneshot(request(
+            Method::POST,
+            "/api/sessions".into(),
+            learner,
+            Some("es-MX"),
+            Some(json!({ "topic_id": topic_id })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    assert_eq!(read_json(resp).await["language"], "es");
+
+    let resp = app
+        .clone()
+        .oneshot(request(
+            Method::POST,
+            "/api/sessions".into(),
+            learner,
+            None,
+            Some(json!({ "topic_id": topic_id, "language": "ja" })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::CREATED);
+
+    let resp = app
+        .clone()
+        .oneshot(request(
+            Method::POST,
+            "/api/sessions".into(),
+            learner,
+            None,
+            Some(json!({ "topic_id": topic_id, "language": "klingon" })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    let resp = app
+        .clone()
+        .oneshot(request(
+            Method::GET,
+            "/api/sessions?language=ja".into(),
+            learner,
+            None,
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let page = read_json(resp).await;
+    assert_eq!(page["total"], 1);
+    assert_eq!(page["sessions"][0]["language"], "ja");
+
+    let resp = app
+        .clone()
+        .oneshot(request(
+            Method::DELETE,
+            format!("/api/topics/{topic_id}/translations/ja"),
+            admin,
+            None,
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+}
@@ -0,0 +1,264 @@
+use axum::body::{self, Body};
+use axum::http::{Method, Request, StatusCode};
+use axum::Router;
+use backend::api;
+use backend::models::topic::{NewTopic, Topic};
+use backend::services::storage::StorageService;
+use backend::services::topics::{create_private_topic, TopicError, MAX_PRIVATE_TOPICS_PER_USER};
+use backend::state::AppState;
+use dotenvy::dotenv;
+use serde_json::{json, Value};
+use sqlx::postgres::PgPoolOptions;
+use sqlx::PgPool;
+use tower::util::ServiceExt;
+use uuid::Uuid;
+
+async fn test_app(pool: PgPool) -> Router {
+    std::env::set_var("S3_BUCKET", "test-bucket");
+    std::env::set_var("S3_ENDPOINT", "http://localhost:9000");
+    std::env::set_var("S3_ACCESS_KEY", "test");
+    std::env::set_var("S3_SECRET_KEY", "test");
+    let storage = StorageService::from_env().await.expect("storage");
+    let state = AppState::new(pool, storage);
+    api::router(state)
+}
+
+async fn read_json(res: axum::response::Response) -> Value {
+    let bytes = body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    serde_json::from_slice(&bytes).unwrap()
+}
+
+fn request(method: Method, uri: String, user: Option<Uuid>, body: Option<Value>) -> Request<Body> {
+    let mut builder = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("content-type", "application/json");
+    if let Some(user) = user {
+        builder = builder.header("x-user-id", user.to_string());
+    }
+    match body {
+        Some(body) => builder.body(Body::from(body.to_string())).unwrap(),
+        None => builder.body(Body::empty()).unwrap(),
+    }
+}
+
+async fn test_pool() -> PgPool {
+    dotenv().ok();
+    let url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set for tests");
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect(&url)
+        .await
+        .expect("connect db");
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+    sqlx::query("TRUNCATE client_secrets, transcripts, audio_recordings, sessions, topics RESTART IDENTITY CASCADE")
+        .execute(&pool)
+        .await
+        .unwrap();
+    pool
+}
+
+fn titles(topics: &Value) -> Vec<String> {
+    topics
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|t| t["title"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn private_topics_are_owner_scoped_and_limited() {
+    let pool = test_pool().await;
+    let app = test_app(pool.clone()).await;
+    let owner = Uuid::new_v4();
+    let other = Uuid::new_v4();
+    let title = format!("My Interview {}", Uuid::new_v4());
+    Topic::create(
+        &pool,
+        &NewTopic {
+            title: title.clone(),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+
+    // The same title may exist globally and privately.
+    let resp = app
+        .clone()
+        .oneshot(request(
+            Method::POST,
+            "/api/topics/private".into(),
+            Some(owner),
+            Some(json!({ "title": title, "prompt_hint": "Why do you want this role?" })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let topic = read_json(resp).await;
+    let topic_id = topic["id"].as_str().unwrap().to_string();
+    assert_eq!(topic["owner_id"], owner.to_string());
+
+    let resp = app
+        .clone()
+        .oneshot(request(
+            Method::POST,
+            "/api/topics/private".into(),
+            Some(owner),
+            Some(json!({ "title": title })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::CONFLICT);
+
+    let resp = app
+        .clone()
+        .oneshot(request(Method::GET, "/api/topics".into(), Some(owner), None))
+        .await
+        .unwrap();
+    assert_eq!(resp.headers()["x-total-count"], "2");
+    assert_eq!(titles(&read_json(resp).await).len(), 2);
+
+    for viewer in [Some(other), None] {
+        let resp = app
+            .clone()
+            .oneshot(request(Method::GET, "/api/topics".into(), viewer, None))
+            .await
+            .unwrap();
+        assert_eq!(resp.headers()["x-total-count"], "1");
+    }
+
+    let resp = app
+        .clone()
+        .oneshot(request(
+            Method::PATCH,
+            format!("/api/topics/private/{topic_id}"),
+            Some(other),
+            Some(json!({ "difficulty": "hard" })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    let resp = app
+        .clone()
+        .oneshot(request(
+            Method::PATCH,
+            format!("/api/topics/private/{topic_id}"),
+            Some(owner),
+            Some(json!({ "difficulty": "hard" })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(read_json(resp).await["difficulty"], "hard");
+
+    let resp = app
+        .clone()
+        .oneshot(request(
+            Method::POST,
+            "/api/sessions".into(),
+            Some(other),
+            Some(json!({ "topic_id": topic_id })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    let resp = app
+        .clone()
+        .oneshot(request(
+            Method::POST,
+            "/api/sessions".into(),
+            Some(owner),
+            Some(json!({ "topic_id": topic_id })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::CREATED);
+
+    // A topic with sessions is archived rather than deleted.
+    let resp = app
+        .clone()
+        .oneshot(request(
+            Method::DELETE,
+            format!("/api/topics/private/{topic_id}"),
+            Some(owner),
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+    let id = Uuid::parse_str(&topic_id).unwrap();
+    let archived = Topic::get(&pool, id).await.unwrap().unwrap();
+    assert!(archived.archived_at.is_some());
+
+    let resp = app
+        .clone()
+        .oneshot(request(Method::GET, "/api/topics/private".into(), Some(owner), None))
+        .await
+        .unwrap();
+    assert!(titles(&read_json(resp).await).is_empty());
+
+    // Kept in the same test: a parallel test's TRUNCATE would race the counts above.
+    let owner = Uuid::new_v4();
+    for idx in 0..MAX_PRIVATE_TOPICS_PER_USER {
+        create_private_topic(
+            &pool,
+            owner,
+            &NewTopic {
+                title: format!("Prompt {idx}"),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    }
+    let err = create_private_topic(
+        &pool,
+        owner,
+        &NewTopic {
+            title: "One too many".into(),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(err, TopicError::LimitReached(_)));
+
+    let unused = Topic::list_owned(&pool, owner).await.unwrap();
+    backend::services::topics::delete_private_topic(&pool, owner, unused[0].id)
+        .await
+        .unwrap();
+    assert!(Topic::get(&pool, unused[0].id).await.unwrap().is_none());
+
+    // Concurrent creates at the last free slot: only one may win.
+    let racer = Uuid::new_v4();
+    for idx in 1..MAX_PRIVATE_TOPICS_PER_USER {
+        create_private_topic(
+            &pool,
+            racer,
+            &NewTopic {
+                title: format!("Prompt {idx}"),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    }
+    let first = NewTopic {
+        title: "Race A".into(),
+        ..Default::default()
+    };
+    let second = NewTopic {
+        title: "Race B".into(),
+        ..Default::default()
+    };
+    let (a, b) = tokio::join!(
+        create_private_topic(&pool, racer, &first),
+        create_private_topic(&pool, racer, &second)
+    );
+    assert_eq!([a.is_ok(), b.is_ok()].iter().filter(|ok| **ok).count(), 1);
+    assert_eq!(Topic::count_owned(&pool, racer).await.unwrap(), MAX_PRIVATE_TOPICS_PER_USER);
+}
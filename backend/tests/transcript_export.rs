@@ -0,0 +1,88 @@
+use backend::models::transcript::TranscriptSegment;
+use backend::services::transcript_export::{
+    content_disposition, export_filename, format_srt_timestamp, format_vtt_timestamp, render, render_srt, render_vtt,
+    wrap_caption_lines, ExportFormat, TranscriptDocument, CAPTION_LINE_WIDTH,
+};
+use chrono::{TimeZone, Utc};
+
+fn segment(speaker: &str, text: &str, start_ms: i64, end_ms: i64) -> TranscriptSegment {
+    TranscriptSegment {
+        speaker: speaker.into(),
+        text: text.into(),
+        start_ms,
+        end_ms,
+        words: None,
+    }
+}
+
+#[test]
+fn formats_caption_timestamps() {
+    assert_eq!(format_srt_timestamp(3_723_045), "01:02:03,045");
+    assert_eq!(format_vtt_timestamp(3_723_045), "01:02:03.045");
+    assert_eq!(format_srt_timestamp(-5), "00:00:00,000");
+}
+
+#[test]
+fn wraps_long_captions_into_multiple_cues() {
+    let text = "This is a fairly long answer that will certainly not fit on a single caption line and needs several cues";
+    let lines = wrap_caption_lines(text, CAPTION_LINE_WIDTH);
+    assert!(lines.len() > 2);
+    assert!(lines.iter().all(|l| l.chars().count() <= CAPTION_LINE_WIDTH));
+
+    let srt = render_srt(&[segment("user", text, 0, 9000)]);
+    assert!(srt.starts_with("1\n00:00:00,000 --> "));
+    assert!(srt.contains("\n2\n"));
+    assert!(srt.contains("You: This is"));
+    assert!(srt.trim_end().ends_with("cues"));
+    assert!(srt.contains(" --> 00:00:09,000\n"));
+}
+
+#[test]
+fn renders_vtt_with_voice_tags() {
+    let vtt = render_vtt(&[
+        segment("ai", "What did you do today?", 0, 1500),
+        segment("user", "I practised speaking.", 1600, 3000),
+    ]);
+    assert!(vtt.starts_with("WEBVTT\n\n"));
+    assert!(vtt.contains("00:00:00.000 --> 00:00:01.500\n<v Coach>What did you do today?"));
+    assert!(vtt.contains("00:00:01.600 --> 00:00:03.000\n<v You>I practised speaking."));
+}
+
+#[test]
+fn renders_markdown_text_and_filenames() {
+    let start = Utc.with_ymd_and_hms(2024, 5, 17, 9, 30, 0).unwrap();
+    let segments = vec![segment("user", "Hello there", 61_000, 62_000)];
+    let doc = TranscriptDocument {
+        topic_title: "Tell a Memorable Trip Story!",
+        start_time: start,
+        segments: &segments,
+    };
+
+    let md = render(ExportFormat::Md, &doc).unwrap();
+    assert!(md.starts_with("# Tell a Memorable Trip Story!\n"));
+    assert!(md.contains("**You** `01:01`: Hello there"));
+
+    let txt = render(ExportFormat::Txt, &doc).unwrap();
+    assert_eq!(txt, "[01:01] You: Hello there\n");
+
+    let json: serde_json::Value =
+        serde_json::from_str(&render(ExportFormat::Json, &doc).unwrap()).unwrap();
+    assert_eq!(json["segments"][0]["text"], "Hello there");
+
+    assert_eq!(
+        export_filename(doc.topic_title, start, ExportFormat::Srt),
+        "tell-a-memorable-trip-story-2024-05-17.srt"
+    );
+    assert_eq!(
+        content_disposition("Путешествие мечты", start, ExportFormat::Vtt),
+        "attachment; filename=\"session-2024-05-17.vtt\"; filename*=UTF-8''%D0%BF%D1%83%D1%82%D0%B5%D1%88%D0%B5%D1%81%D1%82%D0%B2%D0%B8%D0%B5-%D0%BC%D0%B5%D1%87%D1%82%D1%8B-2024-05-17.vtt"
+    );
+}
+
+#[test]
+fn escapes_vtt_cue_text() {
+    let vtt = render_vtt(&[segment("user", "a --> b\n\nfish & <chips>", 0, 1500)]);
+    assert!(vtt.contains("<v You>a --&gt; b fish &amp; &lt;chips&gt;\n\n"));
+    assert_eq!(vtt.matches(" --> ").count(), 1);
+    assert_eq!(vtt.matches("\n\n").count(), 2);
+}
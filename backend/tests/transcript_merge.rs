@@ -0,0 +1,110 @@
+use backend::models::transcript::TranscriptSegment;
+use backend::services::transcript_merge::{
+    merge_transcripts, shift_segments, split_sentences, MergeOptions,
+};
+
+fn segment(speaker: &str, text: &str, start_ms: i64, end_ms: i64) -> TranscriptSegment {
+    TranscriptSegment {
+        speaker: speaker.into(),
+        text: text.into(),
+        start_ms,
+        end_ms,
+        words: None,
+    }
+}
+
+#[test]
+fn interleaves_coach_turns_with_user_segments() {
+    let mut user = vec![
+        segment("user", "I live in Osaka.", 2_000, 4_000),
+        segment("user", "I work as a nurse.", 8_000, 10_000),
+    ];
+    shift_segments(&mut user, 500);
+    let coach = vec![
+        segment("assistant", "Where do you live?", 0, 2_000),
+        segment("assistant", "And what do you do?", 5_000, 7_000),
+    ];
+
+    let merged = merge_transcripts(&user, &coach, &MergeOptions::default());
+    let speakers: Vec<&str> = merged.iter().map(|s| s.speaker.as_str()).collect();
+    assert_eq!(speakers, vec!["ai", "user", "ai", "user"]);
+    assert_eq!(merged[1].start_ms, 2_500);
+    assert_eq!(merged[3].end_ms, 10_500);
+}
+
+#[test]
+fn resolves_overlapping_turns() {
+    let user = vec![segment("user", "Well, I think so", 1_000, 4_000)];
+    let coach = vec![
+        segment("ai", "Tell me more", 0, 2_000),
+        segment("ai", "Sure", 1_100, 1_600),
+    ];
+
+    let merged = merge_transcripts(&user, &coach, &MergeOptions::default());
+    assert_eq!(merged.len(), 3);
+    for pair in merged.windows(2) {
+        assert!(pair[0].end_ms <= pair[1].start_ms, "{:?}", pair);
+        assert!(pair[0].start_ms <= pair[0].end_ms);
+    }
+    // the coach's interruption is trimmed, the short overlap pushes the later turn back
+    assert_eq!(merged[0].end_ms, 1_000);
+    assert_eq!(merged[2].start_ms, 4_000);
+    assert_eq!(merged[2].end_ms, 4_500);
+}
+
+#[test]
+fn retried_finalize_does_not_duplicate_coach_turns() {
+    let primary = vec![
+        segment("assistant", "Good morning!", 0, 1_000),
+        segment("user", "I think so", 3_000, 6_000),
+    ];
+    let coach = vec![
+        segment("ai", "Good  morning!", 0, 1_000),
+        segment("ai", "Tell me more", 2_000, 4_000),
+        segment(
+            "ai",
+            "Tell me about your commute. How long does it take? Do you enjoy it?",
+            10_000,
+            40_000,
+        ),
+    ];
+
+    let first = merge_transcripts(&primary, &coach, &MergeOptions::default());
+    assert!(first.iter().all(|s| s.speaker != "assistant"));
+    assert_eq!(first.iter().filter(|s| s.text == "Good morning!").count(), 1);
+    // trimmed by the user's answer and split at sentences
+    assert_eq!(first[1].end_ms, 3_000);
+    assert_eq!(first.len(), 6);
+
+    // finalize retries merge the same coach turns into the stored transcript
+    let retried = merge_transcripts(&first, &coach, &MergeOptions::default());
+    assert_eq!(retried, first);
+    assert_eq!(merge_transcripts(&primary, &coach, &MergeOptions::default()), first);
+}
+
+#[test]
+fn splits_long_segments_at_sentence_boundaries() {
+    let text = "First I went to the station. Then I took a train to Kyoto! It was crowded. Finally I arrived?";
+    let user = vec![segment("user", text, 0, 40_000)];
+
+    let merged = merge_transcripts(&user, &[], &MergeOptions::default());
+    assert!(merged.len() > 1);
+    assert_eq!(merged[0].start_ms, 0);
+    assert_eq!(merged.last().unwrap().end_ms, 40_000);
+    assert!(merged.iter().all(|s| s.end_ms - s.start_ms <= 15_000));
+    assert!(merged[0].text.starts_with("First I went"));
+    let rejoined: Vec<String> = merged.iter().map(|s| s.text.clone()).collect();
+    assert_eq!(rejoined.join(" "), text);
+
+    // merging is idempotent so re-finalizing does not rewrite the transcript
+    let again = merge_transcripts(&merged, &[], &MergeOptions::default());
+    assert_eq!(again, merged);
+}
+
+#[test]
+fn splits_sentences_with_japanese_punctuation() {
+    assert_eq!(
+        split_sentences("こんにちは。元気ですか？ Yes. ok"),
+        vec!["こんにちは。", "元気ですか？", "Yes.", "ok"]
+    );
+}
@@ -0,0 +1,275 @@
+use axum::body::{self, Body};
+use axum::http::{Method, Request, StatusCode};
+use axum::Router;
+use backend::api;
+use backend::encryption::Encryption;
+use backend::models::transcript::{upsert_transcript, TranscriptSegment};
+use backend::services::storage::StorageService;
+use backend::state::AppState;
+use dotenvy::dotenv;
+use serde_json::{json, Value};
+use sqlx::postgres::PgPoolOptions;
+use sqlx::{PgPool, Row};
+use tower::util::ServiceExt;
+use uuid::Uuid;
+
+async fn test_app(pool: PgPool) -> Router {
+    std::env::set_var("S3_BUCKET", "test-bucket");
+    std::env::set_var("S3_ENDPOINT", "http://localhost:9000");
+    std::env::set_var("S3_ACCESS_KEY", "test");
+    std::env::set_var("S3_SECRET_KEY", "test");
+    let storage = StorageService::from_env().await.expect("storage");
+    let state = AppState::new(pool, storage);
+    api::router(state)
+}
+
+async fn insert_topic(pool: &PgPool) -> Uuid {
+    sqlx::query("INSERT INTO topics (title) VALUES ($1) RETURNING id")
+        .bind(format!("Revision Topic {}", Uuid::new_v4()))
+        .fetch_one(pool)
+        .await
+        .unwrap()
+        .get::<Uuid, _>(0)
+}
+
+async fn insert_session(pool: &PgPool, user: Uuid, topic_id: Uuid) -> Uuid {
+    sqlx::query(
+        r#"
+        INSERT INTO sessions (user_id, topic_id, status)
+        VALUES ($1, $2, 'ended')
+        RETURNING id
+        "#,
+    )
+    .bind(user)
+    .bind(topic_id)
+    .fetch_one(pool)
+    .await
+    .unwrap()
+    .get::<Uuid, _>(0)
+}
+
+fn segment(speaker: &str, text: &str, start_ms: i64) -> TranscriptSegment {
+    TranscriptSegment {
+        speaker: speaker.into(),
+        text: text.into(),
+        start_ms,
+        end_ms: start_ms + 1000,
+        words: None,
+    }
+}
+
+fn json_request(method: Method, uri: String, user: Uuid, if_match: Option<&str>, body: Value) -> Request<Body> {
+    let mut builder = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("content-type", "application/json")
+        .header("x-user-id", user.to_string());
+    if let Some(etag) = if_match {
+        builder = builder.header("if-match", etag);
+    }
+    builder.body(Body::from(body.to_string())).unwrap()
+}
+
+async fn read_json(res: axum::response::Response) -> Value {
+    let bytes = body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    serde_json::from_slice(&bytes).unwrap()
+}
+
+async fn test_pool() -> PgPool {
+    dotenv().ok();
+    let url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set for tests");
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect(&url)
+        .await
+        .expect("connect db");
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+    sqlx::query("TRUNCATE client_secrets, transcripts, audio_recordings, sessions, topics RESTART IDENTITY CASCADE")
+        .execute(&pool)
+        .await
+        .unwrap();
+    pool
+}
+
+#[tokio::test]
+async fn edits_are_versioned_and_restorable() {
+    let pool = test_pool().await;
+    let topic_id = insert_topic(&pool).await;
+    let user = Uuid::new_v4();
+    let session_id = insert_session(&pool, user, topic_id).await;
+    upsert_transcript(
+        &pool,
+        &Encryption::disabled(),
+        session_id,
+        true,
+        &[
+            segment("ai", "How was your weekend?", 0),
+            segment("user", "I went to Tokio", 1500),
+        ],
+    )
+    .await
+    .unwrap();
+
+    let app = test_app(pool.clone()).await;
+    let uri = format!("/api/sessions/{session_id}/transcript");
+
+    // reads hand out the revision to edit against
+    for read_uri in [format!("/api/sessions/{session_id}"), uri.clone()] {
+        let resp = app
+            .clone()
+            .oneshot(json_request(Method::GET, read_uri, user, None, Value::Null))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.headers()["etag"], "\"0\"");
+    }
+
+    // edits without a base revision are rejected
+    let resp = app
+        .clone()
+        .oneshot(json_request(
+            Method::PATCH,
+            uri.clone(),
+            user,
+            None,
+            json!({ "edits": [{ "index": 1, "text": "I went to Tokyo" }] }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::PRECONDITION_REQUIRED);
+
+    let resp = app
+        .clone()
+        .oneshot(json_request(
+            Method::PATCH,
+            uri.clone(),
+            user,
+            Some("\"0\""),
+            json!({ "edits": [{ "index": 1, "text": "I went to Tokyo" }] }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers()["etag"], "\"1\"");
+    let body = read_json(resp).await;
+    assert_eq!(body["revision"], 1);
+    assert_eq!(body["segments"][1]["text"], "I went to Tokyo");
+
+    // a stale revision conflicts
+    let resp = app
+        .clone()
+        .oneshot(json_request(
+            Method::PATCH,
+            uri.clone(),
+            user,
+            None,
+            json!({ "base_revision": 0, "edits": [{ "index": 0, "speaker": "coach" }] }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::CONFLICT);
+
+    let resp = app
+        .clone()
+        .oneshot(json_request(
+            Method::GET,
+            format!("{uri}/diff?from=0"),
+            user,
+            None,
+            Value::Null,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let diff = read_json(resp).await;
+    assert_eq!(diff["to"], 1);
+    let changes = diff["changes"].as_array().unwrap();
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0]["before"]["text"], "I went to Tokio");
+    assert_eq!(changes[0]["after"]["text"], "I went to Tokyo");
+
+    let resp = app
+        .clone()
+        .oneshot(json_request(
+            Method::POST,
+            format!("{uri}/revisions/0/restore"),
+            user,
+            Some("\"1\""),
+            Value::Null,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let restored = read_json(resp).await;
+    assert_eq!(restored["revision"], 2);
+    assert_eq!(restored["segments"][1]["text"], "I went to Tokio");
+
+    let resp = app
+        .clone()
+        .oneshot(json_request(
+            Method::GET,
+            format!("{uri}/revisions"),
+            user,
+            None,
+            Value::Null,
+        ))
+        .await
+        .unwrap();
+    let history = read_json(resp).await;
+    assert_eq!(history["current_revision"], 2);
+    let revisions = history["revisions"].as_array().unwrap();
+    assert_eq!(revisions.len(), 3);
+    assert!(revisions[0]["author_id"].is_null());
+    assert_eq!(revisions[1]["author_id"].as_str(), Some(user.to_string().as_str()));
+    assert_eq!(revisions[2]["restored_from"], 0);
+
+    // the machine transcript stays untouched as revision 0
+    let original: (Value,) =
+        sqlx::query_as("SELECT original_segments FROM transcripts WHERE session_id = $1")
+            .bind(session_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(original.0[1]["text"], "I went to Tokio");
+}
+
+#[tokio::test]
+async fn finalizing_after_an_edit_keeps_the_original_transcript() {
+    let pool = test_pool().await;
+    let topic_id = insert_topic(&pool).await;
+    let user = Uuid::new_v4();
+    let session_id = insert_session(&pool, user, topic_id).await;
+    upsert_transcript(&pool, &Encryption::disabled(), session_id, false, &[segment("user", "draft from realtime", 0)])
+        .await
+        .unwrap();
+
+    let app = test_app(pool.clone()).await;
+    let uri = format!("/api/sessions/{session_id}/transcript");
+    let resp = app
+        .clone()
+        .oneshot(json_request(
+            Method::PATCH,
+            uri.clone(),
+            user,
+            Some("\"0\""),
+            json!({ "edits": [{ "index": 0, "text": "draft, corrected" }] }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    upsert_transcript(&pool, &Encryption::disabled(), session_id, true, &[segment("user", "final asr output", 0)])
+        .await
+        .unwrap();
+
+    let resp = app
+        .clone()
+        .oneshot(json_request(Method::GET, format!("{uri}/diff?from=0"), user, None, Value::Null))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let diff = read_json(resp).await;
+    assert_eq!(diff["to"], 1);
+    assert_eq!(diff["changes"][0]["before"]["text"], "draft from realtime");
+    assert_eq!(diff["changes"][0]["after"]["text"], "draft, corrected");
+}
@@ -0,0 +1,100 @@
+use backend::services::transcription::parse_verbose_response;
+use serde_json::json;
+
+#[test]
+fn attaches_words_with_confidence_to_segments() {
+    let raw = json!({
+        "text": "Hello there. I am fine.",
+        "segments": [
+            { "start": 0.0, "end": 1.2, "text": " Hello there.", "avg_logprob": -0.05 },
+            { "start": 1.2, "end": 2.5, "text": " I am fine.", "avg_logprob": -1.2 }
+        ],
+        "words": [
+            { "word": "Hello", "start": 0.0, "end": 0.5 },
+            { "word": "there", "start": 0.5, "end": 1.1 },
+            { "word": "I", "start": 1.3, "end": 1.4 },
+            { "word": "am", "start": 1.4, "end": 1.7, "probability": 0.97 },
+            { "word": "fine", "start": 1.8, "end": 2.6 }
+        ]
+    })
+    .to_string();
+
+    let segments = parse_verbose_response(raw.as_bytes(), None).unwrap();
+    assert_eq!(segments.len(), 2);
+
+    let first = segments[0].words.as_ref().unwrap();
+    assert_eq!(first.len(), 2);
+    assert_eq!(first[1].word, "there");
+    assert_eq!((first[1].start_ms, first[1].end_ms), (500, 1100));
+    assert!(!first[0].low_confidence);
+
+    let second = segments[1].words.as_ref().unwrap();
+    assert_eq!(second.len(), 3);
+    // segment confidence exp(-1.2) ~ 0.3 flags words without their own probability
+    assert!(second[0].low_confidence);
+    assert_eq!(second[1].confidence, Some(0.97));
+    assert!(!second[1].low_confidence);
+    assert_eq!(second[2].end_ms, 2600);
+}
+
+#[test]
+fn keeps_words_outside_segment_bounds() {
+    let raw = json!({
+        "text": "Um, hello. Yes.",
+        "segments": [
+            { "start": 1.0, "end": 2.0, "text": " hello." },
+            { "start": 5.0, "end": 6.0, "text": " Yes." }
+        ],
+        "words": [
+            { "word": "Um", "start": 0.2, "end": 0.6 },
+            { "word": "hello", "start": 1.1, "end": 1.6 },
+            { "word": "so", "start": 2.4, "end": 2.6 },
+            { "word": "well", "start": 4.5, "end": 4.8 },
+            { "word": "Yes", "start": 5.1, "end": 5.5 }
+        ]
+    })
+    .to_string();
+
+    let segments = parse_verbose_response(raw.as_bytes(), None).unwrap();
+    let words: Vec<Vec<&str>> = segments
+        .iter()
+        .map(|s| s.words.as_ref().unwrap().iter().map(|w| w.word.as_str()).collect())
+        .collect();
+    assert_eq!(words, vec![vec!["Um", "hello", "so"], vec!["well", "Yes"]]);
+}
+
+#[test]
+fn falls_back_to_single_segment_without_segments() {
+    let raw = json!({ "text": "Just text" }).to_string();
+    let segments = parse_verbose_response(raw.as_bytes(), Some(4)).unwrap();
+    assert_eq!(segments.len(), 1);
+    assert_eq!(segments[0].end_ms, 4000);
+    assert!(segments[0].words.is_none());
+
+    let serialized = serde_json::to_value(&segments[0]).unwrap();
+    assert!(serialized.get("words").is_none());
+}
+
+#[test]
+fn groups_words_of_long_recordings() {
+    let segments: Vec<_> = (0..5000)
+        .map(|i| json!({ "start": i as f64 * 2.0, "end": i as f64 * 2.0 + 1.5, "text": " one two" }))
+        .collect();
+    let words: Vec<_> = (0..5000)
+        .flat_map(|i| {
+            let start = i as f64 * 2.0;
+            vec![
+                json!({ "word": "one", "start": start, "end": start + 0.5 }),
+                json!({ "word": "two", "start": start + 1.7, "end": start + 1.9 }),
+            ]
+        })
+        .collect();
+    let raw = json!({ "text": "", "segments": segments, "words": words }).to_string();
+
+    let segments = parse_verbose_response(raw.as_bytes(), None).unwrap();
+    assert_eq!(segments.len(), 5000);
+    // "two" sits in the gap closer to its own segment's end than the next one's start.
+    assert!(segments
+        .iter()
+        .all(|s| s.words.as_ref().unwrap().iter().map(|w| w.word.as_str()).eq(["one", "two"])));
+}